@@ -0,0 +1,126 @@
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::sync::Arc;
+
+use smart_road::config::{JunctionKind, SimConfig};
+use smart_road::geometry::RoadGeometry;
+use smart_road::manager::{IntersectionManager, Movement};
+use smart_road::pedestrian::build_crosswalks;
+use smart_road::perception::Sensor;
+use smart_road::simulation::{Simulation, DIRECTIONS, ROUTES};
+use smart_road::spatial::{Neighborhood, SpatialHash};
+use smart_road::vehicle::{build_junction_path, exit_side, Direction, Route, Vehicle};
+
+const DT: f32 = 1.0 / 60.0;
+
+/// `n` cars spread over the approach lanes, somewhere between spawn and the box
+fn approach_traffic(n: usize, rng: &mut StdRng) -> Vec<Vehicle> {
+    let g = Arc::new(RoadGeometry::default());
+    (0..n)
+        .map(|_| {
+            let dir = DIRECTIONS[rng.random_range(0..4)];
+            let route = ROUTES[rng.random_range(0..3)];
+            let lanes = g.spawn_lanes(dir);
+            let lane = lanes[rng.random_range(0..lanes.len())];
+
+            let mut v = Vehicle::new(&g, dir, route, 1, lane, JunctionKind::Cross);
+            let t: f32 = rng.random();
+            let ((x0, y0), (x1, y1)) = (v.path[0], v.path[1]);
+            v.x = x0 + (x1 - x0) * t;
+            v.y = y0 + (y1 - y0) * t;
+            v
+        })
+        .collect()
+}
+
+fn vehicle_update(c: &mut Criterion) {
+    let mut group = c.benchmark_group("vehicle_update");
+    let crosswalks = build_crosswalks(&RoadGeometry::default());
+    let sensor = Sensor::perfect();
+
+    for n in [10, 100, 1_000] {
+        let mut rng = StdRng::seed_from_u64(1);
+        let others = approach_traffic(n, &mut rng);
+        let mut grid = SpatialHash::new();
+        grid.rebuild(&others);
+        let subject = approach_traffic(1, &mut rng).remove(0);
+
+        group.bench_with_input(BenchmarkId::from_parameter(n), &n, |b, _| {
+            b.iter_batched_ref(
+                || subject.clone(),
+                |v| v.update(DT, Neighborhood::new(&others, &grid), &crosswalks, &sensor),
+                BatchSize::SmallInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+fn build_path(c: &mut Criterion) {
+    let mut group = c.benchmark_group("build_path");
+    let g = RoadGeometry::default();
+    let lane = g.spawn_lanes(Direction::Up)[0];
+
+    for junction in [JunctionKind::Cross, JunctionKind::Roundabout] {
+        let id = format!("{:?}", junction);
+        group.bench_function(id, |b| {
+            b.iter(|| {
+                for dir in DIRECTIONS {
+                    for route in [Route::Left, Route::Straight, Route::Right, Route::UTurn] {
+                        black_box(build_junction_path(&g, dir, route, junction, black_box(lane)));
+                    }
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+fn headless_step(c: &mut Criterion) {
+    let mut group = c.benchmark_group("headless_step");
+    group.sample_size(10);
+
+    for n in [100, 1_000, 10_000] {
+        let mut rng = StdRng::seed_from_u64(7);
+        let traffic = approach_traffic(n, &mut rng);
+
+        group.bench_with_input(BenchmarkId::from_parameter(n), &n, |b, _| {
+            b.iter_batched_ref(
+                || {
+                    let mut sim = Simulation::new(SimConfig { seed: Some(7), ..SimConfig::default() });
+                    sim.vehicles = traffic.clone();
+                    sim
+                },
+                |sim| sim.step(DT),
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+fn manager_requests(c: &mut Criterion) {
+    // Every movement from every side, including ones that will be rejected
+    let movements: Vec<Movement> = DIRECTIONS
+        .iter()
+        .flat_map(|&from| {
+            ROUTES.iter().map(move |&route| Movement::new(from.opposite(), exit_side(from, route)))
+        })
+        .collect();
+
+    c.bench_function("manager_requests", |b| {
+        b.iter(|| {
+            let mut manager = IntersectionManager::new();
+            for (vehicle, &movement) in movements.iter().enumerate().cycle().take(1_000) {
+                if manager.request(vehicle, movement) && vehicle % 3 == 0 {
+                    manager.release(vehicle);
+                }
+            }
+            black_box(manager.granted)
+        })
+    });
+}
+
+criterion_group!(benches, vehicle_update, build_path, headless_step, manager_requests);
+criterion_main!(benches);
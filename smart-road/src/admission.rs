@@ -0,0 +1,294 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use crate::config::{JunctionKind, SimConfig};
+use crate::geometry::RoadGeometry;
+use crate::manager::{IntersectionManager, Movement};
+use crate::v2i::{Endpoint, Lease, Message, MessageBus, Payload, HEARTBEAT_INTERVAL};
+use crate::vehicle::{progress_along, Vehicle, VehicleKind, VelocityLevel};
+
+// =======================================================
+// 🛂 JUNCTION ADMISSION (single cross junction, over V2I)
+// =======================================================
+//
+// The cross junction has a reservation manager of its own, and autonomous
+// cars deal with it only through the V2I bus, with the protocol the network
+// junctions use (see v2i.rs): close to the stop line a car sends a Request,
+// holds at the line until a Grant arrives, and sends Cancel once it has
+// left the junction box or let the grant lapse.
+//
+// Human drivers and cyclists carry no radio and never wait for a grant. The
+// controller's own sensors register them while they are first in line at a
+// stop line or inside the box, so requests that conflict with them wait.
+// Roundabouts keep yield-on-entry and have no manager.
+
+const JUNCTION: Endpoint = Endpoint::Junction(0);
+pub const REQUEST_DISTANCE: f32 = 180.0; // Ask the manager this far ahead of the stop line
+const REQUEST_RETRY: f32 = 0.25; // Wait after a reject before asking again
+const REQUEST_TIMEOUT: f32 = 1.0; // Ask again if neither grant nor reject arrived by then
+const GRANT_LEASE: f32 = 5.0; // A grant is only good for entering this long after it was issued
+const CROSSING_SPEED: f32 = 20.0; // Slowest a car crawls through the box
+const HOLD_MARGIN: f32 = 6.0; // Gap kept to the stop line while waiting
+
+/// An autonomous car's side of the exchange with the junction controller
+#[derive(Clone, Copy, Debug, Default)]
+pub struct V2iState {
+    pub requested: bool,       // Request sent, no answer yet
+    pub granted: bool,         // May enter before `grant_until`
+    pub grant_until: f32,
+    pub retry_at: f32,         // No new request before this time
+    pub cancelled_at: f32,     // Last Cancel sent (grants for older requests are void)
+    pub advisory: Option<f32>, // Speed advised while waiting
+    pub last_heartbeat: f32,
+    pub done: bool,            // Crossed and released the junction
+}
+
+impl Vehicle {
+    /// Autonomous car bound for the cross junction: enters only with a grant
+    pub fn talks_v2i(&self) -> bool {
+        self.kind == VehicleKind::Car && self.driver.is_none() && self.junction == JunctionKind::Cross
+    }
+
+    /// Side entered from and side left through
+    pub fn movement(&self) -> Movement {
+        Movement::new(self.direction.opposite(), self.exit.side)
+    }
+
+    /// Speed cap while waiting for a grant: no faster than advised, and a
+    /// stop at the line
+    pub fn admission_speed_limit(&self) -> f32 {
+        if !self.talks_v2i() || self.v2i.granted || self.v2i.done || self.entered_intersection {
+            return f32::MAX;
+        }
+        if self.gap_to_stop_line() < self.braking_distance() + HOLD_MARGIN * 2.0 {
+            0.0
+        } else {
+            self.v2i.advisory.unwrap_or(f32::MAX)
+        }
+    }
+
+    /// Left the junction box behind
+    fn has_crossed(&self) -> bool {
+        self.entered_intersection && !self.is_in_intersection()
+    }
+}
+
+pub struct JunctionController {
+    pub bus: MessageBus,
+    pub manager: IntersectionManager,
+    pub leases: BTreeMap<usize, Lease>, // Reservation holders, by vehicle
+    observed: BTreeSet<usize>,          // Road users without a radio, registered by sight
+    allowance: f32,                     // Slowest crossing of the box (seconds)
+}
+
+impl JunctionController {
+    /// Links follow --latency, --jitter and --packet-loss; the run's seed
+    /// drives their losses
+    pub fn new(config: &SimConfig, g: &RoadGeometry) -> Self {
+        let mut bus = MessageBus::new(config.seed.unwrap_or(0));
+        bus.channel = config.channel;
+        bus.logging = config.v2i_log.is_some();
+
+        let (lo, hi) = g.junction_edges();
+        Self {
+            bus,
+            manager: IntersectionManager::new(),
+            leases: BTreeMap::new(),
+            observed: BTreeSet::new(),
+            allowance: (hi - lo + 2.0 * g.lane_width as f32) / CROSSING_SPEED,
+        }
+    }
+
+    /// One exchange before the vehicles move: deliver last step's messages,
+    /// expire leases of silent holders, register road users without a
+    /// radio, then let every autonomous car ask, heartbeat or release
+    pub fn update(&mut self, now: f32, vehicles: &mut [Vehicle]) {
+        let at: HashMap<usize, usize> = vehicles.iter().enumerate().map(|(i, v)| (v.id, i)).collect();
+
+        for message in self.bus.deliver(now) {
+            match message.to {
+                Endpoint::Junction(_) => self.junction_receives(now, message),
+                Endpoint::Vehicle(id) => {
+                    if let Some(&i) = at.get(&id) {
+                        self.vehicle_receives(now, &mut vehicles[i], message);
+                    }
+                }
+            }
+        }
+
+        let expired: Vec<usize> = self
+            .leases
+            .iter()
+            .filter(|(_, lease)| lease.expired(now, self.allowance))
+            .map(|(&id, _)| id)
+            .collect();
+        for id in expired {
+            self.manager.release(id);
+            self.leases.remove(&id);
+        }
+
+        // First in line: nobody ahead, or only someone already let in
+        let first_in_line: Vec<bool> = vehicles
+            .iter()
+            .map(|v| {
+                let leader = v.leader.and_then(|id| at.get(&id)).map(|&j| &vehicles[j]);
+                leader.is_none_or(|l| l.entered_intersection || l.v2i.granted)
+            })
+            .collect();
+
+        self.watch(vehicles, &first_in_line);
+        for (v, first) in vehicles.iter_mut().zip(first_in_line) {
+            if v.talks_v2i() {
+                self.drive(now, v, first);
+            }
+        }
+    }
+
+    /// Grants and message counts for the end-of-run report (nothing if no
+    /// car ever talked to the junction, e.g. at a roundabout)
+    pub fn print_summary(&self) {
+        if self.bus.sent.iter().all(|&n| n == 0) {
+            return;
+        }
+        println!("\n🛂 Junction manager: granted {}, rejected {}", self.manager.granted, self.manager.rejected);
+        self.bus.print_summary();
+    }
+
+    /// A vehicle is about to be removed: give back whatever it still holds
+    pub fn forget(&mut self, now: f32, v: &Vehicle) {
+        if v.talks_v2i() && !v.v2i.done && (v.v2i.granted || v.v2i.requested) {
+            self.bus.send(now, Endpoint::Vehicle(v.id), JUNCTION, Payload::Cancel);
+        }
+    }
+
+    /// Whoever the sensors see committed to the junction without a radio
+    /// holds their movement until they leave the box
+    fn watch(&mut self, vehicles: &[Vehicle], first_in_line: &[bool]) {
+        let seen: BTreeSet<usize> = vehicles
+            .iter()
+            .zip(first_in_line)
+            .filter(|(v, &first)| {
+                let unequipped = v.kind == VehicleKind::Car && v.driver.is_some() && v.junction == JunctionKind::Cross;
+                let waiting = first && !v.entered_intersection && v.gap_to_stop_line() < REQUEST_DISTANCE;
+                unequipped && !v.has_crossed() && (waiting || v.is_in_intersection())
+            })
+            .map(|(v, _)| v.id)
+            .collect();
+
+        for id in self.observed.difference(&seen) {
+            self.manager.release(*id);
+        }
+        for v in vehicles.iter().filter(|v| seen.contains(&v.id)) {
+            self.manager.observe(v.id, v.movement());
+        }
+        self.observed = seen;
+    }
+
+    /// Vehicle side: release after crossing, give up a lapsed grant, ask once
+    /// close and first in line, and keep the controller posted
+    fn drive(&mut self, now: f32, v: &mut Vehicle, first_in_line: bool) {
+        if v.v2i.done {
+            return;
+        }
+        let me = Endpoint::Vehicle(v.id);
+
+        // 🏁 Out of the box: the junction is free again
+        if v.has_crossed() {
+            v.v2i = V2iState { done: true, cancelled_at: now, ..V2iState::default() };
+            self.bus.send(now, me, JUNCTION, Payload::Cancel);
+            return;
+        }
+
+        // ⌛ A grant not used in time lapses, unless it is too late to stop
+        let can_stop = v.can_stop_at_signal();
+        let near = v.gap_to_stop_line() < REQUEST_DISTANCE;
+        let (movement, red, entered) = (v.movement(), v.red_light, v.entered_intersection);
+        let pos = progress_along(v.direction, v.x, v.y);
+        let speed = v.speed;
+        let s = &mut v.v2i;
+        if s.granted && now > s.grant_until && can_stop {
+            s.granted = false;
+            s.retry_at = now + REQUEST_RETRY;
+            s.cancelled_at = now;
+            self.bus.send(now, me, JUNCTION, Payload::Cancel);
+        }
+
+        // 🚦 Ask (again if the answer got lost), never on red or behind a car
+        // still waiting: a grant held behind it would block the junction
+        if !s.granted && !entered && near && first_in_line && !red && now >= s.retry_at {
+            self.bus.send(now, me, JUNCTION, Payload::Request(movement));
+            s.requested = true;
+            s.retry_at = now + REQUEST_TIMEOUT;
+        }
+
+        if (s.granted || s.requested || entered) && now - s.last_heartbeat >= HEARTBEAT_INTERVAL {
+            self.bus.send(now, me, JUNCTION, Payload::Heartbeat { pos, speed });
+            s.last_heartbeat = now;
+        }
+    }
+
+    /// Controller side: grant or reject requests, release cancelled reservations
+    fn junction_receives(&mut self, now: f32, message: Message) {
+        let Endpoint::Vehicle(id) = message.from else {
+            return;
+        };
+
+        match message.payload {
+            Payload::Request(movement) => {
+                if self.manager.request(id, movement) {
+                    let until = now + GRANT_LEASE;
+                    let asked = self.leases.get(&id).map_or(message.sent, |l| l.asked.max(message.sent));
+                    self.leases.insert(id, Lease { asked, until, heard: now });
+                    self.bus.send(now, JUNCTION, message.from, Payload::Grant { until, asked: message.sent });
+                } else {
+                    let advised = VelocityLevel::Slow.to_speed();
+                    self.bus.send(now, JUNCTION, message.from, Payload::Reject);
+                    self.bus.send(now, JUNCTION, message.from, Payload::SpeedAdvisory(advised));
+                }
+            }
+            // A cancel overtaken by a newer request is stale
+            Payload::Cancel => {
+                if self.leases.get(&id).is_none_or(|l| message.sent >= l.asked) {
+                    self.manager.release(id);
+                    self.leases.remove(&id);
+                }
+            }
+            Payload::Heartbeat { .. } => {
+                if let Some(lease) = self.leases.get_mut(&id) {
+                    lease.heard = now;
+                }
+            }
+            Payload::Grant { .. } | Payload::Reject | Payload::SpeedAdvisory(_) => {}
+        }
+    }
+
+    /// Vehicle side: answers may be duplicated, reordered or come too late
+    fn vehicle_receives(&mut self, now: f32, v: &mut Vehicle, message: Message) {
+        let me = Endpoint::Vehicle(v.id);
+        let s = &mut v.v2i;
+        let waiting = !s.granted && !s.done;
+
+        match message.payload {
+            // Answers a request sent before our last Cancel, which voids it
+            Payload::Grant { asked, .. } if asked <= s.cancelled_at => {}
+            Payload::Grant { until, .. } if waiting => {
+                s.requested = false;
+                if now <= until {
+                    s.granted = true;
+                    s.grant_until = until;
+                    s.advisory = None;
+                } else {
+                    s.retry_at = now + REQUEST_RETRY;
+                    s.cancelled_at = now;
+                    self.bus.send(now, me, JUNCTION, Payload::Cancel);
+                }
+            }
+            Payload::Reject if waiting => {
+                s.requested = false;
+                s.retry_at = now + REQUEST_RETRY;
+            }
+            Payload::SpeedAdvisory(speed) if waiting => s.advisory = Some(speed),
+            // Duplicates of the grant in hand, late rejects and advisories
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,587 @@
+use std::io::{self, ErrorKind, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+
+use rand::Rng;
+use serde::{Serialize, Serializer};
+use serde_json::{Map, Value};
+
+use crate::config::JunctionKind;
+use crate::driver::Autonomy;
+use crate::network::{NetState, RoadNetwork};
+use crate::simulation::{Command, Simulation, STEP};
+use crate::stats::Stats;
+use crate::vehicle::{Direction, Route, VehicleKind, VelocityLevel};
+
+// =======================================================
+// 🔌 CONTROL API (line-delimited JSON over TCP)
+// =======================================================
+//
+// --api PORT listens on localhost. Clients send one JSON object per line and
+// get one back per line: {"type":"ok","cmd":...} or {"type":"error",...}.
+//
+//     {"cmd":"spawn","dir":"up","route":"left"}   route and dir optional
+//     {"cmd":"spawn_pedestrian"}  {"cmd":"spawn_bicycle"}
+//     {"cmd":"pause"}  {"cmd":"resume"}  {"cmd":"step","n":10}
+//     {"cmd":"speed","level":"slow|medium|fast"}
+//     {"cmd":"auto_spawn","on":true}
+//     {"cmd":"policy","junction":"cross|roundabout"}
+//     {"cmd":"stream","every":6}   tick messages every N steps (0: off)
+//     {"cmd":"quit"}
+//
+// Every client is sent a tick after each step (until it asks otherwise):
+//     {"type":"tick","step":..,"time":..,"vehicles":[..],"stats":{..}}
+//
+// Single-junction inputs go through `Simulation::apply`, so a recorded run
+// replays API sessions like keyboard ones. The socket is polled from the
+// event loop: no threads, and commands land between whole steps. Output is
+// queued per client and written as fast as it reads; a client that falls
+// more than MAX_OUTGOING behind, or sends a line longer than MAX_PENDING,
+// is dropped.
+
+pub const MAX_OUTGOING: usize = 8 << 20; // Bytes of unread output a client may pile up
+pub const MAX_PENDING: usize = 64 << 10; // Bytes of an unfinished input line
+
+/// A parsed client command
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Request {
+    Spawn { dir: Option<Direction>, route: Option<Route> },
+    SpawnPedestrian,
+    SpawnBicycle,
+    Pause,
+    Resume,
+    Step(u64),
+    Speed(VelocityLevel),
+    AutoSpawn(bool),
+    Policy(JunctionKind),
+    Stream(u64),
+    Quit,
+}
+
+impl Request {
+    /// The "cmd" this request is sent as
+    pub fn name(&self) -> &'static str {
+        match self {
+            Request::Spawn { .. } => "spawn",
+            Request::SpawnPedestrian => "spawn_pedestrian",
+            Request::SpawnBicycle => "spawn_bicycle",
+            Request::Pause => "pause",
+            Request::Resume => "resume",
+            Request::Step(_) => "step",
+            Request::Speed(_) => "speed",
+            Request::AutoSpawn(_) => "auto_spawn",
+            Request::Policy(_) => "policy",
+            Request::Stream(_) => "stream",
+            Request::Quit => "quit",
+        }
+    }
+}
+
+/// Answer to one request line
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum Reply {
+    Ok { cmd: &'static str },
+    Error { message: String },
+}
+
+/// Pausing, single-stepping and quitting, as asked for over the API
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RunControl {
+    pub paused: bool,
+    pub pending_steps: u64, // Steps asked for while paused
+    pub quit: bool,
+}
+
+impl RunControl {
+    /// Whether the loop may take another step now (uses up a pending step)
+    pub fn take_step(&mut self) -> bool {
+        if !self.paused {
+            return true;
+        }
+        if self.pending_steps > 0 {
+            self.pending_steps -= 1;
+            return true;
+        }
+        false
+    }
+
+    /// Requests about the run itself; false if `request` is for the simulation
+    fn handle(&mut self, request: Request) -> bool {
+        match request {
+            Request::Pause => self.paused = true,
+            Request::Resume => self.paused = false,
+            Request::Step(n) => self.pending_steps += n,
+            Request::Quit => self.quit = true,
+            _ => return false,
+        }
+        true
+    }
+}
+
+struct Client {
+    stream: TcpStream,
+    pending: Vec<u8>,  // Received, not yet a whole line (at most MAX_PENDING)
+    outgoing: Vec<u8>, // Queued lines the socket has not taken yet
+    every: u64,        // Tick every N steps (0: no ticks)
+    gone: bool,
+}
+
+impl Client {
+    fn send(&mut self, line: &str) {
+        self.outgoing.extend_from_slice(line.as_bytes());
+        self.outgoing.push(b'\n');
+        if self.outgoing.len() > MAX_OUTGOING {
+            self.gone = true; // Not reading: stop buffering for it
+        }
+    }
+
+    /// Write as much queued output as the socket accepts without blocking
+    fn flush(&mut self) {
+        while !self.outgoing.is_empty() && !self.gone {
+            match self.stream.write(&self.outgoing) {
+                Ok(0) => self.gone = true,
+                Ok(n) => {
+                    self.outgoing.drain(..n);
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(_) => self.gone = true,
+            }
+        }
+    }
+}
+
+pub struct ApiServer {
+    listener: TcpListener,
+    clients: Vec<Client>,
+}
+
+impl ApiServer {
+    pub fn bind(addr: impl ToSocketAddrs) -> io::Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        listener.set_nonblocking(true)?;
+        Ok(Self { listener, clients: Vec::new() })
+    }
+
+    /// --api PORT: localhost only
+    pub fn on_port(port: u16) -> io::Result<Self> {
+        Self::bind(("127.0.0.1", port))
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    pub fn client_count(&self) -> usize {
+        self.clients.len()
+    }
+
+    /// Accept new clients and hand every complete line to `handle`, answering
+    /// each client with the outcome
+    pub fn serve(&mut self, mut handle: impl FnMut(Request) -> Result<(), String>) {
+        while let Ok((stream, _)) = self.listener.accept() {
+            if stream.set_nonblocking(true).is_ok() {
+                self.clients.push(Client { stream, pending: Vec::new(), outgoing: Vec::new(), every: 1, gone: false });
+            }
+        }
+
+        for client in &mut self.clients {
+            let mut buf = [0u8; 4096];
+            loop {
+                match client.stream.read(&mut buf) {
+                    Ok(0) => {
+                        client.gone = true;
+                        break;
+                    }
+                    Ok(n) => {
+                        client.pending.extend_from_slice(&buf[..n]);
+                        if client.pending.len() >= MAX_PENDING {
+                            break; // Handle what we have before reading more
+                        }
+                    }
+                    Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                    Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                    Err(_) => {
+                        client.gone = true;
+                        break;
+                    }
+                }
+            }
+
+            while let Some(end) = client.pending.iter().position(|&b| b == b'\n') {
+                let line: Vec<u8> = client.pending.drain(..=end).collect();
+                let line = String::from_utf8_lossy(&line);
+                let line = line.trim();
+                if line.is_empty() {
+                    continue;
+                }
+
+                let outcome = parse_request(line).and_then(|request| {
+                    if let Request::Stream(every) = request {
+                        client.every = every;
+                    } else {
+                        handle(request)?;
+                    }
+                    Ok(request.name())
+                });
+                let reply = match outcome {
+                    Ok(cmd) => Reply::Ok { cmd },
+                    Err(message) => Reply::Error { message },
+                };
+                client.send(&serde_json::to_string(&reply).unwrap());
+            }
+            if client.pending.len() >= MAX_PENDING {
+                client.gone = true; // A line that long is not a command
+            }
+            client.flush();
+        }
+        self.clients.retain(|c| !c.gone);
+    }
+
+    /// Send a tick to the clients due one at `step`; the message is only
+    /// built if someone wants it
+    pub fn tick(&mut self, step: u64, message: impl FnOnce() -> String) {
+        let mut due = self.clients.iter_mut().filter(|c| c.every > 0 && step.is_multiple_of(c.every)).peekable();
+        if due.peek().is_none() {
+            return;
+        }
+        let message = message();
+        for client in due {
+            client.send(&message);
+            client.flush();
+        }
+    }
+}
+
+// ---------------------------------------------------
+// 🎮 Applying requests
+// ---------------------------------------------------
+
+/// Carry out a request on the single-junction simulation; replays take no
+/// inputs besides pausing and stepping
+pub fn apply_to_simulation(sim: &mut Simulation, control: &mut RunControl, request: Request, replay: bool) -> Result<(), String> {
+    if control.handle(request) {
+        return Ok(());
+    }
+    if replay {
+        return Err("replays take no inputs".into());
+    }
+
+    let command = match request {
+        Request::Spawn { dir: None, route: None } => Command::SpawnRandom, // Drawn from the run's RNG, so replays match
+        Request::Spawn { dir: Some(dir), route: None } => Command::SpawnVehicle(dir),
+        Request::Spawn { dir: Some(dir), route: Some(route) } => Command::SpawnRoute(dir, route),
+        Request::Spawn { dir: None, route: Some(_) } => return Err("a route needs a 'dir'".into()),
+        Request::SpawnPedestrian => Command::SpawnPedestrian,
+        Request::SpawnBicycle => Command::SpawnBicycle,
+        Request::Speed(level) => Command::SetVelocity(level),
+        Request::AutoSpawn(on) if on == sim.auto_spawn => return Ok(()),
+        Request::AutoSpawn(_) => Command::ToggleAutoSpawn,
+        Request::Policy(kind) => Command::SetJunction(kind),
+        Request::Pause | Request::Resume | Request::Step(_) | Request::Stream(_) | Request::Quit => return Ok(()),
+    };
+    sim.apply(command);
+    Ok(())
+}
+
+/// Carry out a request on the network (random trips; one junction policy)
+pub fn apply_to_network(
+    network: &mut RoadNetwork,
+    control: &mut RunControl,
+    auto_spawn: &mut bool,
+    rng: &mut impl Rng,
+    stats: &mut Stats,
+    request: Request,
+) -> Result<(), String> {
+    if control.handle(request) {
+        return Ok(());
+    }
+
+    match request {
+        Request::Spawn { dir: None, route: None } => {
+            if !network.spawn_random(rng, stats) {
+                return Err("entry link backed up".into());
+            }
+        }
+        Request::Spawn { .. } => return Err("network trips run between random gateways".into()),
+        Request::SpawnPedestrian | Request::SpawnBicycle => return Err("no pedestrians or cyclists in network mode".into()),
+        Request::Speed(level) => network.velocity_level = level,
+        Request::AutoSpawn(on) => *auto_spawn = on,
+        Request::Policy(_) => return Err("network junctions only run reservations".into()),
+        Request::Pause | Request::Resume | Request::Step(_) | Request::Stream(_) | Request::Quit => {}
+    }
+    Ok(())
+}
+
+// ---------------------------------------------------
+// 📤 Tick messages
+// ---------------------------------------------------
+
+/// A number as JSON: whole numbers without a fraction, the rest to 3
+/// decimals, and null for NaN and infinity (JSON has neither)
+#[derive(Clone, Copy)]
+struct Num(f32);
+
+impl Serialize for Num {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let x = self.0;
+        if !x.is_finite() {
+            serializer.serialize_none()
+        } else if x.fract() == 0.0 && x.abs() < 1e9 {
+            serializer.serialize_i64(x as i64)
+        } else {
+            serializer.serialize_f64((x as f64 * 1000.0).round() / 1000.0)
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Tick<T: Serialize> {
+    r#type: &'static str,
+    step: u64,
+    time: Num,
+    vehicles: Vec<T>,
+    stats: StatsMessage,
+}
+
+impl<T: Serialize> Tick<T> {
+    fn message(step: u64, vehicles: Vec<T>, stats: &Stats) -> String {
+        let tick = Tick { r#type: "tick", step, time: Num(step as f32 * STEP), vehicles, stats: StatsMessage::new(stats) };
+        serde_json::to_string(&tick).unwrap()
+    }
+}
+
+#[derive(Serialize)]
+struct VehicleMessage {
+    id: usize,
+    x: Num,
+    y: Num,
+    speed: Num,
+    dir: &'static str,
+    route: &'static str,
+    kind: &'static str,
+    autonomy: &'static str,
+    in_junction: bool,
+}
+
+#[derive(Serialize)]
+struct NetVehicleMessage {
+    id: usize,
+    x: Num,
+    y: Num,
+    speed: Num,
+    state: &'static str,
+    autonomy: &'static str,
+    destination: usize,
+}
+
+/// Vehicle states and statistics after the simulation's latest step
+pub fn simulation_tick(sim: &Simulation) -> String {
+    let vehicles = sim
+        .vehicles
+        .iter()
+        .map(|v| VehicleMessage {
+            id: v.id,
+            x: Num(v.x),
+            y: Num(v.y),
+            speed: Num(v.speed),
+            dir: direction_name(v.direction),
+            route: route_name(v.route),
+            kind: if v.kind == VehicleKind::Bicycle { "bicycle" } else { "car" },
+            autonomy: v.autonomy().name(),
+            in_junction: v.is_in_intersection(),
+        })
+        .collect();
+    Tick::message(sim.steps, vehicles, &sim.stats)
+}
+
+/// Vehicle states and statistics after the network's latest step
+pub fn network_tick(network: &RoadNetwork, step: u64, stats: &Stats) -> String {
+    let vehicles = network
+        .vehicles
+        .values()
+        .map(|v| {
+            let (x, y) = network.vehicle_position(v);
+            NetVehicleMessage {
+                id: v.id,
+                x: Num(x),
+                y: Num(y),
+                speed: Num(v.speed),
+                state: match v.state {
+                    NetState::Driving => "driving",
+                    NetState::Crossing { .. } => "crossing",
+                },
+                autonomy: v.autonomy().name(),
+                destination: v.destination,
+            }
+        })
+        .collect();
+    Tick::message(step, vehicles, stats)
+}
+
+#[derive(Serialize)]
+struct StatsMessage {
+    runtime: Num,
+    total_vehicles: u32,
+    vehicles_exited: u32,
+    throughput: Num,
+    wrong_exits: u32,
+    total_distance: Num,
+    collision_avoided: u32,
+    pedestrians: u32,
+    pedestrians_crossed: u32,
+    avg_pedestrian_wait: Num,
+    bicycles: u32,
+    bike_close_calls: u32,
+    lane_changes: u32,
+    network_spawned: u32,
+    network_trips: u32,
+    network_travel_time: Num,
+    network_spillbacks: u32,
+    avg_grant_wait: Num,
+    network_lapsed_grants: u32,
+    safety_violations: u32,
+    by_class: ByClass,
+}
+
+#[derive(Serialize)]
+struct ByClass {
+    autonomous: ClassMessage,
+    human: ClassMessage,
+}
+
+#[derive(Serialize)]
+struct ClassMessage {
+    spawned: u32,
+    trips: u32,
+    avg_travel_time: Num,
+    violations: u32,
+}
+
+impl StatsMessage {
+    fn new(s: &Stats) -> Self {
+        let class = |autonomy: Autonomy| {
+            let c = &s.by_class[autonomy.index()];
+            ClassMessage { spawned: c.spawned, trips: c.trips, avg_travel_time: Num(c.avg_travel_time()), violations: c.violations }
+        };
+        Self {
+            runtime: Num(s.runtime),
+            total_vehicles: s.total_vehicles,
+            vehicles_exited: s.vehicles_exited,
+            throughput: Num(s.throughput()),
+            wrong_exits: s.wrong_exits,
+            total_distance: Num(s.total_distance),
+            collision_avoided: s.collision_avoided,
+            pedestrians: s.pedestrians,
+            pedestrians_crossed: s.pedestrians_crossed,
+            avg_pedestrian_wait: Num(s.avg_pedestrian_wait()),
+            bicycles: s.bicycles,
+            bike_close_calls: s.bike_close_calls,
+            lane_changes: s.lane_changes,
+            network_spawned: s.network_spawned,
+            network_trips: s.network_trips,
+            network_travel_time: Num(s.network_travel_time),
+            network_spillbacks: s.network_spillbacks,
+            avg_grant_wait: Num(s.avg_grant_wait()),
+            network_lapsed_grants: s.network_lapsed_grants,
+            safety_violations: s.safety_violations(),
+            by_class: ByClass { autonomous: class(Autonomy::Autonomous), human: class(Autonomy::Human) },
+        }
+    }
+}
+
+pub fn stats_json(s: &Stats) -> String {
+    serde_json::to_string(&StatsMessage::new(s)).unwrap()
+}
+
+pub(crate) fn direction_name(dir: Direction) -> &'static str {
+    match dir {
+        Direction::Up => "up",
+        Direction::Down => "down",
+        Direction::Left => "left",
+        Direction::Right => "right",
+    }
+}
+
+pub(crate) fn route_name(route: Route) -> &'static str {
+    match route {
+        Route::Right => "right",
+        Route::Straight => "straight",
+        Route::Left => "left",
+        Route::UTurn => "uturn",
+    }
+}
+
+// ---------------------------------------------------
+// 📥 Parsing
+// ---------------------------------------------------
+
+/// One command line, e.g. {"cmd":"speed","level":"fast"}
+pub fn parse_request(line: &str) -> Result<Request, String> {
+    let fields: Map<String, Value> = serde_json::from_str(line).map_err(|e| format!("not a JSON object: {}", e))?;
+    let get = |key: &str| fields.get(key);
+    let text = |key: &str| match get(key) {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::String(s)) => Ok(Some(s.as_str())),
+        Some(_) => Err(format!("'{}' must be a string", key)),
+    };
+    let count = |key: &str, default: u64| match get(key) {
+        None | Some(Value::Null) => Ok(default),
+        Some(n) => n.as_u64().ok_or_else(|| format!("'{}' must be a whole number", key)),
+    };
+
+    let request = match text("cmd")?.ok_or("missing \"cmd\"")? {
+        "spawn" => Request::Spawn {
+            dir: text("dir")?.map(parse_direction).transpose()?,
+            route: text("route")?.map(parse_route).transpose()?,
+        },
+        "spawn_pedestrian" => Request::SpawnPedestrian,
+        "spawn_bicycle" => Request::SpawnBicycle,
+        "pause" => Request::Pause,
+        "resume" => Request::Resume,
+        "step" => Request::Step(count("n", 1)?),
+        "speed" => Request::Speed(parse_level(text("level")?.unwrap_or(""))?),
+        "auto_spawn" => match get("on") {
+            Some(Value::Bool(on)) => Request::AutoSpawn(*on),
+            _ => return Err("'on' must be true or false".into()),
+        },
+        "policy" => Request::Policy(match text("junction")? {
+            Some("cross") => JunctionKind::Cross,
+            Some("roundabout") => JunctionKind::Roundabout,
+            _ => return Err("'junction' must be cross or roundabout".into()),
+        }),
+        "stream" => Request::Stream(count("every", 1)?),
+        "quit" => Request::Quit,
+        other => return Err(format!("unknown command '{}'", other)),
+    };
+    Ok(request)
+}
+
+fn parse_direction(name: &str) -> Result<Direction, String> {
+    match name {
+        "up" => Ok(Direction::Up),
+        "down" => Ok(Direction::Down),
+        "left" => Ok(Direction::Left),
+        "right" => Ok(Direction::Right),
+        _ => Err(format!("'dir' must be up, down, left or right, not '{}'", name)),
+    }
+}
+
+pub(crate) fn parse_level(name: &str) -> Result<VelocityLevel, String> {
+    match name {
+        "slow" => Ok(VelocityLevel::Slow),
+        "medium" => Ok(VelocityLevel::Medium),
+        "fast" => Ok(VelocityLevel::Fast),
+        _ => Err("'level' must be slow, medium or fast".into()),
+    }
+}
+
+fn parse_route(name: &str) -> Result<Route, String> {
+    match name {
+        "right" => Ok(Route::Right),
+        "straight" => Ok(Route::Straight),
+        "left" => Ok(Route::Left),
+        "uturn" => Ok(Route::UTurn),
+        _ => Err(format!("'route' must be right, straight, left or uturn, not '{}'", name)),
+    }
+}
@@ -0,0 +1,209 @@
+use sdl2::pixels::Color;
+use sdl2::rect::Rect;
+use sdl2::render::{Canvas, RenderTarget};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use crate::config::{JunctionKind, SimConfig};
+use crate::geometry::RoadGeometry;
+use crate::roundabout::build_roundabout_path;
+use crate::stats::Stats;
+use crate::vehicle::{Direction, Route, Vehicle, QUEUE_GAP, VEHICLE_HALF_LENGTH};
+
+// 🚲 Bike lane strip painted along the curb edge of the curb lane
+pub const BIKE_LANE_WIDTH: f32 = 12.0;
+// Below this lateral offset a bike and a car are treated as sharing a corridor
+pub const BIKE_LANE_CLEARANCE: f32 = 15.0;
+pub const BIKE_HALF_LENGTH: f32 = 10.0;
+pub const BIKE_HALF_WIDTH: f32 = 4.0;
+// Share of the usual clearance (a car passing the bike lane, or queueing
+// behind a bike in its lane) that makes a close call
+const BIKE_CLOSE_CALL_SHARE: f32 = 0.5;
+
+/// Unit vector pointing from the lane center towards the curb
+fn curb_side(dir: Direction) -> (f32, f32) {
+    match dir {
+        Direction::Up => (1.0, 0.0),
+        Direction::Down => (-1.0, 0.0),
+        Direction::Left => (0.0, -1.0),
+        Direction::Right => (0.0, 1.0),
+    }
+}
+
+/// Lateral offset of a cyclist from the curb lane's centerline
+pub fn bike_lane_offset(g: &RoadGeometry) -> f32 {
+    (g.lane_width as f32 - BIKE_LANE_WIDTH) / 2.0
+}
+
+/// Center distance from a car below which a cyclist has had a close call:
+/// well inside what a car passing the bike lane, or queueing behind a bike
+/// in its own lane, keeps
+fn close_call_distance(bike: &Vehicle) -> f32 {
+    if bike.in_bike_lane {
+        bike_lane_offset(&bike.road) * BIKE_CLOSE_CALL_SHARE
+    } else {
+        VEHICLE_HALF_LENGTH + BIKE_HALF_LENGTH + QUEUE_GAP * BIKE_CLOSE_CALL_SHARE
+    }
+}
+
+/// Straight-through path along the curb lane, shifted onto the bike lane if enabled
+pub fn build_bike_path(g: &RoadGeometry, dir: Direction, bike_lane: bool, junction: JunctionKind) -> Vec<(f32, f32)> {
+    let lane = g.curb_lane(dir);
+    let exit = g.exit_curb_lane(dir);
+
+    let mut path = match junction {
+        JunctionKind::Roundabout => {
+            // Ridden like a car going straight; only the approach and departure follow the curb
+            let mut path = build_roundabout_path(g, dir, Route::Straight, lane);
+            let n = path.len();
+            path[n - 2] = align_to_lane(g, dir, path[n - 2], exit);
+            path[n - 1] = align_to_lane(g, dir, path[n - 1], exit);
+            path
+        }
+        JunctionKind::Cross => [
+            (lane, g.spawn_coord(dir)),
+            (lane, g.box_entry(dir)),
+            (exit, g.box_exit(dir)),
+            (exit, g.despawn_coord(dir)),
+        ]
+        .into_iter()
+        .map(|(lateral, along)| match dir {
+            Direction::Up | Direction::Down => g.tile_center(lateral, along),
+            Direction::Left | Direction::Right => g.tile_center(along, lateral),
+        })
+        .collect(),
+    };
+
+    let offset = if bike_lane { bike_lane_offset(g) } else { 0.0 };
+    let (sx, sy) = curb_side(dir);
+    let n = path.len();
+    for i in [0, 1, n - 2, n - 1] {
+        let (x, y) = path[i];
+        path[i] = (x + sx * offset, y + sy * offset);
+    }
+
+    path
+}
+
+/// Move a point sideways onto the centerline of `lane`
+fn align_to_lane(g: &RoadGeometry, dir: Direction, (x, y): (f32, f32), lane: i32) -> (f32, f32) {
+    let c = g.lane_center(lane);
+    match dir {
+        Direction::Up | Direction::Down => (c, y),
+        Direction::Left | Direction::Right => (x, c),
+    }
+}
+
+/// Spawn a cyclist unless the entry point is still occupied
+pub fn spawn_bicycle(vehicles: &mut Vec<Vehicle>, stats: &mut Stats, road: &Arc<RoadGeometry>, dir: Direction, config: &SimConfig) {
+    const MIN_SPAWN_DISTANCE: f32 = 40.0;
+
+    let bike = Vehicle::new_bicycle(road, dir, config.bike_lanes, config.junction);
+    if vehicles.iter().any(|v| v.distance_to(&bike) < MIN_SPAWN_DISTANCE) {
+        return;
+    }
+
+    vehicles.push(bike);
+    stats.bicycles += 1;
+}
+
+// =======================================================
+// 🎨 DRAWING
+// =======================================================
+
+/// Paint the green curb-side bike lanes (outside the intersection and zebras)
+pub fn draw_bike_lanes<T: RenderTarget>(canvas: &mut Canvas<T>, g: &RoadGeometry) {
+    let t = g.lane_width;
+    let w = BIKE_LANE_WIDTH as i32;
+    let (near, far) = g.junction_edges();
+    // Stop short of the crosswalk rows/columns
+    let low = (0, near as i32 - t);
+    let high = (far as i32 + t, g.pixel_size());
+
+    canvas.set_draw_color(Color::RGB(40, 140, 70));
+
+    for dir in [Direction::Up, Direction::Down, Direction::Left, Direction::Right] {
+        let (approach, departure) = match dir {
+            Direction::Up | Direction::Left => (high, low),
+            Direction::Down | Direction::Right => (low, high),
+        };
+        let (sx, sy) = curb_side(dir);
+        let curb_positive = sx + sy > 0.0;
+
+        for (lane, (start, end)) in [(g.curb_lane(dir), approach), (g.exit_curb_lane(dir), departure)] {
+            // Strip along the curb edge of the lane
+            let edge = if curb_positive { (lane + 1) * t - w } else { lane * t };
+            let len = (end - start) as u32;
+            let rect = match dir {
+                Direction::Up | Direction::Down => Rect::new(edge, start, w as u32, len),
+                Direction::Left | Direction::Right => Rect::new(start, edge, len, w as u32),
+            };
+            canvas.fill_rect(rect).ok();
+        }
+    }
+}
+
+pub fn draw_bicycle<T: RenderTarget>(canvas: &mut Canvas<T>, x: f32, y: f32, dir: Direction) {
+    let (hw, hh) = match dir {
+        Direction::Up | Direction::Down => (BIKE_HALF_WIDTH, BIKE_HALF_LENGTH),
+        Direction::Left | Direction::Right => (BIKE_HALF_LENGTH, BIKE_HALF_WIDTH),
+    };
+
+    canvas.set_draw_color(Color::RGB(20, 200, 90));
+    canvas
+        .fill_rect(Rect::new(
+            (x - hw) as i32,
+            (y - hh) as i32,
+            (hw * 2.0) as u32,
+            (hh * 2.0) as u32,
+        ))
+        .ok();
+
+    // Rider's helmet
+    canvas.set_draw_color(Color::RGB(250, 250, 250));
+    canvas.fill_rect(Rect::new(x as i32 - 2, y as i32 - 2, 4, 4)).ok();
+}
+
+// =======================================================
+// 📊 MIXED-TRAFFIC SAFETY TRACKING
+// =======================================================
+
+/// Counts cars yielding to cyclists and car↔bike close calls (once per agent)
+#[derive(Default)]
+pub struct BikeSafetyTracker {
+    yielded_cars: HashSet<usize>,
+    close_call_bikes: HashSet<usize>,
+}
+
+impl BikeSafetyTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn update(&mut self, vehicles: &[Vehicle], stats: &mut Stats) {
+        // Forget whoever has left the map
+        let present: HashSet<usize> = vehicles.iter().map(|v| v.id).collect();
+        self.yielded_cars.retain(|id| present.contains(id));
+        self.close_call_bikes.retain(|id| present.contains(id));
+
+        for car in vehicles.iter().filter(|v| v.yielding_to_bike) {
+            if self.yielded_cars.insert(car.id) {
+                stats.bike_yields += 1;
+            }
+        }
+
+        for bike in vehicles.iter().filter(|v| v.is_bicycle()) {
+            if self.close_call_bikes.contains(&bike.id) {
+                continue;
+            }
+
+            let limit = close_call_distance(bike);
+            let close = vehicles.iter().any(|car| !car.is_bicycle() && car.distance_to(bike) < limit);
+
+            if close {
+                self.close_call_bikes.insert(bike.id);
+                stats.bike_close_calls += 1;
+            }
+        }
+    }
+}
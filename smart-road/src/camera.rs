@@ -0,0 +1,169 @@
+use sdl2::event::Event;
+use sdl2::keyboard::Keycode;
+use sdl2::mouse::{MouseButton, MouseWheelDirection};
+use sdl2::rect::Rect;
+
+// =======================================================
+// 🎥 CAMERA (world → screen transform)
+// =======================================================
+
+const ZOOM_STEP: f32 = 1.15; // Per mouse-wheel notch
+const MIN_ZOOM: f32 = 0.05;
+const MAX_ZOOM: f32 = 8.0;
+const DRAG_THRESHOLD: i32 = 4; // Screen pixels a press may move and still count as a click
+const PICK_RADIUS: f32 = 30.0; // Screen pixels around a click that select a vehicle
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Camera {
+    pub x: f32, // World coordinate shown at the screen's top-left corner
+    pub y: f32,
+    pub zoom: f32, // Screen pixels per world unit
+}
+
+impl Camera {
+    /// Camera that shows the world bounds as large as possible, centered
+    pub fn fit(min_x: f32, min_y: f32, max_x: f32, max_y: f32, view_w: u32, view_h: u32) -> Self {
+        let w = (max_x - min_x).max(1.0);
+        let h = (max_y - min_y).max(1.0);
+        let zoom = (view_w as f32 / w).min(view_h as f32 / h);
+
+        Self {
+            x: min_x - (view_w as f32 / zoom - w) / 2.0,
+            y: min_y - (view_h as f32 / zoom - h) / 2.0,
+            zoom,
+        }
+    }
+
+    pub fn to_screen(self, x: f32, y: f32) -> (i32, i32) {
+        (
+            ((x - self.x) * self.zoom) as i32,
+            ((y - self.y) * self.zoom) as i32,
+        )
+    }
+
+    pub fn to_world(self, sx: i32, sy: i32) -> (f32, f32) {
+        (self.x + sx as f32 / self.zoom, self.y + sy as f32 / self.zoom)
+    }
+
+    /// Scale a world length to screen pixels (at least one pixel)
+    pub fn scale(&self, len: f32) -> u32 {
+        ((len * self.zoom) as u32).max(1)
+    }
+
+    /// Screen rectangle for a world rectangle
+    pub fn rect(&self, x: f32, y: f32, w: f32, h: f32) -> Rect {
+        let (sx, sy) = self.to_screen(x, y);
+        Rect::new(sx, sy, self.scale(w), self.scale(h))
+    }
+
+    /// Zoom by `factor`, keeping the world point under screen (sx, sy) in place
+    pub fn zoom_at(&mut self, factor: f32, sx: i32, sy: i32) {
+        let (wx, wy) = self.to_world(sx, sy);
+        self.zoom = (self.zoom * factor).clamp(MIN_ZOOM, MAX_ZOOM);
+        self.x = wx - sx as f32 / self.zoom;
+        self.y = wy - sy as f32 / self.zoom;
+    }
+
+    /// Move the view with the mouse: the world follows a drag of (dx, dy) screen pixels
+    pub fn pan(&mut self, dx: i32, dy: i32) {
+        self.x -= dx as f32 / self.zoom;
+        self.y -= dy as f32 / self.zoom;
+    }
+
+    /// Put world point (x, y) in the middle of a `view_w` x `view_h` screen
+    pub fn center_on(&mut self, x: f32, y: f32, view_w: u32, view_h: u32) {
+        self.x = x - view_w as f32 / self.zoom / 2.0;
+        self.y = y - view_h as f32 / self.zoom / 2.0;
+    }
+}
+
+// ---------------------------------------------------
+// 🖱️ Mouse controls (zoom, pan, follow a vehicle)
+// ---------------------------------------------------
+
+pub struct CameraControl {
+    pub camera: Camera,
+    pub following: Option<usize>, // Vehicle id kept in the middle of the view
+    home: Camera,                 // Where Z takes the view back to
+    view: (u32, u32),
+    press: Option<(i32, i32)>, // Left button held since this screen point
+    last: (i32, i32),          // Pointer position the view was last panned to
+    dragged: bool,
+}
+
+impl CameraControl {
+    pub fn new(camera: Camera, view_w: u32, view_h: u32) -> Self {
+        Self { camera, following: None, home: camera, view: (view_w, view_h), press: None, last: (0, 0), dragged: false }
+    }
+
+    /// Wheel zooms at the cursor, left-drag pans, Z resets the view. Returns
+    /// the world point of a click (press and release without dragging).
+    pub fn handle(&mut self, event: &Event) -> Option<(f32, f32)> {
+        match *event {
+            Event::MouseWheel { precise_y, direction, mouse_x, mouse_y, .. } if precise_y != 0.0 => {
+                let notches = if direction == MouseWheelDirection::Flipped { -precise_y } else { precise_y };
+                let (sx, sy) = if self.following.is_some() {
+                    (self.view.0 as i32 / 2, self.view.1 as i32 / 2) // Keep the followed vehicle centered
+                } else {
+                    (mouse_x, mouse_y)
+                };
+                self.camera.zoom_at(ZOOM_STEP.powf(notches), sx, sy);
+            }
+            Event::MouseButtonDown { mouse_btn: MouseButton::Left, x, y, .. } => {
+                self.press = Some((x, y));
+                self.last = (x, y);
+                self.dragged = false;
+            }
+            Event::MouseMotion { x, y, .. } if self.press.is_some() => {
+                let (px, py) = self.press.unwrap_or((x, y));
+                if (x - px).abs().max((y - py).abs()) > DRAG_THRESHOLD {
+                    self.dragged = true;
+                    self.following = None;
+                }
+                // Pan by the movement since the last pan; the first one includes
+                // the slack taken from the press, so the map keeps up with the hand
+                if self.dragged {
+                    self.camera.pan(x - self.last.0, y - self.last.1);
+                    self.last = (x, y);
+                }
+            }
+            Event::MouseButtonUp { mouse_btn: MouseButton::Left, x, y, .. } => {
+                let clicked = self.press.take().is_some() && !self.dragged;
+                return clicked.then(|| self.camera.to_world(x, y));
+            }
+            Event::KeyDown { keycode: Some(Keycode::Z), repeat: false, .. } => {
+                self.camera = self.home;
+                self.following = None;
+            }
+            _ => {}
+        }
+        None
+    }
+
+    /// Closest of `vehicles` (id, x, y) to a clicked world point, if any is
+    /// within reach of the cursor
+    pub fn pick(&self, (x, y): (f32, f32), vehicles: impl Iterator<Item = (usize, f32, f32)>) -> Option<usize> {
+        let reach = PICK_RADIUS / self.camera.zoom;
+        vehicles
+            .map(|(id, vx, vy)| (id, (vx - x).hypot(vy - y)))
+            .filter(|&(_, d)| d <= reach)
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(id, _)| id)
+    }
+
+    /// Keep the followed vehicle centered; `position` is None once it is gone,
+    /// which ends following. Returns the id of a vehicle that was lost.
+    pub fn follow(&mut self, position: impl Fn(usize) -> Option<(f32, f32)>) -> Option<usize> {
+        let id = self.following?;
+        match position(id) {
+            Some((x, y)) => {
+                self.camera.center_on(x, y, self.view.0, self.view.1);
+                None
+            }
+            None => {
+                self.following = None;
+                Some(id)
+            }
+        }
+    }
+}
@@ -1,5 +1,302 @@
+use std::path::PathBuf;
+
+use crate::geometry::RoadGeometry;
+use crate::lane_use::Approach;
+use crate::map::{MapError, TileMap};
+use crate::perception::Perception;
+use crate::v2i::Channel;
+use crate::vehicle::Direction;
+
 pub const TILE_SIZE: i32 = 50;
-pub const GRID_W: i32 = 18;
-pub const GRID_H: i32 = 18;
-pub const MID_TILE: i32 = GRID_W / 2;
-pub const ROAD_HALF_TILES: i32 = 3;
\ No newline at end of file
+
+// =======================================================
+// ⚙️ RUN CONFIGURATION (command-line flags)
+// =======================================================
+
+/// Layout of the central junction
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum JunctionKind {
+    Cross,      // 4-way autonomous intersection
+    Roundabout, // Single-lane roundabout, yield on entry
+}
+
+pub const MAX_LANES: i32 = 6;
+pub const LANE_WIDTHS: std::ops::RangeInclusive<i32> = 40..=80; // --lane-width bounds (cars are ~32 px wide)
+
+#[derive(Clone, Debug)]
+pub struct SimConfig {
+    pub junction: JunctionKind,
+    pub bike_lanes: bool,            // --bike-lanes
+    pub network: Option<(i32, i32)>, // --network RxC
+    pub seed: Option<u64>,           // --seed N (same seed → same demand sequence)
+    pub map: Option<PathBuf>,        // --map FILE (tile layout; generated from the geometry otherwise)
+    pub road: RoadGeometry,          // --lanes N, --approach DIR=LANES/EXITS (repeatable), --lane-width PX
+    pub headless: bool,              // --headless (no window, fixed time step)
+    pub duration: f32,               // --duration S (simulated seconds in headless runs)
+    pub record: Option<PathBuf>,     // --record FILE (save seed and inputs on exit)
+    pub replay: Option<PathBuf>,     // --replay FILE (rerun a recording)
+    pub frames: Option<PathBuf>,     // --frames DIR (headless PNG frame export)
+    pub frame_interval: f32,         // --frame-interval S (simulated seconds between frames)
+    pub heatmap: Option<PathBuf>,    // --heatmap DIR (export heatmaps when the run ends)
+    pub abort_on_violation: bool,    // --abort-on-violation (stop at the first safety violation)
+    pub v2i_log: Option<PathBuf>,    // --v2i-log FILE (write every V2I message as CSV)
+    pub channel: Channel,            // --latency MS, --jitter MS, --packet-loss P (V2I links)
+    pub channels: Vec<((i32, i32), Channel)>, // --channel C,R=LAT/JIT/LOSS overrides per junction (--network only)
+    pub latency_sweep: bool,         // --latency-sweep (headless network: rerun at rising latencies)
+    pub perception: Perception,      // --sensor-range, --sensor-fov, --position-noise, --speed-noise, --occlusion
+    pub av_penetration: f32,         // --av-penetration P (share of autonomous cars, the rest are human-driven)
+    pub api: Option<u16>,            // --api PORT (control the run over line-delimited JSON on localhost)
+}
+
+impl Default for SimConfig {
+    /// Standard 3-lane cross, no extras
+    fn default() -> Self {
+        Self {
+            junction: JunctionKind::Cross,
+            bike_lanes: false,
+            network: None,
+            seed: None,
+            map: None,
+            road: RoadGeometry::default(),
+            headless: false,
+            duration: 300.0,
+            record: None,
+            replay: None,
+            frames: None,
+            frame_interval: 0.5,
+            heatmap: None,
+            abort_on_violation: false,
+            v2i_log: None,
+            channel: Channel::default(),
+            channels: Vec::new(),
+            latency_sweep: false,
+            perception: Perception::default(),
+            av_penetration: 1.0,
+            api: None,
+        }
+    }
+}
+
+impl SimConfig {
+    pub fn from_args() -> Self {
+        let args: Vec<String> = std::env::args().skip(1).collect();
+        Self::from_arg_list(&args)
+    }
+
+    /// Parse flags (without the program name)
+    pub fn from_arg_list(args: &[String]) -> Self {
+        let value = |flag: &str| {
+            args.iter()
+                .position(|a| a == flag)
+                .and_then(|i| args.get(i + 1))
+                .map(String::as_str)
+        };
+
+        let junction = match value("--junction") {
+            None | Some("cross") => JunctionKind::Cross,
+            Some("roundabout") => JunctionKind::Roundabout,
+            Some(other) => {
+                eprintln!("⚠️  Unknown --junction '{}', expected cross or roundabout", other);
+                JunctionKind::Cross
+            }
+        };
+
+        let seed = value("--seed").and_then(|s| match s.parse() {
+            Ok(seed) => Some(seed),
+            Err(_) => {
+                eprintln!("⚠️  Invalid --seed '{}'", s);
+                None
+            }
+        });
+
+        let lanes = match value("--lanes").map(str::parse::<i32>) {
+            None => 3,
+            Some(Ok(n)) if (1..=MAX_LANES).contains(&n) => n,
+            Some(_) => {
+                eprintln!("⚠️  --lanes expects 1..={}, using 3", MAX_LANES);
+                3
+            }
+        };
+
+        let duration = match value("--duration").map(str::parse::<f32>) {
+            None => 300.0,
+            Some(Ok(s)) if s > 0.0 => s,
+            Some(_) => {
+                eprintln!("⚠️  --duration expects a positive number of seconds, using 300");
+                300.0
+            }
+        };
+
+        let frame_interval = match value("--frame-interval").map(str::parse::<f32>) {
+            None => 0.5,
+            Some(Ok(s)) if s > 0.0 => s,
+            Some(_) => {
+                eprintln!("⚠️  --frame-interval expects a positive number of seconds, using 0.5");
+                0.5
+            }
+        };
+
+        let millis = |flag: &str| match value(flag).map(str::parse::<f32>) {
+            None => 0.0,
+            Some(Ok(ms)) if ms >= 0.0 => ms / 1000.0,
+            Some(_) => {
+                eprintln!("⚠️  {} expects a non-negative number of milliseconds, using 0", flag);
+                0.0
+            }
+        };
+        let loss = match value("--packet-loss").map(str::parse::<f32>) {
+            None => 0.0,
+            Some(Ok(p)) if (0.0..1.0).contains(&p) => p,
+            Some(_) => {
+                eprintln!("⚠️  --packet-loss expects a probability in 0..1, using 0");
+                0.0
+            }
+        };
+        let channel = Channel { latency: millis("--latency"), jitter: millis("--jitter"), loss };
+
+        let positive = |flag: &str, default: f32| match value(flag).map(str::parse::<f32>) {
+            None => default,
+            Some(Ok(x)) if x >= 0.0 => x,
+            Some(_) => {
+                eprintln!("⚠️  {} expects a non-negative number, using {}", flag, default);
+                default
+            }
+        };
+        let perception = Perception {
+            range: positive("--sensor-range", f32::INFINITY),
+            fov: positive("--sensor-fov", 360.0).min(360.0),
+            position_noise: positive("--position-noise", 0.0),
+            speed_noise: positive("--speed-noise", 0.0),
+            occlusion: args.iter().any(|a| a == "--occlusion"),
+        };
+
+        let av_penetration = match value("--av-penetration").map(str::parse::<f32>) {
+            None => 1.0,
+            Some(Ok(p)) if (0.0..=1.0).contains(&p) => p,
+            Some(_) => {
+                eprintln!("⚠️  --av-penetration expects a share in 0..=1, using 1");
+                1.0
+            }
+        };
+
+        let api = value("--api").and_then(|p| match p.parse::<u16>() {
+            Ok(port) => Some(port),
+            Err(_) => {
+                eprintln!("⚠️  --api expects a port number, running without the control API");
+                None
+            }
+        });
+
+        let channels = args
+            .windows(2)
+            .filter(|w| w[0] == "--channel")
+            .filter_map(|w| parse_channel(&w[1]))
+            .collect();
+
+        let mut road = RoadGeometry::new(lanes);
+        for (dir, approach) in args.windows(2).filter(|w| w[0] == "--approach").filter_map(|w| parse_approach(&w[1])) {
+            road.set_approach(dir, approach);
+        }
+        road.lane_width = match value("--lane-width").map(str::parse::<i32>) {
+            None => TILE_SIZE,
+            Some(Ok(px)) if LANE_WIDTHS.contains(&px) => px,
+            Some(_) => {
+                eprintln!("⚠️  --lane-width expects {}..={} px, using {}", LANE_WIDTHS.start(), LANE_WIDTHS.end(), TILE_SIZE);
+                TILE_SIZE
+            }
+        };
+
+        Self {
+            junction,
+            bike_lanes: args.iter().any(|a| a == "--bike-lanes"),
+            network: value("--network").and_then(parse_network_size),
+            seed,
+            map: value("--map").map(PathBuf::from),
+            road,
+            headless: args.iter().any(|a| a == "--headless"),
+            duration,
+            record: value("--record").map(PathBuf::from),
+            replay: value("--replay").map(PathBuf::from),
+            frames: value("--frames").map(PathBuf::from),
+            frame_interval,
+            heatmap: value("--heatmap").map(PathBuf::from),
+            abort_on_violation: args.iter().any(|a| a == "--abort-on-violation"),
+            v2i_log: value("--v2i-log").map(PathBuf::from),
+            channel,
+            channels,
+            latency_sweep: args.iter().any(|a| a == "--latency-sweep"),
+            perception,
+            av_penetration,
+            api,
+        }
+    }
+
+    /// Tile map for this run (--map FILE, or generated from the road); a loaded
+    /// map also sets the road layout, so vehicles drive the lanes it draws
+    pub fn load_map(&mut self) -> Result<TileMap, MapError> {
+        let Some(path) = &self.map else {
+            return Ok(self.road.tile_map());
+        };
+        let map = TileMap::load(path)?;
+        self.road = self.road.for_tile_map(&map)?;
+        Ok(map)
+    }
+}
+
+/// "up=L,T,TR/2" → the approach of northbound traffic (see lane_use.rs)
+fn parse_approach(value: &str) -> Option<(Direction, Approach)> {
+    let (dir, spec) = value.split_once('=')?;
+    let dir = match dir {
+        "up" => Direction::Up,
+        "down" => Direction::Down,
+        "left" => Direction::Left,
+        "right" => Direction::Right,
+        _ => {
+            eprintln!("⚠️  --approach '{}': direction must be up, down, left or right", value);
+            return None;
+        }
+    };
+
+    match Approach::parse(spec, MAX_LANES) {
+        Ok(approach) => Some((dir, approach)),
+        Err(e) => {
+            eprintln!("⚠️  --approach '{}': {}", value, e);
+            None
+        }
+    }
+}
+
+/// "1,0=200/50/0.1" → the link to the junction in column 1, row 0 (see Channel::parse)
+fn parse_channel(value: &str) -> Option<((i32, i32), Channel)> {
+    let parsed = value.split_once('=').and_then(|(at, spec)| {
+        let (c, r) = at.split_once(',')?;
+        Some(((c.parse().ok()?, r.parse().ok()?), spec))
+    });
+    let Some((junction, spec)) = parsed else {
+        eprintln!("⚠️  --channel '{}': expected C,R=LATENCY/JITTER/LOSS", value);
+        return None;
+    };
+
+    match Channel::parse(spec) {
+        Ok(channel) => Some((junction, channel)),
+        Err(e) => {
+            eprintln!("⚠️  --channel '{}': {}", value, e);
+            None
+        }
+    }
+}
+
+/// "RxC" → Some((rows, cols))
+fn parse_network_size(value: &str) -> Option<(i32, i32)> {
+    let parsed = value
+        .split_once('x')
+        .and_then(|(r, c)| Some((r.parse::<i32>().ok()?, c.parse::<i32>().ok()?)));
+
+    match parsed {
+        Some((r, c)) if r > 0 && c > 0 => Some((r, c)),
+        _ => {
+            eprintln!("⚠️  Invalid --network size '{}', expected e.g. 2x2", value);
+            None
+        }
+    }
+}
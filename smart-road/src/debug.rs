@@ -0,0 +1,67 @@
+use sdl2::pixels::Color;
+use sdl2::rect::{Point, Rect};
+use sdl2::render::{Canvas, RenderTarget};
+
+use crate::bicycle::BIKE_LANE_CLEARANCE;
+use crate::geometry::RoadGeometry;
+use crate::vehicle::{Direction, Vehicle, LATERAL_THRESHOLD, SAFETY_DISTANCE};
+
+// =======================================================
+// 🐞 DEBUG OVERLAY (paths, look-ahead, who brakes for whom)
+// =======================================================
+
+const PATH_COLOR: Color = Color::RGB(80, 200, 255);
+const CORRIDOR_COLOR: Color = Color::RGB(255, 255, 255);
+const LEADER_COLOR: Color = Color::RGB(255, 60, 60);
+const JUNCTION_COLOR: Color = Color::RGB(255, 0, 255);
+
+fn point(x: f32, y: f32) -> Point {
+    Point::new(x as i32, y as i32)
+}
+
+/// Area `is_vehicle_ahead` searches: SAFETY_DISTANCE ahead, the lateral
+/// threshold to either side
+fn look_ahead_corridor(v: &Vehicle) -> Rect {
+    let half = if v.in_bike_lane { BIKE_LANE_CLEARANCE } else { LATERAL_THRESHOLD };
+    let (w, l) = ((half * 2.0) as u32, SAFETY_DISTANCE as u32);
+    let (x, y) = (v.x as i32, v.y as i32);
+    let (h, s) = (half as i32, SAFETY_DISTANCE as i32);
+
+    match v.facing_direction() {
+        Direction::Up => Rect::new(x - h, y - s, w, l),
+        Direction::Down => Rect::new(x - h, y, w, l),
+        Direction::Left => Rect::new(x - s, y - h, l, w),
+        Direction::Right => Rect::new(x, y - h, l, w),
+    }
+}
+
+pub fn draw_debug<T: RenderTarget>(canvas: &mut Canvas<T>, g: &RoadGeometry, vehicles: &[Vehicle]) {
+    // Box that `is_in_intersection` tests against
+    let (lo, hi) = g.junction_edges();
+    canvas.set_draw_color(JUNCTION_COLOR);
+    canvas
+        .draw_rect(Rect::new(lo as i32, lo as i32, (hi - lo) as u32, (hi - lo) as u32))
+        .ok();
+
+    for v in vehicles {
+        // Remaining waypoints
+        let remaining = v.path.get(v.current_target..).unwrap_or_default();
+        let points: Vec<Point> = std::iter::once(point(v.x, v.y))
+            .chain(remaining.iter().map(|&(x, y)| point(x, y)))
+            .collect();
+        canvas.set_draw_color(PATH_COLOR);
+        canvas.draw_lines(points.as_slice()).ok();
+        for p in &points[1..] {
+            canvas.fill_rect(Rect::new(p.x - 2, p.y - 2, 4, 4)).ok();
+        }
+
+        canvas.set_draw_color(CORRIDOR_COLOR);
+        canvas.draw_rect(look_ahead_corridor(v)).ok();
+
+        // Leader we are braking for
+        if let Some(leader) = v.leader.and_then(|id| vehicles.iter().find(|o| o.id == id)) {
+            canvas.set_draw_color(LEADER_COLOR);
+            canvas.draw_line(point(v.x, v.y), point(leader.x, leader.y)).ok();
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use rand::Rng;
+use std::collections::VecDeque;
+use std::f32::consts::PI;
+
+// =======================================================
+// 🧑 HUMAN DRIVERS (mixed traffic)
+// =======================================================
+//
+// A share of the cars can be driven by people instead of the autonomous
+// controller (--av-penetration). A human driver:
+// - responds to the road ahead a reaction time late, braking included
+//   (in the single junction they still brake at the last moment),
+// - judges gaps by their own standard, which may be tighter than the
+//   autonomous one (lane changes, roundabout entry, network junctions),
+// - never talks to junction managers. The single cross junction registers
+//   them by sight while they are first in line or crossing; in network mode
+//   the controller spots them at the stop line, stops granting and hands
+//   them the whole junction once it has emptied.
+
+const REACTION_MEAN: f32 = 1.0; // Seconds
+const REACTION_SD: f32 = 0.3;
+const REACTION_RANGE: (f32, f32) = (0.4, 2.0);
+const GAP_ACCEPTANCE_RANGE: (f32, f32) = (0.5, 1.3); // Below 1: takes tighter gaps than an AV would
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Autonomy {
+    Autonomous,
+    Human,
+}
+
+impl Autonomy {
+    pub const ALL: [Autonomy; 2] = [Autonomy::Autonomous, Autonomy::Human];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Autonomy::Autonomous => "autonomous",
+            Autonomy::Human => "human",
+        }
+    }
+
+    /// Position in per-class tables (see `Stats::by_class`)
+    pub fn index(self) -> usize {
+        self as usize
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct HumanDriver {
+    pub reaction: f32,       // Seconds between seeing something and acting on it
+    pub gap_acceptance: f32, // Scales the gaps the autonomous logic insists on
+    pending: Pending,
+}
+
+/// Decisions made but not acted on yet. Nobody else can see them, so copies
+/// of a vehicle (each step's read-only front buffer) start without them
+/// rather than duplicating the queue.
+#[derive(Debug, Default)]
+struct Pending {
+    decisions: VecDeque<(f32, f32)>, // (time, value)
+    acting_on: Option<f32>,
+}
+
+impl Clone for Pending {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+impl HumanDriver {
+    pub fn new(reaction: f32, gap_acceptance: f32) -> Self {
+        Self { reaction, gap_acceptance, pending: Pending::default() }
+    }
+
+    /// A driver drawn from the population
+    pub fn sample(rng: &mut impl Rng) -> Self {
+        // Box-Muller
+        let (u1, u2) = (1.0 - rng.random::<f32>(), rng.random::<f32>());
+        let normal = (-2.0 * u1.ln()).sqrt() * (2.0 * PI * u2).cos();
+        let reaction = (REACTION_MEAN + normal * REACTION_SD).clamp(REACTION_RANGE.0, REACTION_RANGE.1);
+        let gap_acceptance = rng.random_range(GAP_ACCEPTANCE_RANGE.0..=GAP_ACCEPTANCE_RANGE.1);
+        Self::new(reaction, gap_acceptance)
+    }
+
+    /// Who drives a new car: a human with probability 1 - `av_penetration`.
+    /// A fully autonomous fleet draws nothing, so seeded runs without human
+    /// drivers see the same random sequence as before they existed.
+    pub fn at_penetration(rng: &mut impl Rng, av_penetration: f32) -> Option<Self> {
+        if av_penetration >= 1.0 || rng.random::<f32>() < av_penetration {
+            None
+        } else {
+            Some(Self::sample(rng))
+        }
+    }
+
+    /// Note what the driver would do at `now` and return what they actually
+    /// do: the decision made a reaction time ago (the first one until then)
+    pub fn react(&mut self, now: f32, decided: f32) -> f32 {
+        let p = &mut self.pending;
+        p.decisions.push_back((now, decided));
+        while p.decisions.front().is_some_and(|&(t, _)| t <= now - self.reaction) {
+            p.acting_on = p.decisions.pop_front().map(|(_, value)| value);
+        }
+        p.acting_on.or(p.decisions.front().map(|&(_, value)| value)).unwrap_or(decided)
+    }
+}
@@ -0,0 +1,237 @@
+use crate::config::SimConfig;
+use crate::driver::Autonomy;
+use crate::signal::Phase;
+use crate::simulation::{Simulation, DIRECTIONS, STEP};
+use crate::vehicle::{Direction, Route, VehicleKind, VelocityLevel};
+
+// =======================================================
+// 🤖 RL ENVIRONMENT (reset / step over the single junction)
+// =======================================================
+//
+// A controller sees per-lane queues and vehicle states, acts on velocity
+// levels or signal phases, and is scored on throughput, delay and
+// collisions. Each `step` runs `decision_interval` seconds of fixed STEPs,
+// so the same seed and actions always give the same episode.
+//
+// Actions are one-shot: a level set on an approach applies to the vehicles
+// on it at the time, and later arrivals cruise at the default level until
+// the controller acts again. A signal phase holds until the next change.
+
+const QUEUE_SPEED: f32 = 25.0; // Slower than this before the junction counts as queued (px/s)
+
+#[derive(Clone, Debug)]
+pub enum Action {
+    Keep,
+    /// Levels for the vehicles approaching from each of `DIRECTIONS`
+    Approaches([Option<VelocityLevel>; 4]),
+    /// Levels for single vehicles by id (unknown ids are ignored)
+    Vehicles(Vec<(usize, VelocityLevel)>),
+    /// Switch to a signal phase, or back to no lights
+    Signal(Option<Phase>),
+}
+
+/// Weights of the reward terms; the reward of a step is
+/// throughput * exited - delay * lost seconds - collisions * overlaps,
+/// counting cars only (cyclists can never reach the fast cruise speed)
+#[derive(Clone, Copy, Debug)]
+pub struct RewardWeights {
+    pub throughput: f32, // Per vehicle that left the map
+    pub delay: f32,      // Per car-second lost against the fast cruise speed
+    pub collisions: f32, // Per new overlap episode
+}
+
+impl Default for RewardWeights {
+    fn default() -> Self {
+        Self { throughput: 1.0, delay: 0.1, collisions: 10.0 }
+    }
+}
+
+/// What one step produced, before weighting
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct RewardTerms {
+    pub exited: u32,
+    pub delay: f32,
+    pub collisions: u32,
+}
+
+impl RewardTerms {
+    pub fn reward(&self, w: &RewardWeights) -> f32 {
+        w.throughput * self.exited as f32 - w.delay * self.delay - w.collisions * self.collisions as f32
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct EnvConfig {
+    pub sim: SimConfig,         // Layout, demand extras and perception (the seed comes from `reset`)
+    pub decision_interval: f32, // Simulated seconds per step (whole STEPs, at least one)
+    pub episode_length: f32,    // Simulated seconds until done
+    pub auto_spawn: bool,       // Random demand, as in headless runs
+    pub reward: RewardWeights,
+}
+
+impl Default for EnvConfig {
+    fn default() -> Self {
+        Self {
+            sim: SimConfig::default(),
+            decision_interval: 1.0,
+            episode_length: 300.0,
+            auto_spawn: true,
+            reward: RewardWeights::default(),
+        }
+    }
+}
+
+/// Cars in one entry lane that have not reached the junction yet (cyclists
+/// ride their own lane or alongside, so they only show in `vehicles`)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LaneQueue {
+    pub direction: Direction,
+    pub lane: i32,
+    pub queued: u32,      // Stopped or crawling
+    pub approaching: u32, // All of them, queued included
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct VehicleState {
+    pub id: usize,
+    pub x: f32,
+    pub y: f32,
+    pub speed: f32,
+    pub direction: Direction,
+    pub route: Route,
+    pub lane: i32,
+    pub kind: VehicleKind,
+    pub autonomy: Autonomy,
+    pub velocity_level: VelocityLevel,
+    pub in_junction: bool,
+    pub time_in_system: f32,
+}
+
+#[derive(Clone, Debug)]
+pub struct Observation {
+    pub time: f32,
+    pub lanes: Vec<LaneQueue>, // Entry lanes of `DIRECTIONS` in order, same layout every step
+    pub vehicles: Vec<VehicleState>,
+    pub signal: Option<Phase>,
+}
+
+impl Observation {
+    /// Queued cars per entry lane, as a fixed-length feature vector
+    pub fn queue_lengths(&self) -> Vec<f32> {
+        self.lanes.iter().map(|l| l.queued as f32).collect()
+    }
+}
+
+pub struct Env {
+    pub config: EnvConfig,
+    pub sim: Simulation,
+    pub last_terms: RewardTerms,
+}
+
+impl Env {
+    pub fn new(config: EnvConfig) -> Self {
+        let sim = Simulation::new(config.sim.clone());
+        Self { config, sim, last_terms: RewardTerms::default() }
+    }
+
+    /// Start a new episode on `seed`'s demand
+    pub fn reset(&mut self, seed: u64) -> Observation {
+        let config = SimConfig { seed: Some(seed), ..self.config.sim.clone() };
+        self.sim = Simulation::new(config);
+        self.sim.auto_spawn = self.config.auto_spawn;
+        self.last_terms = RewardTerms::default();
+        self.observe()
+    }
+
+    /// Act, run one decision interval and score it
+    pub fn step(&mut self, action: &Action) -> (Observation, f32, bool) {
+        self.act(action);
+
+        let steps = ((self.config.decision_interval / STEP).round() as u32).max(1);
+        let mut terms = RewardTerms::default();
+        for _ in 0..steps {
+            if self.done() {
+                break;
+            }
+            let (exited, overlaps) = (self.sim.stats.vehicles_exited, self.sim.stats.overlaps);
+            self.sim.step(STEP);
+
+            terms.exited += self.sim.stats.vehicles_exited - exited;
+            terms.collisions += self.sim.stats.overlaps - overlaps;
+            terms.delay += self
+                .sim
+                .vehicles
+                .iter()
+                .filter(|v| !v.is_bicycle())
+                .map(|v| STEP * (1.0 - v.speed / v.kind.speed(VelocityLevel::Fast)).max(0.0))
+                .sum::<f32>();
+        }
+
+        self.last_terms = terms;
+        (self.observe(), terms.reward(&self.config.reward), self.done())
+    }
+
+    pub fn done(&self) -> bool {
+        self.sim.time() >= self.config.episode_length - STEP / 2.0 || self.sim.safety.aborted()
+    }
+
+    fn act(&mut self, action: &Action) {
+        match action {
+            Action::Keep => {}
+            Action::Approaches(levels) => {
+                for v in self.sim.vehicles.iter_mut().filter(|v| !v.entered_intersection) {
+                    let at = DIRECTIONS.iter().position(|&d| d == v.direction).unwrap();
+                    if let Some(level) = levels[at] {
+                        v.set_velocity_level(level);
+                    }
+                }
+            }
+            Action::Vehicles(levels) => {
+                for &(id, level) in levels {
+                    if let Some(v) = self.sim.vehicles.iter_mut().find(|v| v.id == id) {
+                        v.set_velocity_level(level);
+                    }
+                }
+            }
+            Action::Signal(phase) => self.sim.signal = *phase,
+        }
+    }
+
+    pub fn observe(&self) -> Observation {
+        let g = &self.sim.road;
+        let mut lanes: Vec<LaneQueue> = DIRECTIONS
+            .iter()
+            .flat_map(|&direction| g.entry_lanes(direction).into_iter().map(move |lane| LaneQueue { direction, lane, queued: 0, approaching: 0 }))
+            .collect();
+        for v in self.sim.vehicles.iter().filter(|v| !v.entered_intersection && !v.is_bicycle()) {
+            if let Some(q) = lanes.iter_mut().find(|q| q.direction == v.direction && q.lane == v.lane) {
+                q.approaching += 1;
+                if v.speed < QUEUE_SPEED {
+                    q.queued += 1;
+                }
+            }
+        }
+
+        let vehicles = self
+            .sim
+            .vehicles
+            .iter()
+            .map(|v| VehicleState {
+                id: v.id,
+                x: v.x,
+                y: v.y,
+                speed: v.speed,
+                direction: v.direction,
+                route: v.route,
+                lane: v.lane,
+                kind: v.kind,
+                autonomy: v.autonomy(),
+                velocity_level: v.velocity_level,
+                in_junction: v.is_in_intersection(),
+                time_in_system: v.time_in_system,
+            })
+            .collect();
+
+        Observation { time: self.sim.time(), lanes, vehicles, signal: self.sim.signal }
+    }
+}
@@ -0,0 +1,457 @@
+use crate::config::{MAX_LANES, TILE_SIZE};
+use crate::lane_use::{Approach, LaneUse};
+use crate::map::{MapError, Tile, TileMap};
+use crate::vehicle::{curb_route, exit_side, Direction, Route};
+
+// =======================================================
+// 📐 ROAD GEOMETRY (single source of lane positions)
+// =======================================================
+//
+// Two perpendicular roads either side of a median, crossing in a square
+// junction box sized for the widest carriageway. Each approach has its own
+// entry lanes (with a lane-use table) and exit lanes. Every lane is one tile
+// (`lane_width` px) wide, so lane centerlines are tile centers. Rendering, the
+// tile map, crosswalks, stop lines and vehicle paths are all derived from here.
+// Each simulation owns its layout, so runs with different roads can share a process.
+
+// Tiles of approach road between the map edge and the crosswalk
+const APPROACH_TILES: i32 = 5;
+// Turn pockets open this many tiles before the crosswalk
+const POCKET_TILES: i32 = 3;
+
+/// A lane leaving the map: the side it leaves through and its lane tile
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ExitLane {
+    pub side: Direction, // Up = north edge, etc.
+    pub lane: i32,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RoadGeometry {
+    pub approaches: [Approach; 4], // Indexed by the direction of inbound traffic
+    pub median: i32,               // Median width in tiles
+    pub lane_width: i32,           // Pixels per lane (and per tile)
+}
+
+impl Default for RoadGeometry {
+    fn default() -> Self {
+        Self::new(3)
+    }
+}
+
+fn approach_index(dir: Direction) -> usize {
+    match dir {
+        Direction::Up => 0,
+        Direction::Down => 1,
+        Direction::Left => 2,
+        Direction::Right => 3,
+    }
+}
+
+/// Tile at `along` the direction of travel and `lateral` across it
+fn at(dir: Direction, lateral: i32, along: i32) -> (i32, i32) {
+    match dir {
+        Direction::Up | Direction::Down => (lateral, along),
+        Direction::Left | Direction::Right => (along, lateral),
+    }
+}
+
+impl RoadGeometry {
+    /// Every approach with `lanes` lanes in and out
+    pub fn new(lanes: i32) -> Self {
+        Self {
+            approaches: std::array::from_fn(|_| Approach::uniform(lanes)),
+            median: 1,
+            lane_width: TILE_SIZE,
+        }
+    }
+
+    /// Convert tile coords → pixel center
+    pub fn tile_center(&self, tx: i32, ty: i32) -> (f32, f32) {
+        (self.lane_center(tx), self.lane_center(ty))
+    }
+
+    pub fn set_approach(&mut self, dir: Direction, approach: Approach) {
+        self.approaches[approach_index(dir)] = approach;
+    }
+
+    pub fn approach(&self, dir: Direction) -> &Approach {
+        &self.approaches[approach_index(dir)]
+    }
+
+    /// Widest carriageway, which sets the size of the junction box
+    fn max_lanes(&self) -> i32 {
+        self.approaches
+            .iter()
+            .map(|a| (a.lanes.len() as i32).max(a.exit_lanes))
+            .max()
+            .unwrap_or(1)
+    }
+
+    /// Lanes leaving the junction for traffic heading `dir`
+    pub fn exit_lane_count(&self, dir: Direction) -> i32 {
+        // The arm a vehicle heading `dir` leaves through is the approach of the opposite traffic
+        self.approach(dir.opposite()).exit_lanes
+    }
+
+    /// Width and height of the (square) map in tiles
+    pub fn grid_size(&self) -> i32 {
+        2 * self.max_lanes() + self.median + 2 + 2 * APPROACH_TILES - 1
+    }
+
+    pub fn pixel_size(&self) -> i32 {
+        self.grid_size() * self.lane_width
+    }
+
+    fn median_lo(&self) -> i32 {
+        self.grid_size() / 2 - (self.median - 1) / 2
+    }
+
+    fn median_hi(&self) -> i32 {
+        self.median_lo() + self.median - 1
+    }
+
+    // ---------- Junction box ----------
+
+    /// First and last tile of the junction box (same on both axes)
+    pub fn junction_tiles(&self) -> (i32, i32) {
+        (self.median_lo() - self.max_lanes(), self.median_hi() + self.max_lanes())
+    }
+
+    /// Pixel edges of the junction box (low side, high side)
+    pub fn junction_edges(&self) -> (f32, f32) {
+        let (lo, hi) = self.junction_tiles();
+        ((lo * self.lane_width) as f32, ((hi + 1) * self.lane_width) as f32)
+    }
+
+    /// Pixel coordinate of the junction center (same on both axes)
+    pub fn junction_center(&self) -> f32 {
+        let (lo, hi) = self.junction_edges();
+        (lo + hi) / 2.0
+    }
+
+    pub fn in_junction(&self, x: f32, y: f32) -> bool {
+        let (lo, hi) = self.junction_edges();
+        x >= lo && x < hi && y >= lo && y < hi
+    }
+
+    /// Progress (see `progress_along`) of the stop line where an approach meets the junction
+    pub fn stop_line(&self, dir: Direction) -> f32 {
+        let (lo, hi) = self.junction_edges();
+        match dir {
+            Direction::Up | Direction::Left => -hi,
+            Direction::Down | Direction::Right => lo,
+        }
+    }
+
+    /// Crosswalk tiles (x0, y0, x1, y1) across an approach, just outside the junction
+    pub fn crosswalk_tiles(&self, side: Direction) -> (i32, i32, i32, i32) {
+        let (lo, hi) = self.junction_tiles();
+        match side {
+            Direction::Up => (lo, lo - 1, hi, lo - 1),
+            Direction::Down => (lo, hi + 1, hi, hi + 1),
+            Direction::Left => (lo - 1, lo, lo - 1, hi),
+            Direction::Right => (hi + 1, lo, hi + 1, hi),
+        }
+    }
+
+    /// Progress where an approach ends (outer edge of its crosswalk)
+    pub fn approach_end(&self, dir: Direction) -> f32 {
+        let (lo, hi) = self.junction_edges();
+        let t = self.lane_width as f32;
+        match dir {
+            Direction::Up | Direction::Left => -(hi + t),
+            Direction::Down | Direction::Right => lo - t,
+        }
+    }
+
+    // ---------- Lanes ----------
+
+    /// Tile (column for vertical traffic, row for horizontal) of lane `index`
+    /// of the carriageway travelling `dir`; lane 0 runs along the median
+    pub fn lane_tile(&self, dir: Direction, index: i32) -> i32 {
+        match dir {
+            Direction::Up | Direction::Right => self.median_hi() + 1 + index,
+            Direction::Down | Direction::Left => self.median_lo() - 1 - index,
+        }
+    }
+
+    /// Pixel centerline of a lane tile
+    pub fn lane_center(&self, lane: i32) -> f32 {
+        (lane * self.lane_width + self.lane_width / 2) as f32
+    }
+
+    /// Entry lanes of an approach, from the median out to the curb
+    pub fn entry_lanes(&self, dir: Direction) -> Vec<i32> {
+        (0..self.approach(dir).lanes.len() as i32).map(|i| self.lane_tile(dir, i)).collect()
+    }
+
+    /// Entry lanes that run the full approach (vehicles can spawn in them)
+    pub fn spawn_lanes(&self, dir: Direction) -> Vec<i32> {
+        self.approach(dir)
+            .lanes
+            .iter()
+            .enumerate()
+            .filter(|(_, l)| !l.pocket)
+            .map(|(i, _)| self.lane_tile(dir, i as i32))
+            .collect()
+    }
+
+    fn lane_index(&self, dir: Direction, lane: i32) -> Option<usize> {
+        self.entry_lanes(dir).iter().position(|&l| l == lane)
+    }
+
+    pub fn lane_use(&self, dir: Direction, lane: i32) -> Option<LaneUse> {
+        self.lane_index(dir, lane).map(|i| self.approach(dir).lanes[i])
+    }
+
+    pub fn lane_allows(&self, dir: Direction, lane: i32, route: Route) -> bool {
+        self.lane_use(dir, lane).is_some_and(|u| u.allows(dir, route))
+    }
+
+    /// A lane exists at this progress along the approach (pockets only near the junction)
+    pub fn lane_open_at(&self, dir: Direction, lane: i32, progress: f32) -> bool {
+        match self.lane_use(dir, lane) {
+            Some(u) if u.pocket => progress >= self.pocket_start(dir),
+            Some(_) => true,
+            None => false,
+        }
+    }
+
+    /// Progress where turn pockets on an approach open
+    pub fn pocket_start(&self, dir: Direction) -> f32 {
+        self.approach_end(dir) - (POCKET_TILES * self.lane_width) as f32
+    }
+
+    /// Indices of the lanes that allow a route, median first
+    fn route_lane_indices(&self, dir: Direction, route: Route) -> Vec<usize> {
+        let lanes = &self.approach(dir).lanes;
+        let mut indices: Vec<usize> = (0..lanes.len()).filter(|&i| lanes[i].allows(dir, route)).collect();
+        if indices.is_empty() {
+            indices.push(0); // Validated approaches always serve every movement
+        }
+        indices
+    }
+
+    /// Lane allowing `route` closest to `lane`
+    pub fn route_lane_near(&self, dir: Direction, route: Route, lane: i32) -> i32 {
+        let here = self.lane_index(dir, lane).unwrap_or(0) as i32;
+        let best = self
+            .route_lane_indices(dir, route)
+            .into_iter()
+            .min_by_key(|&i| (i as i32 - here).abs())
+            .unwrap_or(0);
+        self.lane_tile(dir, best as i32)
+    }
+
+    /// Movement served by a lane, for a vehicle stuck in it: straight if allowed
+    pub fn route_for_lane(&self, dir: Direction, lane: i32) -> Route {
+        match self.lane_use(dir, lane) {
+            Some(u) if u.through => Route::Straight,
+            Some(u) if u.right => curb_route(dir),
+            Some(_) if curb_route(dir) == Route::Right => Route::Left,
+            Some(_) => Route::Right,
+            None => Route::Straight,
+        }
+    }
+
+    /// Lane a route leaves on, coming from `entry_lane`: parallel turning lanes
+    /// keep their order, so double turns end up side by side
+    pub fn exit_lane_tile(&self, dir: Direction, route: Route, entry_lane: i32) -> i32 {
+        let side = exit_side(dir, route);
+        let exits = self.exit_lane_count(side);
+        let entry = self.route_lane_near(dir, route, entry_lane);
+        let here = self.lane_index(dir, entry).unwrap_or(0);
+        let turning = self.route_lane_indices(dir, route);
+        let k = turning.iter().position(|&i| i == here).unwrap_or(0) as i32;
+
+        let index = match route {
+            Route::Straight => here as i32,
+            Route::UTurn => 0,
+            r if r == curb_route(dir) => exits - turning.len() as i32 + k,
+            _ => k,
+        };
+        self.lane_tile(side, index.clamp(0, exits - 1))
+    }
+
+    /// Outermost entry lane running the full approach (where cyclists ride)
+    pub fn curb_lane(&self, dir: Direction) -> i32 {
+        *self.spawn_lanes(dir).last().unwrap()
+    }
+
+    /// Outermost lane leaving the junction for traffic heading `dir`
+    pub fn exit_curb_lane(&self, dir: Direction) -> i32 {
+        self.lane_tile(dir, self.exit_lane_count(dir) - 1)
+    }
+
+    /// Exit lanes on the side traffic heading `dir` leaves through, from the median out
+    pub fn exit_lanes(&self, dir: Direction) -> Vec<i32> {
+        (0..self.exit_lane_count(dir)).map(|i| self.lane_tile(dir, i)).collect()
+    }
+
+    /// Where a movement from `entry_lane` is meant to leave the map
+    pub fn exit_lane(&self, dir: Direction, route: Route, entry_lane: i32) -> ExitLane {
+        ExitLane { side: exit_side(dir, route), lane: self.exit_lane_tile(dir, route, entry_lane) }
+    }
+
+    /// Map edge a position lies beyond (the furthest one past a corner)
+    pub fn edge_past(&self, x: f32, y: f32) -> Option<Direction> {
+        let size = self.pixel_size() as f32;
+        [(-y, Direction::Up), (y - size, Direction::Down), (-x, Direction::Left), (x - size, Direction::Right)]
+            .into_iter()
+            .filter(|(overshoot, _)| *overshoot > 0.0)
+            .max_by(|a, b| a.0.total_cmp(&b.0))
+            .map(|(_, side)| side)
+    }
+
+    /// Exit lane a position past the map edge lies on, if any
+    pub fn exit_lane_at(&self, x: f32, y: f32) -> Option<ExitLane> {
+        let side = self.edge_past(x, y)?;
+        let lateral = match side {
+            Direction::Up | Direction::Down => x,
+            Direction::Left | Direction::Right => y,
+        };
+        let lane = (lateral / self.lane_width as f32).floor() as i32;
+        self.exit_lanes(side).contains(&lane).then_some(ExitLane { side, lane })
+    }
+
+    // ---------- Paths ----------
+
+    /// Off-map tile coordinate (along the travel axis) where traffic heading `dir` appears
+    pub fn spawn_coord(&self, dir: Direction) -> i32 {
+        match dir {
+            Direction::Up | Direction::Left => self.grid_size() + 1,
+            Direction::Down | Direction::Right => -2,
+        }
+    }
+
+    /// Off-map tile coordinate where traffic heading `dir` disappears
+    pub fn despawn_coord(&self, dir: Direction) -> i32 {
+        self.spawn_coord(dir.opposite())
+    }
+
+    /// First junction tile along the direction of travel
+    pub fn box_entry(&self, dir: Direction) -> i32 {
+        let (lo, hi) = self.junction_tiles();
+        match dir {
+            Direction::Up | Direction::Left => hi,
+            Direction::Down | Direction::Right => lo,
+        }
+    }
+
+    /// Last junction tile along the direction of travel
+    pub fn box_exit(&self, dir: Direction) -> i32 {
+        self.box_entry(dir.opposite())
+    }
+
+    /// Waypoints through the cross junction from an entry lane:
+    /// [spawn, box entry, (turn point | box exit), exit]
+    pub fn cross_path(&self, dir: Direction, route: Route, entry: i32) -> Vec<(f32, f32)> {
+        let exit = self.exit_lane_tile(dir, route, entry);
+        let side = exit_side(dir, route);
+
+        let mut tiles = vec![
+            at(dir, entry, self.spawn_coord(dir)),
+            at(dir, entry, self.box_entry(dir)),
+        ];
+
+        match route {
+            Route::Straight => {
+                // Shift lanes inside the box if the far side has fewer
+                tiles.push(at(dir, exit, self.box_exit(dir)));
+                tiles.push(at(dir, exit, self.despawn_coord(dir)));
+            }
+            Route::UTurn => {
+                // Swing round through the median
+                let m = self.median_lo();
+                tiles.push(at(dir, entry, m));
+                tiles.push(at(dir, exit, m));
+                tiles.push(at(side, exit, self.despawn_coord(side)));
+            }
+            Route::Left | Route::Right => {
+                tiles.push(at(dir, entry, exit));
+                tiles.push(at(side, exit, self.despawn_coord(side)));
+            }
+        }
+
+        tiles.into_iter().map(|(x, y)| self.tile_center(x, y)).collect()
+    }
+
+    // ---------- Tile map ----------
+
+    /// Lane tiles at `along` on the carriageway heading `dir`, pockets included where open
+    fn road_lanes_at(&self, dir: Direction, along: i32) -> Vec<i32> {
+        let (lo, hi) = self.junction_tiles();
+        let upstream = match dir {
+            Direction::Up | Direction::Left => along > hi,
+            Direction::Down | Direction::Right => along < lo,
+        };
+
+        if upstream {
+            // Progress at the tile's upstream edge
+            let progress = match dir {
+                Direction::Up | Direction::Left => -((along + 1) * self.lane_width) as f32,
+                Direction::Down | Direction::Right => (along * self.lane_width) as f32,
+            };
+            self.entry_lanes(dir)
+                .into_iter()
+                .filter(|&l| self.lane_open_at(dir, l, progress))
+                .collect()
+        } else {
+            self.exit_lanes(dir)
+        }
+    }
+
+    /// The layout a loaded map was drawn for: this one if the map matches it,
+    /// otherwise the uniform layout (at this lane width) that generates it
+    pub fn for_tile_map(&self, map: &TileMap) -> Result<RoadGeometry, MapError> {
+        std::iter::once(self.clone())
+            .chain((1..=MAX_LANES).map(|n| RoadGeometry { lane_width: self.lane_width, ..RoadGeometry::new(n) }))
+            .find(|g| map.same_layout(&g.tile_map()))
+            .ok_or(MapError::NoLayout)
+    }
+
+    /// The default cross-shaped tile map for this geometry
+    pub fn tile_map(&self) -> TileMap {
+        let size = self.grid_size();
+        let (lo, hi) = self.junction_tiles();
+        let in_box = |c: i32| c >= lo && c <= hi;
+        let in_band = |c: i32| c >= lo - 1 && c <= hi + 1;
+
+        let mut tiles = Vec::with_capacity((size * size) as usize);
+        for y in 0..size {
+            for x in 0..size {
+                let vertical = [Direction::Up, Direction::Down]
+                    .iter()
+                    .any(|&d| self.road_lanes_at(d, y).contains(&x));
+                let horizontal = [Direction::Left, Direction::Right]
+                    .iter()
+                    .any(|&d| self.road_lanes_at(d, x).contains(&y));
+
+                let tile = if in_box(x) && in_box(y) {
+                    Tile::Intersection
+                } else if horizontal {
+                    Tile::HorizontalRoad
+                } else if vertical {
+                    Tile::VerticalRoad
+                } else if in_band(x) || in_band(y) {
+                    Tile::Pavement
+                } else {
+                    Tile::Grass
+                };
+                tiles.push(tile);
+            }
+        }
+
+        let mut map = TileMap::new(size, size, tiles);
+        for side in [Direction::Up, Direction::Down, Direction::Left, Direction::Right] {
+            let (x0, y0, x1, y1) = self.crosswalk_tiles(side);
+            for y in y0..=y1 {
+                for x in x0..=x1 {
+                    map.set(x, y, Tile::Crosswalk);
+                }
+            }
+        }
+        map
+    }
+}
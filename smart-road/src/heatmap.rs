@@ -0,0 +1,197 @@
+use sdl2::pixels::{Color, PixelFormatEnum};
+use sdl2::rect::Rect;
+use sdl2::render::{BlendMode, Canvas, RenderTarget};
+use sdl2::surface::Surface;
+use std::collections::HashSet;
+use std::fmt::Write as _;
+use std::path::Path;
+
+use crate::geometry::RoadGeometry;
+use crate::map::TileMap;
+use crate::render::save_png;
+use crate::spatial::Neighborhood;
+use crate::vehicle::{Vehicle, VEHICLE_HALF_LENGTH};
+
+const SLOW_SPEED: f32 = 30.0; // Dropping below this counts as a slowdown (px/s)
+const CLOSE_CALL_GAP: f32 = 4.0; // Bodies of moving vehicles closer than this (px)
+
+// =======================================================
+// 🔥 HEATMAP (occupancy, slowdowns, close calls)
+// =======================================================
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HeatLayer {
+    Occupancy,  // Vehicle-seconds spent in the cell
+    Slowdowns,  // Times a vehicle's speed dropped below SLOW_SPEED there
+    CloseCalls, // Pairs of vehicles that came within CLOSE_CALL_GAP there
+}
+
+impl HeatLayer {
+    pub const ALL: [HeatLayer; 3] = [HeatLayer::Occupancy, HeatLayer::Slowdowns, HeatLayer::CloseCalls];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            HeatLayer::Occupancy => "occupancy",
+            HeatLayer::Slowdowns => "slowdowns",
+            HeatLayer::CloseCalls => "close_calls",
+        }
+    }
+
+    /// Overlay key cycle: off → occupancy → slowdowns → close calls → off
+    pub fn cycle(layer: Option<HeatLayer>) -> Option<HeatLayer> {
+        match layer {
+            None => Some(HeatLayer::Occupancy),
+            Some(HeatLayer::Occupancy) => Some(HeatLayer::Slowdowns),
+            Some(HeatLayer::Slowdowns) => Some(HeatLayer::CloseCalls),
+            Some(HeatLayer::CloseCalls) => None,
+        }
+    }
+}
+
+pub struct Heatmap {
+    tile: i32,  // Tile size of the road layout (px)
+    cell: i32,  // 🔥 Half a tile across, enough to tell lanes and stop lines apart
+    width: i32, // Cells
+    height: i32,
+    occupancy: Vec<f32>,
+    slowdowns: Vec<u32>,
+    close_calls: Vec<u32>,
+    close_pairs: HashSet<(usize, usize)>, // Pairs currently too close, counted once
+}
+
+impl Heatmap {
+    /// Covers the road layout of this run
+    pub fn new(g: &RoadGeometry) -> Self {
+        let cell = g.lane_width / 2;
+        let cells = g.pixel_size() / cell;
+        let n = (cells * cells) as usize;
+        Self {
+            tile: g.lane_width,
+            cell,
+            width: cells,
+            height: cells,
+            occupancy: vec![0.0; n],
+            slowdowns: vec![0; n],
+            close_calls: vec![0; n],
+            close_pairs: HashSet::new(),
+        }
+    }
+
+    fn cell(&self, x: f32, y: f32) -> Option<usize> {
+        let (cx, cy) = ((x / self.cell as f32).floor() as i32, (y / self.cell as f32).floor() as i32);
+        (cx >= 0 && cy >= 0 && cx < self.width && cy < self.height).then(|| (cy * self.width + cx) as usize)
+    }
+
+    /// Accumulate one step: `before` is last step's state (as indexed by
+    /// `neighbors`), `after` the same vehicles once updated
+    pub fn record(&mut self, before: &[Vehicle], after: &[Vehicle], neighbors: Neighborhood, dt: f32) {
+        for (old, v) in before.iter().zip(after) {
+            let Some(i) = self.cell(v.x, v.y) else {
+                continue;
+            };
+            self.occupancy[i] += dt;
+            if old.speed >= SLOW_SPEED && v.speed < SLOW_SPEED {
+                self.slowdowns[i] += 1;
+            }
+        }
+
+        let mut close_now = HashSet::new();
+        for v in before {
+            for o in neighbors.within(v.x, v.y, VEHICLE_HALF_LENGTH * 3.0) {
+                let moving = v.speed > SLOW_SPEED || o.speed > SLOW_SPEED;
+                if o.id <= v.id || !moving || v.footprint_gap(o) >= CLOSE_CALL_GAP {
+                    continue;
+                }
+
+                let pair = (v.id, o.id);
+                if !self.close_pairs.contains(&pair) {
+                    if let Some(i) = self.cell((v.x + o.x) / 2.0, (v.y + o.y) / 2.0) {
+                        self.close_calls[i] += 1;
+                    }
+                }
+                close_now.insert(pair);
+            }
+        }
+        self.close_pairs = close_now;
+    }
+
+    fn value(&self, layer: HeatLayer, i: usize) -> f32 {
+        match layer {
+            HeatLayer::Occupancy => self.occupancy[i],
+            HeatLayer::Slowdowns => self.slowdowns[i] as f32,
+            HeatLayer::CloseCalls => self.close_calls[i] as f32,
+        }
+    }
+
+    /// Blue (rare) → yellow → red (hottest), scaled to the busiest cell
+    pub fn draw<T: RenderTarget>(&self, canvas: &mut Canvas<T>, layer: HeatLayer) {
+        let max = (0..self.occupancy.len()).map(|i| self.value(layer, i)).fold(0.0, f32::max);
+        if max <= 0.0 {
+            return;
+        }
+
+        canvas.set_blend_mode(BlendMode::Blend);
+        for cy in 0..self.height {
+            for cx in 0..self.width {
+                let v = self.value(layer, (cy * self.width + cx) as usize);
+                if v <= 0.0 {
+                    continue;
+                }
+                canvas.set_draw_color(gradient(v / max));
+                let rect = Rect::new(cx * self.cell, cy * self.cell, self.cell as u32, self.cell as u32);
+                canvas.fill_rect(rect).ok();
+            }
+        }
+        canvas.set_blend_mode(BlendMode::None);
+    }
+
+    /// Write heatmap.csv (all layers) and one PNG per layer over a plain map
+    pub fn export(&self, dir: &Path, map: &TileMap) -> Result<(), String> {
+        std::fs::create_dir_all(dir).map_err(|e| format!("cannot create {}: {}", dir.display(), e))?;
+
+        let mut csv = String::from("cell_x,cell_y,occupancy_s,slowdowns,close_calls\n");
+        for cy in 0..self.height {
+            for cx in 0..self.width {
+                let i = (cy * self.width + cx) as usize;
+                writeln!(csv, "{},{},{:.3},{},{}", cx, cy, self.occupancy[i], self.slowdowns[i], self.close_calls[i])
+                    .unwrap();
+            }
+        }
+        let csv_path = dir.join("heatmap.csv");
+        std::fs::write(&csv_path, csv).map_err(|e| format!("cannot write {}: {}", csv_path.display(), e))?;
+
+        let (w, h) = ((map.width * self.tile) as u32, (map.height * self.tile) as u32);
+        for layer in HeatLayer::ALL {
+            let mut canvas = Surface::new(w, h, PixelFormatEnum::RGB888)?.into_canvas()?;
+            draw_plain_map(&mut canvas, map, self.tile);
+            self.draw(&mut canvas, layer);
+            save_png(&canvas, &dir.join(format!("heatmap_{}.png", layer.name())))?;
+        }
+        Ok(())
+    }
+}
+
+fn gradient(t: f32) -> Color {
+    let t = t.clamp(0.0, 1.0);
+    let (r, g, b) = if t < 0.5 {
+        let k = t * 2.0;
+        (255.0 * k, 255.0 * k, 255.0 * (1.0 - k))
+    } else {
+        let k = (t - 0.5) * 2.0;
+        (255.0, 255.0 * (1.0 - k), 0.0)
+    };
+    Color::RGBA(r as u8, g as u8, b as u8, 160)
+}
+
+/// Road in grey, everything else dark, so the export needs no sprites
+fn draw_plain_map<T: RenderTarget>(canvas: &mut Canvas<T>, map: &TileMap, t: i32) {
+    for y in 0..map.height {
+        for x in 0..map.width {
+            let color = if map.get(x, y).is_road() { Color::RGB(90, 90, 90) } else { Color::RGB(30, 45, 30) };
+            canvas.set_draw_color(color);
+            canvas
+                .fill_rect(Rect::new(x * t, y * t, t as u32, t as u32))
+                .ok();
+        }
+    }
+}
@@ -0,0 +1,304 @@
+use sdl2::pixels::Color;
+use sdl2::rect::Rect;
+use sdl2::render::{Canvas, RenderTarget};
+use std::cmp::Ordering;
+use std::sync::Arc;
+
+use crate::geometry::RoadGeometry;
+use crate::spatial::Neighborhood;
+use crate::vehicle::{
+    build_junction_path, progress_along, Direction, Vehicle, QUEUE_GAP, VEHICLE_HALF_LENGTH,
+};
+
+// ↔️ Longitudinal distance covered while drifting over one lane
+const LANE_CHANGE_LENGTH: f32 = 60.0;
+
+// 🧮 MOBIL / IDM parameters (pixels, seconds)
+const IDM_COMFORT_DECEL: f32 = 200.0;
+const IDM_TIME_HEADWAY: f32 = 0.6;
+const MOBIL_SAFE_DECEL: f32 = 250.0; // Max braking imposed on the new follower
+const MOBIL_POLITENESS: f32 = 0.3;
+const MOBIL_THRESHOLD: f32 = 20.0;
+const MOBIL_MANDATORY_BIAS: f32 = 150.0; // Pull towards the lane our route needs, at the last chance to change
+const NEIGHBOR_RANGE: f32 = 300.0; // Leaders/followers further away than this don't matter
+
+/// An in-progress lane change; `waypoint` is the path index where it completes
+#[derive(Clone, Copy, Debug)]
+pub struct LaneChange {
+    pub from_lane: i32,
+    pub to_lane: i32,
+    pub waypoint: usize,
+}
+
+// =======================================================
+// 🛣️ WEAVING ZONE
+// =======================================================
+
+/// Lane changes are allowed from the map edge until the front bumper reaches the zebra
+pub fn weaving_zone(g: &RoadGeometry, dir: Direction) -> (f32, f32) {
+    let start = match dir {
+        Direction::Up | Direction::Left => -g.pixel_size() as f32,
+        Direction::Down | Direction::Right => 0.0,
+    };
+    (start, g.approach_end(dir) - VEHICLE_HALF_LENGTH)
+}
+
+/// Move a waypoint sideways onto the centerline of `lane`
+pub fn set_lane(g: &RoadGeometry, dir: Direction, point: &mut (f32, f32), lane: i32) {
+    let center = g.lane_center(lane);
+    match dir {
+        Direction::Up | Direction::Down => point.0 = center,
+        Direction::Left | Direction::Right => point.1 = center,
+    }
+}
+
+/// Paint a yellow line across each approach where the weaving zone ends
+pub fn draw_weaving_zones<T: RenderTarget>(canvas: &mut Canvas<T>, g: &RoadGeometry) {
+    let thickness = 3;
+
+    canvas.set_draw_color(Color::RGB(240, 200, 40));
+
+    for dir in [Direction::Up, Direction::Down, Direction::Left, Direction::Right] {
+        // Just outside the crosswalk's outer edge
+        let end = g.approach_end(dir);
+        let edge = end.abs() as i32;
+        let at = if end < 0.0 { edge } else { edge - thickness as i32 };
+
+        // Only across the approach's entry lanes
+        let lanes = g.entry_lanes(dir);
+        let first = *lanes.iter().min().unwrap() * g.lane_width;
+        let width = (lanes.len() as i32 * g.lane_width) as u32;
+        let rect = match dir {
+            Direction::Up | Direction::Down => Rect::new(first, at, width, thickness),
+            Direction::Left | Direction::Right => Rect::new(at, first, thickness, width),
+        };
+        canvas.fill_rect(rect).ok();
+    }
+}
+
+// =======================================================
+// 🧮 MOBIL GAP ACCEPTANCE
+// =======================================================
+
+/// IDM acceleration towards `cruise` speed with an optional leader (gap, leader speed)
+fn idm_acceleration(v: &Vehicle, speed: f32, leader: Option<(f32, f32)>) -> f32 {
+    let a_max = v.kind.acceleration();
+    let cruise = v.kind.speed(v.velocity_level).max(1.0);
+    let free = a_max * (1.0 - (speed / cruise).powi(4));
+
+    match leader {
+        None => free,
+        Some((gap, lead_speed)) => {
+            let dv = speed - lead_speed;
+            let desired = QUEUE_GAP
+                + speed * IDM_TIME_HEADWAY
+                + speed * dv / (2.0 * (a_max * IDM_COMFORT_DECEL).sqrt());
+            free - a_max * (desired.max(0.0) / gap.max(1.0)).powi(2)
+        }
+    }
+}
+
+/// Bumper-to-bumper gap between two vehicles on the same approach
+fn bumper_gap(rear: &Vehicle, front: &Vehicle, dir: Direction) -> f32 {
+    progress_along(dir, front.x, front.y)
+        - progress_along(dir, rear.x, rear.y)
+        - rear.half_length()
+        - front.half_length()
+}
+
+impl Vehicle {
+    /// Still upstream of the crosswalk on its entry road
+    pub fn is_approaching(&self) -> bool {
+        !self.entered_intersection
+            && progress_along(self.direction, self.x, self.y) < self.road.approach_end(self.direction)
+    }
+
+    /// Lane this vehicle occupies (or is moving into) on its approach
+    pub(crate) fn occupies_lane(&self, lane: i32) -> bool {
+        self.lane == lane || self.lane_change.is_some_and(|lc| lc.to_lane == lane)
+    }
+
+    /// Closest leader and follower in `lane` on our approach
+    fn lane_neighbors<'a>(&self, lane: i32, others: Neighborhood<'a>) -> (Option<&'a Vehicle>, Option<&'a Vehicle>) {
+        let me = progress_along(self.direction, self.x, self.y);
+        let mut leader: Option<(&Vehicle, f32)> = None;
+        let mut follower: Option<(&Vehicle, f32)> = None;
+
+        for o in others.in_lane(self.direction, lane, self.x, self.y, NEIGHBOR_RANGE) {
+            if o.id == self.id || o.in_bike_lane || !o.is_approaching() {
+                continue;
+            }
+
+            let d = progress_along(self.direction, o.x, o.y) - me;
+            if d >= 0.0 && leader.is_none_or(|(_, best)| d < best) {
+                leader = Some((o, d));
+            } else if d < 0.0 && follower.is_none_or(|(_, best)| d > best) {
+                follower = Some((o, d));
+            }
+        }
+
+        (leader.map(|(v, _)| v), follower.map(|(v, _)| v))
+    }
+
+    /// MOBIL: safety for the new follower plus an incentive, `bias` towards the route lane
+    fn mobil_accepts(&self, to_lane: i32, others: Neighborhood, bias: f32) -> bool {
+        let dir = self.direction;
+        let (old_leader, _) = self.lane_neighbors(self.lane, others);
+        let (new_leader, new_follower) = self.lane_neighbors(to_lane, others);
+
+        let lead = |v: Option<&Vehicle>| v.map(|l| (bumper_gap(self, l, dir), l.speed));
+
+        // Hard gap acceptance: never squeeze in bumper-to-bumper (human
+        // drivers have their own idea of what is enough)
+        let min_gap = QUEUE_GAP * self.gap_acceptance();
+        if new_leader.is_some_and(|l| bumper_gap(self, l, dir) < min_gap) {
+            return false;
+        }
+        if new_follower.is_some_and(|f| bumper_gap(f, self, dir) < min_gap) {
+            return false;
+        }
+
+        // Safety criterion: the new follower must not be forced to brake hard
+        let mut follower_gain = 0.0;
+        if let Some(f) = new_follower {
+            let after = idm_acceleration(f, f.speed, Some((bumper_gap(f, self, dir), self.speed)));
+            if after < -MOBIL_SAFE_DECEL / self.gap_acceptance() {
+                return false;
+            }
+            let before = idm_acceleration(f, f.speed, new_leader.map(|l| (bumper_gap(f, l, dir), l.speed)));
+            follower_gain = after - before;
+        }
+
+        // Incentive criterion
+        let own_gain = idm_acceleration(self, self.speed, lead(new_leader))
+            - idm_acceleration(self, self.speed, lead(old_leader));
+
+        own_gain + MOBIL_POLITENESS * follower_gain + bias > MOBIL_THRESHOLD
+    }
+
+    /// Someone else is already alongside us in the lane we are moving into
+    fn lane_change_blocked(&self, lc: &LaneChange, others: Neighborhood) -> bool {
+        let me = progress_along(self.direction, self.x, self.y);
+
+        others.in_lane(self.direction, lc.to_lane, self.x, self.y, NEIGHBOR_RANGE).any(|o| {
+            o.id != self.id
+                && !o.in_bike_lane
+                && o.is_approaching()
+                && (progress_along(self.direction, o.x, o.y) - me).abs()
+                    < self.half_length() + o.half_length() + QUEUE_GAP / 2.0
+        })
+    }
+
+    fn start_lane_change(&mut self, to_lane: i32) {
+        let dir = self.direction;
+        let me = progress_along(dir, self.x, self.y);
+
+        // New waypoint a little further ahead in the target lane
+        let mut point = match dir {
+            Direction::Up => (self.x, -(me + LANE_CHANGE_LENGTH)),
+            Direction::Down => (self.x, me + LANE_CHANGE_LENGTH),
+            Direction::Left => (-(me + LANE_CHANGE_LENGTH), self.y),
+            Direction::Right => (me + LANE_CHANGE_LENGTH, self.y),
+        };
+        set_lane(&self.road, dir, &mut point, to_lane);
+
+        let waypoint = self.current_target;
+        let path = Arc::make_mut(&mut self.path);
+        path.insert(waypoint, point);
+        // The end of the approach moves over with us
+        set_lane(&self.road, dir, &mut path[waypoint + 1], to_lane);
+
+        self.lane_change = Some(LaneChange { from_lane: self.lane, to_lane, waypoint });
+    }
+
+    fn abort_lane_change(&mut self, lc: LaneChange) {
+        let dir = self.direction;
+        let path = Arc::make_mut(&mut self.path);
+        set_lane(&self.road, dir, &mut path[lc.waypoint], lc.from_lane);
+        set_lane(&self.road, dir, &mut path[lc.waypoint + 1], lc.from_lane);
+
+        self.lane_change = None;
+        self.aborted_lane_changes += 1;
+    }
+
+    /// Rebuild the path from where we are for the current lane and route
+    fn replan_from_here(&mut self) {
+        let mut path = build_junction_path(&self.road, self.direction, self.route, self.junction, self.lane);
+        path[0] = (self.x, self.y);
+        self.path = Arc::new(path);
+        self.current_target = 1;
+        self.exit = self.road.exit_lane(self.direction, self.route, self.lane);
+    }
+
+    /// Out of weaving zone in the wrong lane: take whatever movement this lane serves
+    fn reroute_for_current_lane(&mut self) {
+        self.route = self.road.route_for_lane(self.direction, self.lane);
+        self.replan_from_here();
+        self.aborted_lane_changes += 1;
+    }
+
+    /// Advance lane-change state before moving along the path
+    pub fn update_lane_change(&mut self, others: Neighborhood) {
+        if self.is_bicycle() {
+            return;
+        }
+
+        if let Some(lc) = self.lane_change {
+            if self.current_target > lc.waypoint {
+                self.lane = lc.to_lane;
+                self.lane_change = None;
+                self.lane_changes += 1;
+                // Turn from the lane we ended up in
+                self.replan_from_here();
+            } else if self.lane_change_blocked(&lc, others) {
+                self.abort_lane_change(lc);
+            }
+            return;
+        }
+
+        let g = Arc::clone(&self.road);
+        if g.lane_allows(self.direction, self.lane, self.route) || !self.is_approaching() {
+            return;
+        }
+        let target_lane = g.route_lane_near(self.direction, self.route, self.lane);
+
+        let (zone_start, zone_end) = weaving_zone(&g, self.direction);
+        let me = progress_along(self.direction, self.x, self.y);
+
+        if me >= zone_end {
+            self.reroute_for_current_lane();
+            return;
+        }
+
+        // One lane at a time, towards the route lane
+        let lanes = g.entry_lanes(self.direction);
+        let (Some(here), Some(there)) = (
+            lanes.iter().position(|&l| l == self.lane),
+            lanes.iter().position(|&l| l == target_lane),
+        ) else {
+            return;
+        };
+        let next = match there.cmp(&here) {
+            Ordering::Greater => lanes.get(here + 1),
+            Ordering::Less => here.checked_sub(1).and_then(|i| lanes.get(i)),
+            Ordering::Equal => None,
+        };
+        let Some(&next) = next else {
+            return;
+        };
+
+        // The route lane pulls harder the less room is left: not at all where
+        // the zone starts (a change has to pay off by itself), fully at the
+        // last point one still fits
+        let last_start = zone_end - LANE_CHANGE_LENGTH;
+        let urgency = 1.0 - ((last_start - me) / (last_start - zone_start).max(1.0)).clamp(0.0, 1.0);
+
+        if me >= zone_start
+            && me < last_start
+            && g.lane_open_at(self.direction, next, me + LANE_CHANGE_LENGTH)
+            && self.mobil_accepts(next, others, MOBIL_MANDATORY_BIAS * urgency)
+        {
+            self.start_lane_change(next);
+        }
+    }
+}
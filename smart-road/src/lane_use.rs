@@ -0,0 +1,124 @@
+use crate::vehicle::{curb_route, Direction, Route};
+
+// =======================================================
+// 🚏 LANE-USE TABLES (per-approach lane configuration)
+// =======================================================
+//
+// An approach is written as its entry lanes from the median out to the curb,
+// then the number of lanes leaving the junction on the same arm:
+//
+//     L,T,TR/2    three lanes in (left, through, shared through+right), two out
+//     L,T,T,Rp/3  four lanes in with a right-turn pocket on the curb, three out
+//
+// Turns are from the driver's point of view (right-hand traffic). A trailing
+// `p` marks a turn pocket: a short lane that only opens near the junction.
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LaneUse {
+    pub left: bool,    // Turn across the median (also where U-turns start)
+    pub through: bool, // Straight on
+    pub right: bool,   // Turn towards the curb
+    pub pocket: bool,
+}
+
+impl LaneUse {
+    pub fn parse(spec: &str) -> Option<LaneUse> {
+        let (moves, pocket) = match spec.strip_suffix('p') {
+            Some(m) => (m, true),
+            None => (spec, false),
+        };
+
+        let mut lane = LaneUse { left: false, through: false, right: false, pocket };
+        for c in moves.chars() {
+            match c {
+                'L' => lane.left = true,
+                'T' => lane.through = true,
+                'R' => lane.right = true,
+                _ => return None,
+            }
+        }
+
+        (lane.left || lane.through || lane.right).then_some(lane)
+    }
+
+    /// Whether a vehicle on approach `dir` may take `route` from this lane
+    pub fn allows(self, dir: Direction, route: Route) -> bool {
+        match route {
+            Route::Straight => self.through,
+            Route::UTurn => self.left,
+            r if r == curb_route(dir) => self.right,
+            _ => self.left,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Approach {
+    pub lanes: Vec<LaneUse>, // Entry lanes, median first
+    pub exit_lanes: i32,     // Lanes leaving the junction on this arm
+}
+
+impl Approach {
+    /// `n` lanes each way: left on the median, right on the curb, through in between
+    pub fn uniform(n: i32) -> Self {
+        let specs: Vec<&str> = match n {
+            1 => vec!["LTR"],
+            2 => vec!["LT", "TR"],
+            _ => std::iter::once("L")
+                .chain(std::iter::repeat_n("T", (n - 2) as usize))
+                .chain(std::iter::once("R"))
+                .collect(),
+        };
+
+        Self {
+            lanes: specs.into_iter().filter_map(LaneUse::parse).collect(),
+            exit_lanes: n,
+        }
+    }
+
+    /// Parse "L,T,TR/2"; the exit lane count defaults to the number of entry lanes
+    pub fn parse(spec: &str, max_lanes: i32) -> Result<Approach, String> {
+        let (lanes_spec, exit_spec) = match spec.split_once('/') {
+            Some((l, e)) => (l, Some(e)),
+            None => (spec, None),
+        };
+
+        let lanes = lanes_spec
+            .split(',')
+            .map(|s| LaneUse::parse(s.trim()).ok_or(format!("bad lane '{}'", s.trim())))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let exit_lanes = match exit_spec {
+            Some(e) => e.trim().parse::<i32>().map_err(|_| format!("bad exit lane count '{}'", e))?,
+            None => lanes.len() as i32,
+        };
+
+        let approach = Approach { lanes, exit_lanes };
+        approach.validate(max_lanes)?;
+        Ok(approach)
+    }
+
+    fn validate(&self, max_lanes: i32) -> Result<(), String> {
+        let n = self.lanes.len() as i32;
+        if n > max_lanes || self.exit_lanes < 1 || self.exit_lanes > max_lanes {
+            return Err(format!("lane counts must be within 1..={}", max_lanes));
+        }
+        if !self.lanes.iter().any(|l| l.left) {
+            return Err("no lane allows left turns".into());
+        }
+        if !self.lanes.iter().any(|l| l.through) {
+            return Err("no lane allows going straight".into());
+        }
+        if !self.lanes.iter().any(|l| l.right) {
+            return Err("no lane allows right turns".into());
+        }
+        if self.lanes.iter().all(|l| l.pocket) {
+            return Err("at least one lane must run the full approach".into());
+        }
+        // A pocket in the middle would leave the lanes beyond it cut off
+        if n > 2 && self.lanes[1..n as usize - 1].iter().any(|l| l.pocket) {
+            return Err("turn pockets must be the median or the curb lane".into());
+        }
+        Ok(())
+    }
+}
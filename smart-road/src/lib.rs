@@ -0,0 +1,37 @@
+// =======================================================
+// 🚦 SMART ROAD - simulation library
+// =======================================================
+//
+// Everything except the window and event loop lives here so that benches,
+// tests and other front ends can drive the simulation directly.
+
+pub mod admission;
+pub mod api;
+pub mod bicycle;
+pub mod camera;
+pub mod config;
+pub mod debug;
+pub mod driver;
+pub mod env;
+pub mod geometry;
+pub mod heatmap;
+pub mod lane_change;
+pub mod lane_use;
+pub mod manager;
+pub mod map;
+pub mod network;
+pub mod pedestrian;
+pub mod perception;
+#[cfg(feature = "python")]
+pub mod python;
+pub mod render;
+pub mod replay;
+pub mod roundabout;
+pub mod safety;
+pub mod scenario;
+pub mod signal;
+pub mod simulation;
+pub mod spatial;
+pub mod stats;
+pub mod v2i;
+pub mod vehicle;
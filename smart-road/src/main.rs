@@ -1,161 +1,104 @@
 use sdl2::{event::Event, keyboard::Keycode};
+use std::path::Path;
 use std::time::{Duration, Instant};
-use rand::Rng;
-use rand::prelude::IndexedRandom;
-use sdl2::image::{InitFlag, LoadTexture};
-use std::path::PathBuf;
-use std::collections::HashMap;
-use sdl2::render::Texture;
-use sdl2::rect::Rect;
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+use sdl2::image::InitFlag;
 use sdl2::pixels::Color;
 use sdl2::render::Canvas;
-use crate::config::{TILE_SIZE, GRID_W, GRID_H, MID_TILE};
-
-mod intersection;
-mod vehicle;
-mod stats;
-mod config;
-
-pub const ROAD_HALF_TILES: i32 = 3;
-
-use stats::{Stats, show_stats_window};
-use intersection::*;
-use vehicle::{Vehicle, Direction, Route, VelocityLevel};
-
-#[derive(Clone, Copy, PartialEq)]
-enum Tile {
-    Grass,
-    Pavement,
-    VerticalRoad,
-    HorizontalRoad,
-    Intersection,
-}
-
-type CarTextures<'a> = HashMap<(usize, Direction), Texture<'a>>;
-
-fn build_map() -> [[Tile; GRID_W as usize]; GRID_H as usize] {
-    let mut map = [[Tile::Grass; GRID_W as usize]; GRID_H as usize];
-
-    let mid = GRID_W / 2;
-    let road_half: i32 = 3;
-
-    for y in 0..GRID_H {
-        for x in 0..GRID_W {
-            // Vertical road (3 left lanes + 3 right lanes)
-            if (x >= mid - 3 && x <= mid - 1) || (x >= mid + 1 && x <= mid + 3) {
-                map[y as usize][x as usize] = Tile::VerticalRoad;
-            }
 
-            // Horizontal road (3 top lanes + 3 bottom lanes)
-            if (y >= mid - 3 && y <= mid - 1) || (y >= mid + 1 && y <= mid + 3) {
-                map[y as usize][x as usize] = Tile::HorizontalRoad;
-            }
-
-            // Intersection
-            if (x >= mid - road_half && x <= mid + road_half)
-                && (y >= mid - road_half && y <= mid + road_half)
-            {
-                map[y as usize][x as usize] = Tile::Intersection;
-            }
+use smart_road::api::{self, ApiServer, RunControl};
+use smart_road::config::SimConfig;
+use smart_road::stats::{Stats, show_stats_window};
+use smart_road::vehicle::{Direction, VelocityLevel};
+use smart_road::camera::{Camera, CameraControl};
+use smart_road::network::{latency_sweep, print_latency_sweep, print_network_summary, RoadNetwork, SWEEP_LATENCIES_MS};
+use smart_road::map::TileMap;
+use smart_road::debug::draw_debug;
+use smart_road::heatmap::HeatLayer;
+use smart_road::render::{draw_scene, draw_selection, FrameExporter, SceneTextures};
+use smart_road::replay::Recording;
+use smart_road::safety::SafetyMonitor;
+use smart_road::simulation::{Command, Simulation, STEP};
+use smart_road::v2i::MessageBus;
+
+// 🎞️ Replay controls
+const SCRUB_STEPS: u64 = (10.0 / STEP) as u64; // 10 s per arrow press
+const MAX_LAG: f32 = 0.25; // Drop time rather than spiral after a stall
+const MAX_VIEW: u32 = 900; // Larger maps scroll inside a window this size
+const API_IDLE: Duration = Duration::from_millis(5); // Headless wait for API input while paused
+const NETWORK_V2I_FLAGS: [&str; 2] = ["--channel", "--latency-sweep"];
 
-            // Pavement ring
-            if map[y as usize][x as usize] == Tile::Grass {
-                if (x >= mid - road_half - 1 && x <= mid + road_half + 1)
-                    || (y >= mid - road_half - 1 && y <= mid + road_half + 1)
-                {
-                    map[y as usize][x as usize] = Tile::Pavement;
-                }
-            }
-        }
+fn main() {
+    // --junction cross|roundabout, --bike-lanes, --network RxC, --seed N, --map FILE,
+    // --lanes N, --approach up=L,T,TR/2 (repeatable), --lane-width PX, --headless, --duration S,
+    // --record FILE, --replay FILE, --frames DIR, --frame-interval S, --heatmap DIR,
+    // --abort-on-violation, --sensor-range PX, --sensor-fov DEG, --position-noise PX,
+    // --speed-noise PX/S, --occlusion, --av-penetration P, --api PORT
+    // V2I bus: --v2i-log FILE, --latency MS, --jitter MS, --packet-loss P,
+    // and with --network only: --channel C,R=LAT/JIT/LOSS (repeatable), --latency-sweep
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let mut config = SimConfig::from_arg_list(&args);
+
+    // The single junction has one V2I link and nothing to sweep
+    let v2i_flags: Vec<&str> = args.iter().map(String::as_str).filter(|a| NETWORK_V2I_FLAGS.contains(a)).collect();
+    if config.network.is_none() && !v2i_flags.is_empty() {
+        eprintln!("⚠️  {} only apply to --network runs (the single junction has one V2I link)", v2i_flags.join(", "));
     }
 
-    map
-}
-
-fn entry_lane_tile(dir: Direction, route: Route) -> i32 {
-    match dir {
-        Direction::Up => match route {
-            Route::Left => MID_TILE + 1,
-            Route::Straight => MID_TILE + 2,
-            Route::Right => MID_TILE + 3,
-        },
-        Direction::Down => match route {
-            Route::Left => MID_TILE - 3,
-            Route::Straight => MID_TILE - 2,
-            Route::Right => MID_TILE - 1,
-        },
-        Direction::Left => match route {
-            Route::Left => MID_TILE - 3,
-            Route::Straight => MID_TILE - 2,
-            Route::Right => MID_TILE - 1,
-        },
-        Direction::Right => match route {
-            Route::Left => MID_TILE + 1,
-            Route::Straight => MID_TILE + 2,
-            Route::Right => MID_TILE + 3,
-        },
+    // Recordings hold single-junction runs only
+    if config.network.is_some() && (config.record.is_some() || config.replay.is_some()) {
+        eprintln!("❌ --record and --replay cannot be used with --network");
+        std::process::exit(1);
     }
-}
-
-fn spawn_vehicle(vehicles: &mut Vec<Vehicle>, stats: &mut Stats, r: Route, dir: Direction) {
-    let lane_tile = entry_lane_tile(dir, r);
-
-    let (x, y): (f32, f32) = match dir {
-        Direction::Up => (
-            (lane_tile * TILE_SIZE + TILE_SIZE / 2) as f32,
-            (GRID_H * TILE_SIZE + 50) as f32,
-        ),
-        Direction::Down => (
-            (lane_tile * TILE_SIZE + TILE_SIZE / 2) as f32,
-            -50.0,
-        ),
-        Direction::Left => (
-            (GRID_W * TILE_SIZE + 50) as f32,
-            (lane_tile * TILE_SIZE + TILE_SIZE / 2) as f32,
-        ),
-        Direction::Right => (
-            -50.0,
-            (lane_tile * TILE_SIZE + TILE_SIZE / 2) as f32,
-        ),
-    };
 
-    // Check if spawn position is too close to existing vehicles
-    const MIN_SPAWN_DISTANCE: f32 = 120.0;
-    for existing in vehicles.iter() {
-        let dx = existing.x - x;
-        let dy = existing.y - y;
-        let distance = (dx * dx + dy * dy).sqrt();
-        
-        if distance < MIN_SPAWN_DISTANCE {
-            // Too close to spawn safely
-            return;
+    // A replay runs with the recorded seed and flags
+    let replay = config.replay.as_ref().map(|path| {
+        let mut rec = Recording::load(path).unwrap_or_else(|e| {
+            eprintln!("❌ Invalid recording {}: {}", path.display(), e);
+            std::process::exit(1);
+        });
+        // Every re-simulation (scrubbing included) stops on violations too
+        if config.abort_on_violation {
+            rec.args.push("--abort-on-violation".into());
         }
+        rec
+    });
+    if let Some(rec) = &replay {
+        let session = config;
+        config = rec.config();
+        config.headless = session.headless;
+        config.replay = session.replay;
+        config.record = session.record;
+        config.api = session.api;
     }
 
-    let car_id = rand::rng().random_range(1..=4);
-    let mut vehicle = Vehicle::new(dir, r, car_id);
+    let map = config.load_map().unwrap_or_else(|e| {
+        // Only a --map file can fail to load
+        eprintln!("❌ Invalid map {}: {}", config.map.as_ref().unwrap().display(), e);
+        std::process::exit(1);
+    });
 
-    if !vehicle.path.is_empty() {
-        vehicle.path[0] = (x, y);
-        vehicle.x = x;
-        vehicle.y = y;
+    if config.headless {
+        run_headless(config, replay.as_ref(), &args, &map);
+        return;
     }
 
-    vehicles.push(vehicle);
-    stats.total_vehicles += 1;
-}
-
-fn main() {
-    let mut vehicles: Vec<Vehicle> = vec![];
-    let mut stats = Stats::new();
+    let mut sim = match &replay {
+        Some(rec) => rec.simulate_to(0),
+        None => Simulation::new(config.clone()),
+    };
 
     // === SDL INIT ===
     let sdl = sdl2::init().unwrap();
     let video = sdl.video().unwrap();
     
     sdl2::image::init(InitFlag::PNG).unwrap();
+    let tile = config.road.lane_width;
+    let (world_w, world_h) = ((map.width * tile) as u32, (map.height * tile) as u32);
+    let (view_w, view_h) = (world_w.min(MAX_VIEW), world_h.min(MAX_VIEW));
     let window = video
-        .window("Smart Intersection - Autonomous Vehicles", 900, 900)
+        .window("Smart Intersection - Autonomous Vehicles", view_w, view_h)
         .position_centered()
         .build()
         .unwrap();
@@ -163,80 +106,60 @@ fn main() {
     let mut canvas = window.into_canvas().present_vsync().build().unwrap();
     let texture_creator = canvas.texture_creator();
 
-    let grass_tex = texture_creator
-        .load_texture("assets/grass.png")
-        .unwrap();
-
-    let pavement_tex = texture_creator
-        .load_texture("assets/roads/pavement.png")
-        .unwrap();
-
-    let verti_road_tex = texture_creator
-        .load_texture("assets/roads/vertical-road.png")
-        .unwrap();
-
-    let hori_road_tex = texture_creator
-        .load_texture("assets/roads/horizontal-road.png")
-        .unwrap();
-
-    let intersection_tex = texture_creator
-        .load_texture("assets/roads/intersection.png")
-        .unwrap();
-
-    let map = build_map();
-
-    // Load car textures
-    let mut car_textures: CarTextures = HashMap::new();
-
-    for car_id in 1..=4 {
-        for dir in [Direction::Up, Direction::Down, Direction::Left, Direction::Right] {
-            let filename = format!(
-                "car{}-{}.png",
-                car_id,
-                match dir {
-                    Direction::Up => "up",
-                    Direction::Down => "down",
-                    Direction::Left => "left",
-                    Direction::Right => "right",
-                }
-            );
-
-            let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
-                .join("assets")
-                .join(&filename);
-            let texture = texture_creator
-                .load_texture(&path)
-                .expect("Failed to load car texture");
-
-            car_textures.insert((car_id, dir), texture);
-        }
-    }
+    let textures = SceneTextures::load(&texture_creator).unwrap();
+    // The scene is drawn at world scale, then through the camera onto the window
+    let mut world = texture_creator.create_texture_target(None, world_w, world_h).unwrap();
+    let mut view = CameraControl::new(Camera::fit(0.0, 0.0, world_w as f32, world_h as f32, view_w, view_h), view_w, view_h);
 
     let mut events = sdl.event_pump().unwrap();
+    let mut api = start_api(&config, false);
 
-    let routes = [Route::Right, Route::Straight, Route::Left];
-    let mut rng = rand::rng();
+    if let Some((rows, cols)) = config.network {
+        run_network_mode(&mut canvas, &mut events, &mut sim, api.as_mut(), rows, cols);
+        exit_on_violation(&sim.safety);
+        return;
+    }
 
     let mut last_frame = Instant::now();
-    let mut last_spawn = Instant::now();
-    let mut auto_spawn = false;
+    let mut lag = 0.0;
+    let mut control = RunControl::default();
+    let mut heat_layer = None;
+    let mut show_debug = false;
+    let heatmap_dir = config.heatmap.as_deref().unwrap_or(Path::new("heatmap"));
 
     println!("\n🚗 AUTONOMOUS VEHICLE INTERSECTION SIMULATOR");
     println!("==========================================");
     println!("Controls:");
     println!("  Arrow Keys - Spawn vehicle from direction");
-    println!("  R - Toggle auto-spawn");
+    println!("  P - Spawn pedestrian at a random crosswalk");
+    println!("  U - Spawn U-turning vehicle from a random direction");
+    println!("  C - Spawn cyclist from a random direction");
+    println!("  R - Toggle auto-spawn (vehicles + pedestrians + cyclists)");
     println!("  1/2/3 - Set velocity level (Slow/Medium/Fast)");
+    println!("  H - Cycle heatmap overlay (occupancy/slowdowns/close calls/off)");
+    println!("  E - Export heatmaps (PNG + CSV)");
+    println!("  D - Toggle debug overlay (paths, look-ahead, leaders)");
+    println!("  Mouse wheel - Zoom   Drag - Pan   Click a vehicle - Follow it   Z - Reset view");
     println!("  ESC - Exit and show statistics");
+    if let Some(rec) = &replay {
+        println!("Replaying {:.1}s (inputs disabled):", rec.duration());
+        println!("  Space - Pause   ←/→ - Back/forward 10s   Home - Restart");
+    }
     println!("==========================================\n");
 
     'run: loop {
         let dt = last_frame.elapsed().as_secs_f32();
         last_frame = Instant::now();
-        stats.runtime += dt;
 
         // INPUT ------------------------------
         for evt in events.poll_iter() {
+            if let Some(point) = view.handle(&evt) {
+                view.following = view.pick(point, sim.vehicles.iter().map(|v| (v.id, v.x, v.y)));
+                if let Some(id) = view.following {
+                    println!("🎥 Following vehicle #{}", id);
+                }
+            }
+
             match evt {
                 Event::Quit { .. } => break 'run,
 
@@ -244,182 +167,432 @@ fn main() {
                     keycode: Some(Keycode::Escape),
                     ..
                 } => {
-                    show_stats_window(&stats);
+                    show_stats_window(&sim.stats);
                     break 'run;
                 }
 
-                Event::KeyDown {
-                    keycode: Some(Keycode::R),
-                    repeat: false,
-                    ..
-                } => {
-                    auto_spawn = !auto_spawn;
-                    println!("🔄 Auto-spawn {}", if auto_spawn { "ON" } else { "OFF" });
+                Event::KeyDown { keycode: Some(Keycode::H), repeat: false, .. } => {
+                    heat_layer = HeatLayer::cycle(heat_layer);
+                    println!("🔥 Heatmap: {}", heat_layer.map_or("off", HeatLayer::name));
                 }
 
-                // Velocity control keys
-                Event::KeyDown {
-                    keycode: Some(Keycode::Num1),
-                    repeat: false,
-                    ..
-                } => {
-                    for v in &mut vehicles {
-                        v.set_velocity_level(VelocityLevel::Slow);
-                    }
-                    println!("🐌 All vehicles set to SLOW");
+                Event::KeyDown { keycode: Some(Keycode::D), repeat: false, .. } => {
+                    show_debug = !show_debug;
                 }
 
-                Event::KeyDown {
-                    keycode: Some(Keycode::Num2),
-                    repeat: false,
-                    ..
-                } => {
-                    for v in &mut vehicles {
-                        v.set_velocity_level(VelocityLevel::Medium);
-                    }
-                    println!("🚗 All vehicles set to MEDIUM");
+                Event::KeyDown { keycode: Some(Keycode::E), repeat: false, .. } => {
+                    export_heatmap(&sim, heatmap_dir, &map);
                 }
 
-                Event::KeyDown {
-                    keycode: Some(Keycode::Num3),
-                    repeat: false,
-                    ..
-                } => {
-                    for v in &mut vehicles {
-                        v.set_velocity_level(VelocityLevel::Fast);
+                Event::KeyDown { keycode: Some(key), repeat: false, .. } => match &replay {
+                    // Replays take no inputs; the keys scrub through the recording instead
+                    Some(rec) => match key {
+                        Keycode::Space => control.paused = !control.paused,
+                        Keycode::Right => sim = rec.simulate_to(sim.steps + SCRUB_STEPS),
+                        Keycode::Left => sim = rec.simulate_to(sim.steps.saturating_sub(SCRUB_STEPS)),
+                        Keycode::Home => sim = rec.simulate_to(0),
+                        _ => {}
+                    },
+                    None => {
+                        if let Some(command) = key_command(key) {
+                            sim.apply(command);
+                            announce(command, &sim);
+                        }
                     }
-                    println!("🏎️ All vehicles set to FAST");
-                }
+                },
 
-                Event::KeyDown {
-                    keycode: Some(Keycode::Up),
-                    repeat: false,
-                    ..
-                } => {
-                    spawn_vehicle(
-                        &mut vehicles,
-                        &mut stats,
-                        routes[rng.random_range(0..3)],
-                        Direction::Up,
-                    );
-                }
-                Event::KeyDown {
-                    keycode: Some(Keycode::Down),
-                    repeat: false,
-                    ..
-                } => {
-                    spawn_vehicle(
-                        &mut vehicles,
-                        &mut stats,
-                        routes[rng.random_range(0..3)],
-                        Direction::Down,
-                    );
+                _ => {}
+            }
+        }
+
+        if let Some(api) = &mut api {
+            api.serve(|request| api::apply_to_simulation(&mut sim, &mut control, request, replay.is_some()));
+            if control.quit {
+                break 'run;
+            }
+        }
+
+        // Whole fixed steps only, so a recording replays identically
+        lag = (lag + dt).min(MAX_LAG);
+        while lag >= STEP {
+            lag -= STEP;
+            let ended = replay.as_ref().is_some_and(|rec| sim.steps >= rec.steps);
+            if ended || !control.take_step() {
+                continue;
+            }
+            match &replay {
+                Some(rec) => rec.advance(&mut sim),
+                None => sim.step(STEP),
+            }
+            if let Some(api) = &mut api {
+                api.tick(sim.steps, || api::simulation_tick(&sim));
+            }
+        }
+        if sim.safety.aborted() {
+            eprintln!("🛑 Stopping at the first safety violation (--abort-on-violation)");
+            break 'run;
+        }
+
+        // ================= RENDER =================
+        if let Some(id) = view.follow(|id| sim.vehicles.iter().find(|v| v.id == id).map(|v| (v.x, v.y))) {
+            println!("🎥 Vehicle #{} left the map", id);
+        }
+        canvas
+            .with_texture_canvas(&mut world, |scene| {
+                draw_scene(scene, &textures, &map, &sim);
+                if let Some(layer) = heat_layer {
+                    sim.heatmap.draw(scene, layer);
                 }
-                Event::KeyDown {
-                    keycode: Some(Keycode::Right),
-                    repeat: false,
-                    ..
-                } => {
-                    spawn_vehicle(
-                        &mut vehicles,
-                        &mut stats,
-                        routes[rng.random_range(0..3)],
-                        Direction::Right,
-                    );
+                if show_debug {
+                    draw_debug(scene, &sim.road, &sim.vehicles);
                 }
-                Event::KeyDown {
-                    keycode: Some(Keycode::Left),
-                    repeat: false,
-                    ..
-                } => {
-                    spawn_vehicle(
-                        &mut vehicles,
-                        &mut stats,
-                        routes[rng.random_range(0..3)],
-                        Direction::Left,
-                    );
+                if let Some(v) = view.following.and_then(|id| sim.vehicles.iter().find(|v| v.id == id)) {
+                    draw_selection(scene, v);
                 }
+            })
+            .unwrap();
+        canvas.set_draw_color(Color::RGB(0, 0, 0));
+        canvas.clear();
+        canvas.copy(&world, None, view.camera.rect(0.0, 0.0, world_w as f32, world_h as f32)).unwrap();
 
-                _ => {}
+        canvas.present();
+
+        std::thread::sleep(Duration::from_millis(16));
+    }
+
+    save_recording(&sim, &args);
+    if config.heatmap.is_some() {
+        export_heatmap(&sim, heatmap_dir, &map);
+    }
+    sim.finish();
+
+    println!("\n📊 Simulation finished.");
+    export_v2i_log(&sim.controller.bus, sim.config.v2i_log.as_deref());
+    sim.controller.print_summary();
+    show_stats_window(&sim.stats);
+    exit_on_violation(&sim.safety);
+}
+
+/// Input a key stands for in a live run
+fn key_command(key: Keycode) -> Option<Command> {
+    let command = match key {
+        Keycode::R => Command::ToggleAutoSpawn,
+        Keycode::Num1 => Command::SetVelocity(VelocityLevel::Slow),
+        Keycode::Num2 => Command::SetVelocity(VelocityLevel::Medium),
+        Keycode::Num3 => Command::SetVelocity(VelocityLevel::Fast),
+        Keycode::Up => Command::SpawnVehicle(Direction::Up),
+        Keycode::Down => Command::SpawnVehicle(Direction::Down),
+        Keycode::Right => Command::SpawnVehicle(Direction::Right),
+        Keycode::Left => Command::SpawnVehicle(Direction::Left),
+        Keycode::P => Command::SpawnPedestrian,
+        Keycode::C => Command::SpawnBicycle,
+        Keycode::U => Command::SpawnUTurn,
+        _ => return None,
+    };
+    Some(command)
+}
+
+fn announce(command: Command, sim: &Simulation) {
+    match command {
+        Command::ToggleAutoSpawn => {
+            println!("🔄 Auto-spawn {}", if sim.auto_spawn { "ON" } else { "OFF" })
+        }
+        Command::SetVelocity(VelocityLevel::Slow) => println!("🐌 All vehicles set to SLOW"),
+        Command::SetVelocity(VelocityLevel::Medium) => println!("🚗 All vehicles set to MEDIUM"),
+        Command::SetVelocity(VelocityLevel::Fast) => println!("🏎️ All vehicles set to FAST"),
+        _ => {}
+    }
+}
+
+fn export_heatmap(sim: &Simulation, dir: &Path, map: &TileMap) {
+    match sim.heatmap.export(dir, map) {
+        Ok(()) => println!("🔥 Heatmaps written to {}", dir.display()),
+        Err(e) => eprintln!("⚠️  Heatmap export failed: {}", e),
+    }
+}
+
+/// Write the run to --record FILE, if asked to
+fn save_recording(sim: &Simulation, args: &[String]) {
+    let Some(path) = &sim.config.record else {
+        return;
+    };
+    match Recording::capture(sim, args).save(path) {
+        Ok(()) => println!("🎞️  Recorded {} steps to {}", sim.steps, path.display()),
+        Err(e) => eprintln!("⚠️  {}", e),
+    }
+}
+
+/// Failing exit status for runs stopped by --abort-on-violation
+fn exit_on_violation(safety: &SafetyMonitor) {
+    if safety.aborted() {
+        std::process::exit(2);
+    }
+}
+
+/// Run without a window at a fixed time step, then print the statistics
+fn run_headless(config: SimConfig, replay: Option<&Recording>, args: &[String], map: &TileMap) {
+    let steps = (config.duration / STEP).round() as usize;
+    let started = Instant::now();
+    let (frames_dir, heatmap_dir) = (config.frames.clone(), config.heatmap.clone());
+    let mut api = start_api(&config, true);
+    let mut control = RunControl { paused: api.is_some(), ..RunControl::default() };
+
+    if let Some((rows, cols)) = config.network {
+        let mut stats = Stats::new();
+        let mut rng = match config.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_os_rng(),
+        };
+        if config.latency_sweep {
+            let latencies = SWEEP_LATENCIES_MS.map(|ms| ms / 1000.0);
+            warn_missing_junctions(&config, rows, cols);
+            let points = latency_sweep(&config, rows, cols, &latencies, rng.random());
+            println!("\n⏱️  {} runs in {:.2}s wall time", points.len(), started.elapsed().as_secs_f32());
+            print_latency_sweep(&points);
+            return;
+        }
+
+        let mut safety = SafetyMonitor::new(config.abort_on_violation);
+        let mut network = build_network(&config, rows, cols, rng.random());
+        match &mut api {
+            Some(api) => {
+                let (mut auto_spawn, mut since_spawn, mut step) = (true, 0.0, 0);
+                while step < steps as u64 && !control.quit && !safety.aborted() {
+                    api.serve(|request| {
+                        api::apply_to_network(&mut network, &mut control, &mut auto_spawn, &mut rng, &mut stats, request)
+                    });
+                    if !control.take_step() {
+                        std::thread::sleep(API_IDLE);
+                        continue;
+                    }
+                    step += 1;
+                    network.run_step(step, &mut since_spawn, auto_spawn, &mut rng, &mut stats, &mut safety);
+                    api.tick(step, || api::network_tick(&network, step, &stats));
+                }
             }
+            None => network.run(steps as u64, &mut rng, &mut stats, &mut safety),
         }
 
-        // AUTO SPAWN -------------------------
-        if auto_spawn && last_spawn.elapsed().as_secs_f32() > 0.8 {
-            let r = *routes.choose(&mut rng).unwrap();
-            let d = *[
-                Direction::Up,
-                Direction::Down,
-                Direction::Left,
-                Direction::Right,
-            ]
-            .choose(&mut rng)
-            .unwrap();
+        println!("\n⏱️  {} steps in {:.2}s wall time", steps, started.elapsed().as_secs_f32());
+        export_v2i_log(&network.bus, config.v2i_log.as_deref());
+        print_network_summary(&network);
+        show_stats_window(&stats);
+        exit_on_violation(&safety);
+        return;
+    }
 
-            spawn_vehicle(&mut vehicles, &mut stats, r, d);
-            last_spawn = Instant::now();
+    // Offscreen frames every --frame-interval seconds, if --frames DIR is given
+    let mut frames = frames_dir.as_ref().map(|dir| {
+        FrameExporter::new(map, config.road.lane_width, dir).unwrap_or_else(|e| {
+            eprintln!("❌ Cannot export frames: {}", e);
+            std::process::exit(1);
+        })
+    });
+    let texture_creator = frames.as_ref().map(FrameExporter::texture_creator);
+    let textures = texture_creator.as_ref().map(|c| SceneTextures::load(c).unwrap());
+    let frame_every = ((config.frame_interval / STEP).round() as u64).max(1);
+
+    let (mut sim, steps) = match replay {
+        Some(rec) => (rec.simulate_to(0), rec.steps),
+        None => {
+            let mut sim = Simulation::new(config);
+            sim.apply(Command::ToggleAutoSpawn);
+            (sim, steps as u64)
+        }
+    };
+
+    while sim.steps < steps && !sim.safety.aborted() && !control.quit {
+        if let Some(api) = &mut api {
+            api.serve(|request| api::apply_to_simulation(&mut sim, &mut control, request, replay.is_some()));
+            if !control.take_step() {
+                std::thread::sleep(API_IDLE);
+                continue;
+            }
+        }
+        match replay {
+            Some(rec) => rec.advance(&mut sim),
+            None => sim.step(STEP),
+        }
+        if let Some(api) = &mut api {
+            api.tick(sim.steps, || api::simulation_tick(&sim));
         }
 
-        // UPDATE VEHICLES with collision avoidance -------------------------
-        // Create a snapshot of all vehicles for collision checking
-        let vehicles_snapshot: Vec<Vehicle> = vehicles.clone();
-        
-        for v in &mut vehicles {
-            v.update(dt, &vehicles_snapshot);
+        if let (Some(exporter), Some(textures)) = (&mut frames, &textures) {
+            if sim.steps % frame_every == 0 {
+                if let Err(e) = exporter.export(textures, map, &sim) {
+                    eprintln!("⚠️  Frame export failed: {}", e);
+                }
+            }
         }
+    }
+    save_recording(&sim, args);
+    if let Some(dir) = &heatmap_dir {
+        export_heatmap(&sim, dir, map);
+    }
+    sim.finish();
 
-        // Remove out-of-bounds vehicles
-        vehicles.retain(|v| !v.is_out_of_bounds());
+    println!("\n⏱️  {} steps in {:.2}s wall time", sim.steps, started.elapsed().as_secs_f32());
+    if let (Some(exporter), Some(dir)) = (&frames, &frames_dir) {
+        println!("🖼️  {} frames written to {}", exporter.frames_written(), dir.display());
+    }
+    export_v2i_log(&sim.controller.bus, sim.config.v2i_log.as_deref());
+    sim.controller.print_summary();
+    show_stats_window(&sim.stats);
+    exit_on_violation(&sim.safety);
+}
 
-        // ================= RENDER =================
-        // Grid background
-        for y in 0..GRID_H {
-            for x in 0..GRID_W {
-                let tile = map[y as usize][x as usize];
-
-                let tex = match tile {
-                    Tile::Grass => &grass_tex,
-                    Tile::Pavement => &pavement_tex,
-                    Tile::VerticalRoad => &verti_road_tex,
-                    Tile::HorizontalRoad => &hori_road_tex,
-                    Tile::Intersection => &intersection_tex,
-                };
-
-                canvas
-                    .copy(
-                        tex,
-                        None,
-                        Rect::new(
-                            x * TILE_SIZE,
-                            y * TILE_SIZE,
-                            TILE_SIZE as u32,
-                            TILE_SIZE as u32,
-                        ),
-                    )
-                    .unwrap();
+/// Event loop for the multi-intersection network (whole network fitted to the window)
+fn run_network_mode(
+    canvas: &mut Canvas<sdl2::video::Window>,
+    events: &mut sdl2::EventPump,
+    sim: &mut Simulation,
+    mut api: Option<&mut ApiServer>,
+    rows: i32,
+    cols: i32,
+) {
+    let Simulation { config, stats, safety, rng, .. } = sim;
+    let v2i_log = config.v2i_log.as_deref();
+    let mut network = build_network(config, rows, cols, rng.random());
+    let (min_x, min_y, max_x, max_y) = network.bounds();
+    let (view_w, view_h) = canvas.output_size().unwrap();
+    let mut view = CameraControl::new(Camera::fit(min_x, min_y, max_x, max_y, view_w, view_h), view_w, view_h);
+
+    let mut last_frame = Instant::now();
+    let mut last_spawn = Instant::now();
+    let mut auto_spawn = true;
+    let mut steps = 0;
+    let mut control = RunControl::default();
+
+    println!("\n🕸️  NETWORK MODE - {}x{} intersections", rows, cols);
+    println!("==========================================");
+    println!("Controls:");
+    println!("  Space - Spawn vehicle with a random origin/destination");
+    println!("  R - Toggle auto-spawn");
+    println!("  1/2/3 - Set velocity level (Slow/Medium/Fast)");
+    println!("  Mouse wheel - Zoom   Drag - Pan   Click a vehicle - Follow it   Z - Reset view");
+    println!("  ESC - Exit and show statistics");
+    println!("==========================================\n");
+
+    'run: loop {
+        let dt = last_frame.elapsed().as_secs_f32();
+        last_frame = Instant::now();
+
+        for evt in events.poll_iter() {
+            if let Some(point) = view.handle(&evt) {
+                let positions = network.vehicles.values().map(|v| {
+                    let (x, y) = network.vehicle_position(v);
+                    (v.id, x, y)
+                });
+                view.following = view.pick(point, positions);
+                if let Some(id) = view.following {
+                    println!("🎥 Following vehicle #{}", id);
+                }
+            }
+
+            match evt {
+                Event::Quit { .. } => break 'run,
+                Event::KeyDown { keycode: Some(Keycode::Escape), .. } => break 'run,
+                Event::KeyDown { keycode: Some(Keycode::R), repeat: false, .. } => {
+                    auto_spawn = !auto_spawn;
+                    println!("🔄 Auto-spawn {}", if auto_spawn { "ON" } else { "OFF" });
+                }
+                Event::KeyDown { keycode: Some(Keycode::Space), repeat: false, .. } => {
+                    network.spawn_random(rng, stats);
+                }
+                Event::KeyDown { keycode: Some(Keycode::Num1), repeat: false, .. } => {
+                    network.velocity_level = VelocityLevel::Slow;
+                }
+                Event::KeyDown { keycode: Some(Keycode::Num2), repeat: false, .. } => {
+                    network.velocity_level = VelocityLevel::Medium;
+                }
+                Event::KeyDown { keycode: Some(Keycode::Num3), repeat: false, .. } => {
+                    network.velocity_level = VelocityLevel::Fast;
+                }
+                _ => {}
             }
         }
 
-        // Draw cars
-        for v in &vehicles {
-            v.draw(&mut canvas, &car_textures);
+        if let Some(api) = &mut api {
+            api.serve(|request| api::apply_to_network(&mut network, &mut control, &mut auto_spawn, rng, stats, request));
+            if control.quit {
+                break 'run;
+            }
         }
 
-        // Draw status info
-        draw_status_overlay(&mut canvas, &vehicles, &stats);
+        // Paused from the API: hold still (no clock, no spawning), or take the steps asked for
+        if !control.paused && auto_spawn && last_spawn.elapsed().as_secs_f32() > network.spawn_interval() {
+            network.spawn_random(rng, stats);
+            last_spawn = Instant::now();
+        }
+        let (dt, updates) = if control.paused { (STEP, control.pending_steps) } else { (dt, 1) };
+        control.pending_steps -= updates.min(control.pending_steps);
+        for _ in 0..updates {
+            stats.runtime += dt;
+            network.update(dt, stats);
+            steps += 1;
+            safety.check_network(steps, &network, stats);
+            if let Some(api) = &mut api {
+                api.tick(steps, || api::network_tick(&network, steps, stats));
+            }
+        }
+        if safety.aborted() {
+            eprintln!("🛑 Stopping at the first safety violation (--abort-on-violation)");
+            break 'run;
+        }
 
+        if let Some(id) = view.follow(|id| network.vehicles.get(&id).map(|v| network.vehicle_position(v))) {
+            println!("🎥 Vehicle #{} reached its destination", id);
+        }
+        network.draw(canvas, &view.camera, view.following);
         canvas.present();
 
         std::thread::sleep(Duration::from_millis(16));
     }
 
-    println!("\n📊 Simulation finished.");
-    show_stats_window(&stats);
+    export_v2i_log(&network.bus, v2i_log);
+    print_network_summary(&network);
+    show_stats_window(stats);
 }
 
-fn draw_status_overlay(canvas: &mut Canvas<sdl2::video::Window>, vehicles: &[Vehicle], stats: &Stats) {
-    // Draw semi-transparent overlay at top
-    canvas.set_draw_color(Color::RGBA(0, 0, 0, 180));
-    canvas.fill_rect(Rect::new(0, 0, 900, 80)).unwrap();
-    
-}
\ No newline at end of file
+/// Listen on --api PORT, if asked to. Headless runs start paused so a
+/// client can connect (and stream from step 1) before anything moves.
+fn start_api(config: &SimConfig, headless: bool) -> Option<ApiServer> {
+    let port = config.api?;
+    let server = ApiServer::on_port(port).unwrap_or_else(|e| {
+        eprintln!("❌ Cannot listen on port {}: {}", port, e);
+        std::process::exit(1);
+    });
+    let addr = server.local_addr().map_or(format!("port {}", port), |a| a.to_string());
+    if headless {
+        println!("🔌 Control API on {} (paused until a client sends {{\"cmd\":\"resume\"}})", addr);
+    } else {
+        println!("🔌 Control API on {}", addr);
+    }
+    Some(server)
+}
+
+/// Grid network set up from the flags, see `RoadNetwork::from_config`
+fn build_network(config: &SimConfig, rows: i32, cols: i32, seed: u64) -> RoadNetwork {
+    warn_missing_junctions(config, rows, cols);
+    RoadNetwork::from_config(config, rows, cols, seed)
+}
+
+/// --channel overrides for junctions the grid does not have are ignored
+fn warn_missing_junctions(config: &SimConfig, rows: i32, cols: i32) {
+    for &((c, r), _) in &config.channels {
+        if !(0..cols).contains(&c) || !(0..rows).contains(&r) {
+            eprintln!("⚠️  --channel {},{}: no such junction in a {}x{} network", c, r, rows, cols);
+        }
+    }
+}
+
+/// Write the V2I message log to --v2i-log FILE, if asked to
+fn export_v2i_log(bus: &MessageBus, path: Option<&Path>) {
+    let Some(path) = path else {
+        return;
+    };
+    match bus.export(path) {
+        Ok(()) => println!("📡 {} V2I messages written to {}", bus.log.len(), path.display()),
+        Err(e) => eprintln!("⚠️  V2I log export failed: {}", e),
+    }
+}
@@ -0,0 +1,138 @@
+use crate::vehicle::Direction;
+
+// =======================================================
+// 🚦 INTERSECTION MANAGER (reservation based)
+// =======================================================
+
+/// A movement through a 4-way junction, by the side it enters from and the
+/// side it leaves through (Up = north side, etc.)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Movement {
+    pub from: Direction,
+    pub to: Direction,
+}
+
+impl Movement {
+    pub fn new(from: Direction, to: Direction) -> Self {
+        Self { from, to }
+    }
+}
+
+/// Clockwise position of a side: north, east, south, west
+fn side_index(side: Direction) -> u8 {
+    match side {
+        Direction::Up => 0,
+        Direction::Right => 1,
+        Direction::Down => 2,
+        Direction::Left => 3,
+    }
+}
+
+/// Lane ends around the junction in clockwise order (right-hand traffic):
+/// each side has its entry lanes first, then its exit lanes.
+fn entry_point(side: Direction) -> u8 {
+    side_index(side) * 2
+}
+
+fn exit_point(side: Direction) -> u8 {
+    side_index(side) * 2 + 1
+}
+
+/// Strictly between `a` and `b` going clockwise around the junction
+fn between(a: u8, b: u8, p: u8) -> bool {
+    let span = (b + 8 - a) % 8;
+    let off = (p + 8 - a) % 8;
+    off > 0 && off < span
+}
+
+/// Two movements conflict if they merge into the same exit or their paths cross
+pub fn movements_conflict(a: Movement, b: Movement) -> bool {
+    if a.from == b.from {
+        return false; // Separate lanes of the same approach
+    }
+    if a.to == b.to {
+        return true; // Merge
+    }
+
+    let (a0, a1) = (entry_point(a.from), exit_point(a.to));
+    let (b0, b1) = (entry_point(b.from), exit_point(b.to));
+
+    // Chords of a circle cross iff exactly one endpoint of b lies between a's endpoints
+    between(a0, a1, b0) != between(a0, a1, b1)
+}
+
+#[derive(Clone, Debug)]
+pub struct Reservation {
+    pub vehicle: usize,
+    pub movement: Movement,
+    pub exclusive: bool, // Holds the whole junction, whatever the movement
+}
+
+/// First-come-first-served manager: a request is granted when no conflicting
+/// movement currently holds a reservation. Reservations are held until the
+/// vehicle has cleared the junction and releases them.
+#[derive(Clone, Debug, Default)]
+pub struct IntersectionManager {
+    pub reservations: Vec<Reservation>,
+    pub granted: u32,
+    pub rejected: u32,
+}
+
+impl IntersectionManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_authorized(&self, vehicle: usize) -> bool {
+        self.reservations.iter().any(|r| r.vehicle == vehicle)
+    }
+
+    /// Ask to cross; returns true if the vehicle holds a reservation afterwards
+    pub fn request(&mut self, vehicle: usize, movement: Movement) -> bool {
+        if self.is_authorized(vehicle) {
+            return true;
+        }
+
+        let blocked = self
+            .reservations
+            .iter()
+            .any(|r| r.exclusive || movements_conflict(r.movement, movement));
+
+        if blocked {
+            self.rejected += 1;
+            return false;
+        }
+
+        self.reservations.push(Reservation { vehicle, movement, exclusive: false });
+        self.granted += 1;
+        true
+    }
+
+    /// Hand the whole junction to one vehicle, once nobody else holds any of it
+    /// (for drivers the manager cannot coordinate with)
+    pub fn reserve_exclusive(&mut self, vehicle: usize, movement: Movement) -> bool {
+        if self.is_authorized(vehicle) {
+            return true;
+        }
+        if !self.reservations.is_empty() {
+            return false;
+        }
+
+        self.reservations.push(Reservation { vehicle, movement, exclusive: true });
+        self.granted += 1;
+        true
+    }
+
+    /// Note a road user crossing without asking (seen by the controller's own
+    /// sensors): conflicting requests wait until it is released
+    pub fn observe(&mut self, vehicle: usize, movement: Movement) {
+        if !self.is_authorized(vehicle) {
+            self.reservations.push(Reservation { vehicle, movement, exclusive: false });
+        }
+    }
+
+    /// Give up a reservation (after crossing, or when cancelling)
+    pub fn release(&mut self, vehicle: usize) {
+        self.reservations.retain(|r| r.vehicle != vehicle);
+    }
+}
@@ -0,0 +1,165 @@
+use std::fmt;
+use std::path::Path;
+
+// =======================================================
+// 🗺️ TILE MAP (loaded from a text map file)
+// =======================================================
+//
+// One character per tile, one line per row; lines starting with ';' are
+// comments. Without --map the cross layout is generated from the road
+// geometry; assets/maps/cross.txt is the same layout as a file. A loaded map
+// must be one the geometry generates: vehicles drive the geometry's lanes.
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Tile {
+    Grass,
+    Pavement,
+    VerticalRoad,
+    HorizontalRoad,
+    Intersection,
+    Crosswalk,
+    StopLine,
+}
+
+impl Tile {
+    fn from_char(c: char) -> Option<Tile> {
+        match c {
+            '.' => Some(Tile::Grass),
+            '#' => Some(Tile::Pavement),
+            '|' => Some(Tile::VerticalRoad),
+            '-' => Some(Tile::HorizontalRoad),
+            '+' => Some(Tile::Intersection),
+            '=' => Some(Tile::Crosswalk),
+            's' => Some(Tile::StopLine),
+            _ => None,
+        }
+    }
+
+    pub fn is_road(self) -> bool {
+        !matches!(self, Tile::Grass | Tile::Pavement)
+    }
+}
+
+#[derive(Debug)]
+pub enum MapError {
+    Io(std::io::Error),
+    Empty,
+    RaggedRow { line: usize, width: usize, expected: usize },
+    UnknownTile { line: usize, column: usize, found: char },
+    NoRoad,
+    NoLayout, // Not drawn for the configured road layout or any uniform one
+}
+
+impl fmt::Display for MapError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MapError::Io(e) => write!(f, "cannot read map file: {}", e),
+            MapError::Empty => write!(f, "map has no tile rows"),
+            MapError::RaggedRow { line, width, expected } => write!(
+                f,
+                "line {}: row is {} tiles wide, expected {}",
+                line, width, expected
+            ),
+            MapError::UnknownTile { line, column, found } => {
+                write!(f, "line {}, column {}: unknown tile '{}'", line, column, found)
+            }
+            MapError::NoRoad => write!(f, "map contains no road tiles"),
+            MapError::NoLayout => write!(
+                f,
+                "map does not match the configured road layout (--lanes, --approach) or any uniform one"
+            ),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct TileMap {
+    pub width: i32,
+    pub height: i32,
+    tiles: Vec<Tile>,
+}
+
+impl TileMap {
+    pub fn new(width: i32, height: i32, tiles: Vec<Tile>) -> Self {
+        assert_eq!(tiles.len(), (width * height) as usize, "tile count must match map size");
+        Self { width, height, tiles }
+    }
+
+    pub fn load(path: &Path) -> Result<TileMap, MapError> {
+        let text = std::fs::read_to_string(path).map_err(MapError::Io)?;
+        TileMap::parse(&text)
+    }
+
+    pub fn parse(text: &str) -> Result<TileMap, MapError> {
+        let mut tiles = Vec::new();
+        let mut width = 0;
+        let mut height = 0;
+
+        for (i, raw) in text.lines().enumerate() {
+            let line = i + 1;
+            let row = raw.trim_end();
+            if row.is_empty() || row.starts_with(';') {
+                continue;
+            }
+
+            let row_width = row.chars().count();
+            if height == 0 {
+                width = row_width;
+            } else if row_width != width {
+                return Err(MapError::RaggedRow { line, width: row_width, expected: width });
+            }
+
+            for (col, c) in row.chars().enumerate() {
+                let tile = Tile::from_char(c).ok_or(MapError::UnknownTile {
+                    line,
+                    column: col + 1,
+                    found: c,
+                })?;
+                tiles.push(tile);
+            }
+            height += 1;
+        }
+
+        if height == 0 {
+            return Err(MapError::Empty);
+        }
+        if !tiles.iter().any(|t| t.is_road()) {
+            return Err(MapError::NoRoad);
+        }
+
+        Ok(TileMap { width: width as i32, height, tiles })
+    }
+
+    /// Tile at (x, y); anything outside the map is grass
+    pub fn get(&self, x: i32, y: i32) -> Tile {
+        if x < 0 || y < 0 || x >= self.width || y >= self.height {
+            return Tile::Grass;
+        }
+        self.tiles[(y * self.width + x) as usize]
+    }
+
+    pub fn set(&mut self, x: i32, y: i32, tile: Tile) {
+        if x >= 0 && y >= 0 && x < self.width && y < self.height {
+            self.tiles[(y * self.width + x) as usize] = tile;
+        }
+    }
+
+    /// Same roads in the same places; a stop line stands in for the road tile it is painted on
+    pub fn same_layout(&self, other: &TileMap) -> bool {
+        let matches = |a: Tile, b: Tile| {
+            a == b
+                || (a == Tile::StopLine && matches!(b, Tile::VerticalRoad | Tile::HorizontalRoad))
+                || (b == Tile::StopLine && matches!(a, Tile::VerticalRoad | Tile::HorizontalRoad))
+        };
+        self.width == other.width
+            && self.height == other.height
+            && self.tiles.iter().zip(&other.tiles).all(|(&a, &b)| matches(a, b))
+    }
+
+    /// A stop line spans the road it sits on: across a vertical road it is
+    /// drawn horizontally
+    pub fn stop_line_is_horizontal(&self, x: i32, y: i32) -> bool {
+        matches!(self.get(x, y - 1), Tile::VerticalRoad)
+            || matches!(self.get(x, y + 1), Tile::VerticalRoad)
+    }
+}
@@ -0,0 +1,1038 @@
+use sdl2::pixels::Color;
+use sdl2::render::Canvas;
+use sdl2::video::Window;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BinaryHeap, VecDeque};
+
+use crate::camera::Camera;
+use crate::config::SimConfig;
+use crate::driver::{Autonomy, HumanDriver};
+use crate::manager::{IntersectionManager, Movement};
+use crate::safety::SafetyMonitor;
+use crate::simulation::STEP;
+use crate::stats::Stats;
+use crate::v2i::{Endpoint, Lease, Message, MessageBus, Payload, HEARTBEAT_INTERVAL};
+use crate::vehicle::{Direction, VelocityLevel};
+
+// =======================================================
+// 🗺️ NETWORK GEOMETRY (world units ≈ pixels of the single-crossing view)
+// =======================================================
+
+const BLOCK_LENGTH: f32 = 320.0; // Center-to-center distance between intersections
+const STUB_LENGTH: f32 = 200.0; // Distance from a gateway to its intersection
+const JUNCTION_SIZE: f32 = 60.0;
+const LANE_OFFSET: f32 = 8.0; // Each direction drives right of the link centerline
+
+// 🚗 Link-level vehicle dynamics
+const NET_VEHICLE_LENGTH: f32 = 20.0;
+const NET_MIN_GAP: f32 = 8.0;
+const NET_ACCELERATION: f32 = 150.0;
+const NET_DECELERATION: f32 = 250.0;
+const REQUEST_DISTANCE: f32 = 70.0; // Ask the junction manager this far from the stop line
+const MIN_CROSSING_SPEED: f32 = 30.0;
+
+// 📡 V2I protocol timing
+const REQUEST_RETRY: f32 = 0.25; // Wait after a reject before asking again
+const REQUEST_TIMEOUT: f32 = 1.0; // Ask again if neither grant nor reject arrived by then
+const GRANT_LEASE: f32 = 3.0; // A grant is only good for entering this long after it was issued
+const CROSSING_ALLOWANCE: f32 = JUNCTION_SIZE / MIN_CROSSING_SPEED + 0.5; // Slowest crossing, with a margin
+const ADVISORY_FACTOR: f32 = 0.5; // Advised approach speed (of cruise) while waiting
+
+// 🧑 Human drivers
+const HUMAN_GAP: f32 = 2.0; // Seconds before the next car reaches the junction (times their gap acceptance)
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NodeKind {
+    Intersection,
+    Gateway, // Network boundary: vehicles originate and terminate here
+}
+
+#[derive(Clone, Debug)]
+pub struct Node {
+    pub kind: NodeKind,
+    pub x: f32,
+    pub y: f32,
+    pub manager: IntersectionManager,
+    pub leases: BTreeMap<usize, Lease>, // Reservation holders, by vehicle
+    pub humans: Vec<(usize, Movement)>, // Human drivers seen waiting at the stop lines
+}
+
+impl Node {
+    fn half_size(&self) -> f32 {
+        match self.kind {
+            NodeKind::Intersection => JUNCTION_SIZE / 2.0,
+            NodeKind::Gateway => 0.0,
+        }
+    }
+}
+
+/// One direction of a road between two nodes (a single lane)
+#[derive(Clone, Debug)]
+pub struct Link {
+    pub from: usize,
+    pub to: usize,
+    pub heading: Direction,
+    pub length: f32,
+    pub vehicles: VecDeque<usize>, // Front = most downstream
+    pub incoming: usize,           // Vehicles granted into this link, still crossing
+}
+
+impl Link {
+    pub fn capacity(&self) -> usize {
+        (self.length / (NET_VEHICLE_LENGTH + NET_MIN_GAP)) as usize
+    }
+
+    /// Room left for another vehicle (spillback once this is false)
+    pub fn has_space(&self) -> bool {
+        self.vehicles.len() + self.incoming < self.capacity()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NetState {
+    Driving,
+    Crossing { remaining: f32, total: f32 },
+}
+
+/// A vehicle travelling over links from an origin gateway to a destination gateway
+#[derive(Clone, Debug)]
+pub struct NetVehicle {
+    pub id: usize,
+    pub destination: usize,
+    pub links: Vec<usize>, // Shortest-path route
+    pub leg: usize,        // Index into `links`
+    pub pos: f32,          // Front bumper position along the current link
+    pub speed: f32,
+    pub state: NetState,
+    pub granted: bool, // Holds a reservation at the next junction
+    pub blocked: bool, // Waiting because the next link is full (spillback)
+    pub spawned_at: f32,
+
+    // 📡 V2I state
+    pub requested: bool,           // Request sent, no answer yet
+    pub retry_at: f32,             // No new request before this time
+    pub asked_at: Option<f32>,     // First request to the junction ahead
+    pub grant_until: f32,          // Entry deadline of the grant held
+    pub cancelled_at: f32,         // Last Cancel sent (grants for older requests are void)
+    pub advisory: Option<f32>,     // Speed advised by the junction ahead
+    pub last_heartbeat: f32,
+
+    // 🧑 Human at the wheel (None: autonomous, talks V2I)
+    pub driver: Option<HumanDriver>,
+    pub go_at: Option<f32>, // When they pull away from the stop line, once decided
+}
+
+impl NetVehicle {
+    pub fn autonomy(&self) -> Autonomy {
+        if self.driver.is_some() { Autonomy::Human } else { Autonomy::Autonomous }
+    }
+}
+
+fn unit(dir: Direction) -> (f32, f32) {
+    match dir {
+        Direction::Up => (0.0, -1.0),
+        Direction::Down => (0.0, 1.0),
+        Direction::Left => (-1.0, 0.0),
+        Direction::Right => (1.0, 0.0),
+    }
+}
+
+/// Dijkstra queue entry (min-heap on cost)
+struct Frontier {
+    cost: f32,
+    link: usize,
+}
+
+impl PartialEq for Frontier {
+    fn eq(&self, other: &Self) -> bool {
+        self.cost == other.cost
+    }
+}
+
+impl Eq for Frontier {}
+
+impl PartialOrd for Frontier {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Frontier {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.cost.total_cmp(&self.cost)
+    }
+}
+
+// =======================================================
+// 🕸️ ROAD NETWORK
+// =======================================================
+
+pub struct RoadNetwork {
+    pub rows: i32,
+    pub cols: i32,
+    pub nodes: Vec<Node>,
+    pub links: Vec<Link>,
+    pub gateways: Vec<usize>,
+    pub vehicles: BTreeMap<usize, NetVehicle>,
+    pub velocity_level: VelocityLevel,
+    pub time: f32,
+    pub bus: MessageBus,
+    pub av_penetration: f32, // Share of spawned cars that are autonomous
+    next_id: usize,
+}
+
+impl RoadNetwork {
+    /// A `rows` x `cols` grid of intersections with gateways on every boundary road
+    pub fn grid(rows: i32, cols: i32) -> Self {
+        let mut nodes = Vec::new();
+        let mut index = BTreeMap::new();
+
+        let pos = |c: i32, r: i32| {
+            (
+                STUB_LENGTH + c as f32 * BLOCK_LENGTH,
+                STUB_LENGTH + r as f32 * BLOCK_LENGTH,
+            )
+        };
+
+        for r in 0..rows {
+            for c in 0..cols {
+                let (x, y) = pos(c, r);
+                index.insert((c, r), nodes.len());
+                nodes.push(Node {
+                    kind: NodeKind::Intersection,
+                    x,
+                    y,
+                    manager: IntersectionManager::new(),
+                    leases: BTreeMap::new(),
+                    humans: Vec::new(),
+                });
+            }
+        }
+
+        // Gateways one stub beyond every boundary intersection
+        let mut gateways = Vec::new();
+        let mut add_gateway = |nodes: &mut Vec<Node>, c: i32, r: i32, x: f32, y: f32| {
+            index.insert((c, r), nodes.len());
+            gateways.push(nodes.len());
+            nodes.push(Node {
+                kind: NodeKind::Gateway,
+                x,
+                y,
+                manager: IntersectionManager::new(),
+                leases: BTreeMap::new(),
+                humans: Vec::new(),
+            });
+        };
+        for c in 0..cols {
+            let (x, _) = pos(c, 0);
+            add_gateway(&mut nodes, c, -1, x, 0.0);
+            add_gateway(&mut nodes, c, rows, x, pos(0, rows - 1).1 + STUB_LENGTH);
+        }
+        for r in 0..rows {
+            let (_, y) = pos(0, r);
+            add_gateway(&mut nodes, -1, r, 0.0, y);
+            add_gateway(&mut nodes, cols, r, pos(cols - 1, 0).0 + STUB_LENGTH, y);
+        }
+
+        // Two links (one per direction) between every pair of adjacent nodes
+        let mut links = Vec::new();
+        for (&(c, r), &a) in index.iter() {
+            for (dc, dr) in [(1, 0), (0, 1)] {
+                let Some(&b) = index.get(&(c + dc, r + dr)) else {
+                    continue;
+                };
+                if nodes[a].kind == NodeKind::Gateway && nodes[b].kind == NodeKind::Gateway {
+                    continue;
+                }
+                let forward = if dc == 1 { Direction::Right } else { Direction::Down };
+                links.push(Self::make_link(&nodes, a, b, forward));
+                links.push(Self::make_link(&nodes, b, a, forward.opposite()));
+            }
+        }
+
+        Self {
+            rows,
+            cols,
+            nodes,
+            links,
+            gateways,
+            vehicles: BTreeMap::new(),
+            velocity_level: VelocityLevel::Medium,
+            time: 0.0,
+            bus: MessageBus::new(0),
+            av_penetration: 1.0,
+            next_id: 1,
+        }
+    }
+
+    fn make_link(nodes: &[Node], from: usize, to: usize, heading: Direction) -> Link {
+        let (a, b) = (&nodes[from], &nodes[to]);
+        let distance = (b.x - a.x).abs() + (b.y - a.y).abs();
+
+        Link {
+            from,
+            to,
+            heading,
+            length: distance - a.half_size() - b.half_size(),
+            vehicles: VecDeque::new(),
+            incoming: 0,
+        }
+    }
+
+    /// World bounds (min_x, min_y, max_x, max_y) for fitting the camera
+    pub fn bounds(&self) -> (f32, f32, f32, f32) {
+        let max_x = self.nodes.iter().map(|n| n.x).fold(0.0, f32::max);
+        let max_y = self.nodes.iter().map(|n| n.y).fold(0.0, f32::max);
+        (0.0, 0.0, max_x, max_y)
+    }
+
+    /// Grid whose V2I links follow --latency, --jitter, --packet-loss and
+    /// --channel (overrides for junctions it lacks are left out), with
+    /// --av-penetration of the cars autonomous; `seed` drives the links
+    pub fn from_config(config: &SimConfig, rows: i32, cols: i32, seed: u64) -> Self {
+        let mut network = Self::grid(rows, cols);
+        network.bus = MessageBus::new(seed);
+        network.bus.channel = config.channel;
+        network.bus.logging = config.v2i_log.is_some();
+        network.av_penetration = config.av_penetration;
+        for &((c, r), channel) in &config.channels {
+            if let Some(junction) = network.junction_at(c, r) {
+                network.bus.channels.insert(junction, channel);
+            }
+        }
+        network
+    }
+
+    /// Node index of the intersection in column `c`, row `r`
+    pub fn junction_at(&self, c: i32, r: i32) -> Option<usize> {
+        ((0..self.cols).contains(&c) && (0..self.rows).contains(&r)).then_some((r * self.cols + c) as usize)
+    }
+
+    fn cruise_speed(&self) -> f32 {
+        self.velocity_level.to_speed()
+    }
+
+    /// Movement at the junction between two consecutive links
+    fn movement(&self, in_link: usize, out_link: usize) -> Movement {
+        Movement::new(self.links[in_link].heading.opposite(), self.links[out_link].heading)
+    }
+
+    // ---------------------------------------------------
+    // 🧭 Routing
+    // ---------------------------------------------------
+
+    /// Shortest route (free-flow travel time) as a list of links, without U-turns
+    pub fn shortest_path(&self, origin: usize, destination: usize) -> Option<Vec<usize>> {
+        let junction_time = JUNCTION_SIZE / self.cruise_speed();
+        let link_time = |l: usize| self.links[l].length / self.cruise_speed();
+
+        let mut best = vec![f32::MAX; self.links.len()];
+        let mut previous = vec![usize::MAX; self.links.len()];
+        let mut heap = BinaryHeap::new();
+
+        for l in (0..self.links.len()).filter(|&l| self.links[l].from == origin) {
+            best[l] = link_time(l);
+            heap.push(Frontier { cost: best[l], link: l });
+        }
+
+        while let Some(Frontier { cost, link }) = heap.pop() {
+            if cost > best[link] {
+                continue;
+            }
+
+            if self.links[link].to == destination {
+                let mut route = vec![link];
+                while previous[*route.last().unwrap()] != usize::MAX {
+                    route.push(previous[*route.last().unwrap()]);
+                }
+                route.reverse();
+                return Some(route);
+            }
+
+            let here = &self.links[link];
+            if self.nodes[here.to].kind == NodeKind::Gateway {
+                continue; // Vehicles cannot pass through the boundary
+            }
+
+            for (next, out) in self.links.iter().enumerate() {
+                if out.from != here.to || out.to == here.from {
+                    continue;
+                }
+                let c = cost + junction_time + link_time(next);
+                if c < best[next] {
+                    best[next] = c;
+                    previous[next] = link;
+                    heap.push(Frontier { cost: c, link: next });
+                }
+            }
+        }
+
+        None
+    }
+
+    // ---------------------------------------------------
+    // 🚗 Spawning
+    // ---------------------------------------------------
+
+    /// Spawn a vehicle between two gateways, autonomous unless it has a human
+    /// `driver`; fails if the entry link is backed up
+    pub fn spawn(&mut self, origin: usize, destination: usize, driver: Option<HumanDriver>, stats: &mut Stats) -> bool {
+        let Some(links) = self.shortest_path(origin, destination) else {
+            return false;
+        };
+
+        let first = &self.links[links[0]];
+        let entry_clear = first
+            .vehicles
+            .back()
+            .is_none_or(|id| self.vehicles[id].pos > NET_VEHICLE_LENGTH + NET_MIN_GAP);
+
+        if !first.has_space() || !entry_clear {
+            stats.network_spawn_blocked += 1;
+            return false;
+        }
+
+        let id = self.next_id;
+        self.next_id += 1;
+        self.links[links[0]].vehicles.push_back(id);
+        self.vehicles.insert(
+            id,
+            NetVehicle {
+                id,
+                destination,
+                links,
+                leg: 0,
+                pos: 0.0,
+                speed: self.cruise_speed(),
+                state: NetState::Driving,
+                granted: false,
+                blocked: false,
+                spawned_at: self.time,
+                requested: false,
+                retry_at: 0.0,
+                asked_at: None,
+                grant_until: 0.0,
+                cancelled_at: f32::MIN,
+                advisory: None,
+                last_heartbeat: self.time,
+                driver,
+                go_at: None,
+            },
+        );
+        stats.network_spawned += 1;
+        stats.class(self.vehicles[&id].autonomy()).spawned += 1;
+        true
+    }
+
+    /// Random origin-destination pair between two different gateways
+    pub fn spawn_random(&mut self, rng: &mut impl Rng, stats: &mut Stats) -> bool {
+        let origin = self.gateways[rng.random_range(0..self.gateways.len())];
+        let mut destination = origin;
+        while destination == origin {
+            destination = self.gateways[rng.random_range(0..self.gateways.len())];
+        }
+        let driver = HumanDriver::at_penetration(rng, self.av_penetration);
+        self.spawn(origin, destination, driver, stats)
+    }
+
+    /// Seconds between random spawns, so demand grows with the number of gateways
+    pub fn spawn_interval(&self) -> f32 {
+        1.6 / self.gateways.len() as f32 * 4.0
+    }
+
+    // ---------------------------------------------------
+    // ⏱️ Simulation step
+    // ---------------------------------------------------
+
+    /// Headless run of `steps` fixed steps with random demand; stops early if
+    /// the safety monitor aborts
+    pub fn run(&mut self, steps: u64, rng: &mut impl Rng, stats: &mut Stats, safety: &mut SafetyMonitor) {
+        let mut since_spawn = 0.0;
+        for step in 1..=steps {
+            self.run_step(step, &mut since_spawn, true, rng, stats, safety);
+            if safety.aborted() {
+                break;
+            }
+        }
+    }
+
+    /// Step `step` of a headless run: spawn on schedule (if `auto_spawn`),
+    /// move everyone, check the invariants
+    pub fn run_step(
+        &mut self,
+        step: u64,
+        since_spawn: &mut f32,
+        auto_spawn: bool,
+        rng: &mut impl Rng,
+        stats: &mut Stats,
+        safety: &mut SafetyMonitor,
+    ) {
+        stats.runtime += STEP;
+        *since_spawn += STEP;
+        if auto_spawn && *since_spawn > self.spawn_interval() {
+            self.spawn_random(rng, stats);
+            *since_spawn = 0.0;
+        }
+        self.update(STEP, stats);
+        safety.check_network(step, self, stats);
+    }
+
+    pub fn update(&mut self, dt: f32, stats: &mut Stats) {
+        self.time += dt;
+        self.exchange_messages(stats);
+        self.watch_for_humans();
+
+        for l in 0..self.links.len() {
+            self.update_link(l, dt, stats);
+        }
+        self.update_crossings(dt);
+    }
+
+    /// Car-following along one link plus junction requests at its downstream end
+    fn update_link(&mut self, l: usize, dt: f32, stats: &mut Stats) {
+        let cruise = self.cruise_speed();
+        let length = self.links[l].length;
+        let junction = self.links[l].to;
+        let queue: Vec<usize> = self.links[l].vehicles.iter().copied().collect();
+        let mut leader_pos: Option<f32> = None;
+        let mut ahead_granted = true; // Everyone in front may enter the junction
+        let mut departed = Vec::new();
+
+        for id in queue {
+            let (next_link, pos, human) = {
+                let v = &self.vehicles[&id];
+                (v.links.get(v.leg + 1).copied(), v.pos, v.driver.is_some())
+            };
+
+            // ⌛ A grant not used in time lapses: hold at the line and ask again
+            let v = self.vehicles.get_mut(&id).unwrap();
+            if v.granted && self.time > v.grant_until {
+                v.granted = false;
+                v.asked_at = None;
+                v.retry_at = self.time + REQUEST_RETRY;
+                v.cancelled_at = self.time;
+                if let Some(next) = next_link {
+                    self.links[next].incoming -= 1;
+                }
+                self.bus.send(self.time, Endpoint::Vehicle(id), Endpoint::Junction(junction), Payload::Cancel);
+                stats.network_lapsed_grants += 1;
+            }
+
+            // 🧑 Human drivers ask nobody: first in line, they make up their own mind
+            if let (Some(next), true) = (next_link, human) {
+                if length - pos < REQUEST_DISTANCE && leader_pos.is_none() && !self.vehicles[&id].granted {
+                    self.human_at_stop_line(id, l, next, stats);
+                }
+            }
+
+            // 🚦 Ask the junction once close enough to the stop line (again if
+            // the answer got lost), and only behind cars that may go: a grant
+            // held behind a car that may not would block it for good
+            if let (Some(next), false) = (next_link, human) {
+                let v = &self.vehicles[&id];
+                let waiting = !v.granted && self.time >= v.retry_at && ahead_granted;
+                if length - pos < REQUEST_DISTANCE && waiting {
+                    if self.links[next].has_space() {
+                        let movement = self.movement(l, next);
+                        self.bus.send(self.time, Endpoint::Vehicle(id), Endpoint::Junction(junction), Payload::Request(movement));
+                        let v = self.vehicles.get_mut(&id).unwrap();
+                        v.requested = true;
+                        v.retry_at = self.time + REQUEST_TIMEOUT;
+                        v.asked_at.get_or_insert(self.time);
+                        v.blocked = false;
+                    } else if !v.blocked {
+                        self.vehicles.get_mut(&id).unwrap().blocked = true;
+                        stats.network_spillbacks += 1;
+                    }
+                }
+            }
+
+            let v = self.vehicles.get_mut(&id).unwrap();
+            if !human && (v.granted || v.requested) && self.time - v.last_heartbeat >= HEARTBEAT_INTERVAL {
+                let heartbeat = Payload::Heartbeat { pos: v.pos, speed: v.speed };
+                self.bus.send(self.time, Endpoint::Vehicle(id), Endpoint::Junction(junction), heartbeat);
+                v.last_heartbeat = self.time;
+            }
+
+            // Free space ahead: leader's rear bumper, and the stop line unless granted
+            let mut free = f32::MAX;
+            if let Some(lead) = leader_pos {
+                free = lead - NET_VEHICLE_LENGTH - NET_MIN_GAP - v.pos;
+            }
+            if next_link.is_some() && !v.granted {
+                free = free.min(length - v.pos);
+            }
+
+            let safe_speed = (2.0 * NET_DECELERATION * free.max(0.0)).sqrt();
+            let mut target = cruise.min(safe_speed).min(v.advisory.unwrap_or(f32::MAX));
+            // Humans respond late; the space ahead still caps how far anyone moves
+            if let Some(driver) = &mut v.driver {
+                target = driver.react(self.time, target);
+            }
+            v.speed = (v.speed + NET_ACCELERATION * dt).min(target);
+            v.pos += (v.speed * dt).min(free.max(0.0));
+
+            leader_pos = Some(v.pos);
+            ahead_granted &= v.granted;
+
+            // Held at the stop line lands exactly on `length`: only a grant lets it in
+            if v.pos >= length && (next_link.is_none() || v.granted) {
+                departed.push(id);
+            }
+        }
+
+        for id in departed {
+            self.links[l].vehicles.retain(|&q| q != id);
+            let v = self.vehicles.get_mut(&id).unwrap();
+
+            if self.links[l].to == v.destination {
+                // 🏁 Reached the destination gateway
+                stats.network_trips += 1;
+                stats.network_travel_time += self.time - v.spawned_at;
+                stats.record_trip(v.autonomy(), self.time - v.spawned_at);
+                self.vehicles.remove(&id);
+            } else {
+                let total = JUNCTION_SIZE / v.speed.max(MIN_CROSSING_SPEED);
+                v.state = NetState::Crossing { remaining: total, total };
+            }
+        }
+    }
+
+    /// Vehicles inside junctions: release the reservation and enter the next link
+    fn update_crossings(&mut self, dt: f32) {
+        let mut arrived = Vec::new();
+
+        for v in self.vehicles.values_mut() {
+            if let NetState::Crossing { remaining, total } = v.state {
+                let remaining = remaining - dt;
+                v.state = NetState::Crossing { remaining, total };
+                if remaining <= 0.0 {
+                    arrived.push(v.id);
+                } else if v.driver.is_none() && self.time - v.last_heartbeat >= HEARTBEAT_INTERVAL {
+                    // Still holding the junction
+                    let junction = self.links[v.links[v.leg]].to;
+                    let heartbeat = Payload::Heartbeat { pos: self.links[v.links[v.leg]].length, speed: v.speed };
+                    self.bus.send(self.time, Endpoint::Vehicle(v.id), Endpoint::Junction(junction), heartbeat);
+                    v.last_heartbeat = self.time;
+                }
+            }
+        }
+
+        for id in arrived {
+            let v = self.vehicles.get_mut(&id).unwrap();
+            let junction = self.links[v.links[v.leg]].to;
+            v.leg += 1;
+            v.pos = 0.0;
+            v.state = NetState::Driving;
+            v.granted = false;
+            v.blocked = false;
+            v.cancelled_at = self.time;
+
+            let next = v.links[v.leg];
+            self.links[next].incoming -= 1;
+            self.links[next].vehicles.push_back(id);
+            if v.driver.is_some() {
+                self.nodes[junction].manager.release(id); // The controller sees them leave
+            } else {
+                self.bus.send(self.time, Endpoint::Vehicle(id), Endpoint::Junction(junction), Payload::Cancel);
+            }
+        }
+    }
+
+    // ---------------------------------------------------
+    // 🧑 Human drivers
+    // ---------------------------------------------------
+
+    /// Controllers spot human drivers first in line at their stop lines. While
+    /// one waits (and has somewhere to go) no new grants go out, and the first
+    /// seen pulling out gets the whole junction if nobody else holds any of it.
+    fn watch_for_humans(&mut self) {
+        for node in &mut self.nodes {
+            node.humans.clear();
+        }
+        for link in &self.links {
+            let Some(v) = link.vehicles.front().map(|id| &self.vehicles[id]) else {
+                continue;
+            };
+            let Some(&next) = v.links.get(v.leg + 1) else {
+                continue;
+            };
+            if v.driver.is_some() && link.length - v.pos < REQUEST_DISTANCE && self.links[next].has_space() {
+                let movement = Movement::new(link.heading.opposite(), self.links[next].heading);
+                self.nodes[link.to].humans.push((v.id, movement));
+            }
+        }
+        let vehicles = &self.vehicles;
+        let pulls_out_at = |id: &usize| match &vehicles[id] {
+            v if v.granted => Some(f32::MIN),
+            v => v.go_at,
+        };
+        for node in &mut self.nodes {
+            let first = node
+                .humans
+                .iter()
+                .filter_map(|&(id, movement)| Some((pulls_out_at(&id)?, id, movement)))
+                .min_by(|a, b| a.0.total_cmp(&b.0));
+            if let Some((_, id, movement)) = first {
+                node.manager.reserve_exclusive(id, movement);
+            }
+        }
+    }
+
+    /// A human first in line on link `l` decides to go when the junction looks
+    /// clear, and does so a reaction time later whatever happened in it
+    /// meanwhile. Nobody drives into a queue they can see has no room.
+    fn human_at_stop_line(&mut self, id: usize, l: usize, next: usize, stats: &mut Stats) {
+        let room = self.links[next].has_space();
+        let v = &self.vehicles[&id];
+        let Some(driver) = &v.driver else {
+            return;
+        };
+        if room && v.go_at.is_none() && self.looks_clear(l, driver.gap_acceptance) {
+            let go_at = self.time + driver.reaction;
+            self.vehicles.get_mut(&id).unwrap().go_at = Some(go_at);
+        }
+
+        let v = self.vehicles.get_mut(&id).unwrap();
+        if !room {
+            v.go_at = None;
+            if !v.blocked {
+                v.blocked = true;
+                stats.network_spillbacks += 1;
+            }
+        } else if v.go_at.is_some_and(|t| self.time >= t) {
+            self.links[next].incoming += 1;
+            v.granted = true;
+            v.grant_until = f32::MAX;
+            v.go_at = None;
+            v.blocked = false;
+        }
+    }
+
+    /// What a driver at the end of link `l` sees: nobody inside the junction
+    /// and nobody on another approach due at it within their accepted gap
+    fn looks_clear(&self, l: usize, gap_acceptance: f32) -> bool {
+        let junction = self.links[l].to;
+        let gap = HUMAN_GAP * gap_acceptance;
+
+        self.vehicles.values().all(|o| {
+            let on = o.links[o.leg];
+            let link = &self.links[on];
+            if link.to != junction || on == l {
+                return true;
+            }
+            // Another driver seen pulling out has the junction
+            let pulling_out = o.driver.is_some() && (o.granted || o.go_at.is_some());
+            match o.state {
+                NetState::Crossing { .. } => false,
+                NetState::Driving if pulling_out => false,
+                NetState::Driving => o.speed < 1.0 || (link.length - o.pos) / o.speed > gap,
+            }
+        })
+    }
+
+    // ---------------------------------------------------
+    // 📡 V2I messaging
+    // ---------------------------------------------------
+
+    /// Deliver last step's messages, then let controllers expire lapsed leases
+    fn exchange_messages(&mut self, stats: &mut Stats) {
+        for message in self.bus.deliver(self.time) {
+            match message.to {
+                Endpoint::Junction(node) => self.junction_receives(node, message),
+                Endpoint::Vehicle(id) => self.vehicle_receives(id, message, stats),
+            }
+        }
+
+        for node in 0..self.nodes.len() {
+            let time = self.time;
+            let expired: Vec<usize> = self.nodes[node]
+                .leases
+                .iter()
+                .filter(|(_, lease)| lease.expired(time, CROSSING_ALLOWANCE))
+                .map(|(&id, _)| id)
+                .collect();
+            for id in expired {
+                self.nodes[node].manager.release(id);
+                self.nodes[node].leases.remove(&id);
+            }
+        }
+    }
+
+    /// Controller side: grant or reject requests, release cancelled reservations
+    fn junction_receives(&mut self, node: usize, message: Message) {
+        let Endpoint::Vehicle(id) = message.from else {
+            return;
+        };
+        let (me, them) = (Endpoint::Junction(node), message.from);
+        let junction = &mut self.nodes[node];
+
+        match message.payload {
+            // No new grants while a human driver waits for the junction
+            Payload::Request(movement) => {
+                let draining = !junction.humans.is_empty() && !junction.manager.is_authorized(id);
+                if draining {
+                    junction.manager.rejected += 1;
+                }
+                if !draining && junction.manager.request(id, movement) {
+                    let until = self.time + GRANT_LEASE;
+                    let asked = junction.leases.get(&id).map_or(message.sent, |l| l.asked.max(message.sent));
+                    junction.leases.insert(id, Lease { asked, until, heard: self.time });
+                    self.bus.send(self.time, me, them, Payload::Grant { until, asked: message.sent });
+                } else {
+                    let advised = self.velocity_level.to_speed() * ADVISORY_FACTOR;
+                    self.bus.send(self.time, me, them, Payload::Reject);
+                    self.bus.send(self.time, me, them, Payload::SpeedAdvisory(advised));
+                }
+            }
+            // A cancel overtaken by a newer request is stale
+            Payload::Cancel => {
+                if junction.leases.get(&id).is_none_or(|l| message.sent >= l.asked) {
+                    junction.manager.release(id);
+                    junction.leases.remove(&id);
+                }
+            }
+            Payload::Heartbeat { .. } => {
+                if let Some(lease) = junction.leases.get_mut(&id) {
+                    lease.heard = self.time;
+                }
+            }
+            Payload::Grant { .. } | Payload::Reject | Payload::SpeedAdvisory(_) => {}
+        }
+    }
+
+    /// Vehicle side: take a grant if the next link still has room. Delays and
+    /// retries mean answers can be duplicated, reordered or meant for a
+    /// junction already left behind.
+    fn vehicle_receives(&mut self, id: usize, message: Message, stats: &mut Stats) {
+        let Some(v) = self.vehicles.get_mut(&id) else {
+            return; // Already arrived
+        };
+        let me = Endpoint::Vehicle(id);
+        let ahead = Endpoint::Junction(self.links[v.links[v.leg]].to);
+        let current = message.from == ahead && !v.granted;
+
+        match message.payload {
+            // Stale grant from a junction behind: don't leave it blocked
+            Payload::Grant { .. } if message.from != ahead => {
+                v.cancelled_at = self.time;
+                self.bus.send(self.time, me, message.from, Payload::Cancel);
+            }
+            // Answers a request sent before our last Cancel, which voids it
+            Payload::Grant { asked, .. } if asked <= v.cancelled_at => {}
+            Payload::Grant { until, .. } if current => {
+                v.requested = false;
+                let next = v.links.get(v.leg + 1).copied();
+                match next {
+                    Some(next) if self.links[next].has_space() && self.time <= until => {
+                        self.links[next].incoming += 1;
+                        v.granted = true;
+                        v.grant_until = until;
+                        v.advisory = None;
+                        stats.network_grants += 1;
+                        stats.network_grant_wait += self.time - v.asked_at.take().unwrap_or(self.time);
+                    }
+                    // Next link filled up meanwhile, or the grant came too late
+                    _ => {
+                        v.retry_at = self.time + REQUEST_RETRY;
+                        v.cancelled_at = self.time;
+                        self.bus.send(self.time, me, message.from, Payload::Cancel);
+                    }
+                }
+            }
+            Payload::Reject if current => {
+                v.requested = false;
+                v.retry_at = self.time + REQUEST_RETRY;
+            }
+            Payload::SpeedAdvisory(speed) if current => v.advisory = Some(speed),
+            // Duplicates of the grant in hand, late rejects and advisories
+            _ => {}
+        }
+    }
+
+    // ---------------------------------------------------
+    // 🎨 Drawing
+    // ---------------------------------------------------
+
+    /// World position of a point `pos` along a link, on its right-hand lane
+    fn link_point(&self, l: usize, pos: f32) -> (f32, f32) {
+        let link = &self.links[l];
+        let a = &self.nodes[link.from];
+        let (ux, uy) = unit(link.heading);
+        let along = a.half_size() + pos;
+        // Right-hand side of the heading
+        let (rx, ry) = (-uy, ux);
+        (
+            a.x + ux * along + rx * LANE_OFFSET,
+            a.y + uy * along + ry * LANE_OFFSET,
+        )
+    }
+
+    pub fn vehicle_position(&self, v: &NetVehicle) -> (f32, f32) {
+        let link = v.links[v.leg];
+        match v.state {
+            NetState::Driving => self.link_point(link, v.pos),
+            NetState::Crossing { remaining, total } => {
+                let (x0, y0) = self.link_point(link, self.links[link].length);
+                let (x1, y1) = self.link_point(v.links[v.leg + 1], 0.0);
+                let t = (1.0 - remaining / total).clamp(0.0, 1.0);
+                (x0 + (x1 - x0) * t, y0 + (y1 - y0) * t)
+            }
+        }
+    }
+
+    /// Draw the network through `camera`, outlining the `selected` vehicle
+    pub fn draw(&self, canvas: &mut Canvas<Window>, camera: &Camera, selected: Option<usize>) {
+        canvas.set_draw_color(Color::RGB(60, 110, 50));
+        canvas.clear();
+
+        // Roads, tinted red as they fill up (spillback)
+        let road_w = JUNCTION_SIZE / 2.0;
+        for link in &self.links {
+            let (a, b) = (&self.nodes[link.from], &self.nodes[link.to]);
+            let fill = (link.vehicles.len() + link.incoming) as f32 / link.capacity().max(1) as f32;
+            let red = (45.0 + 150.0 * fill.min(1.0)) as u8;
+            canvas.set_draw_color(Color::RGB(red, 45, 45));
+
+            let (ux, uy) = unit(link.heading);
+            let (rx, ry) = (-uy, ux);
+            // Right half of the road belongs to this link
+            let (x0, y0) = (a.x.min(b.x), a.y.min(b.y));
+            let (w, h) = ((b.x - a.x).abs(), (b.y - a.y).abs());
+            let rect = if uy == 0.0 {
+                camera.rect(x0, y0 + if ry > 0.0 { 0.0 } else { -road_w / 2.0 }, w, road_w / 2.0)
+            } else {
+                camera.rect(x0 + if rx > 0.0 { 0.0 } else { -road_w / 2.0 }, y0, road_w / 2.0, h)
+            };
+            canvas.fill_rect(rect).ok();
+        }
+
+        // Junctions, amber while a reservation is held
+        for node in self.nodes.iter().filter(|n| n.kind == NodeKind::Intersection) {
+            canvas.set_draw_color(if node.manager.reservations.is_empty() {
+                Color::RGB(90, 90, 90)
+            } else {
+                Color::RGB(200, 150, 40)
+            });
+            let half = JUNCTION_SIZE / 2.0;
+            canvas.fill_rect(camera.rect(node.x - half, node.y - half, JUNCTION_SIZE, JUNCTION_SIZE)).ok();
+        }
+
+        // Vehicles: white moving (orange: human-driven), red stopped, magenta
+        // held back by spillback
+        let size = NET_VEHICLE_LENGTH * 0.6;
+        for v in self.vehicles.values() {
+            let (x, y) = self.vehicle_position(v);
+            canvas.set_draw_color(if v.blocked {
+                Color::RGB(220, 60, 220)
+            } else if v.speed < 1.0 {
+                Color::RGB(230, 50, 50)
+            } else if v.driver.is_some() {
+                Color::RGB(255, 140, 0)
+            } else {
+                Color::RGB(240, 240, 240)
+            });
+            canvas.fill_rect(camera.rect(x - size / 2.0, y - size / 2.0, size, size)).ok();
+        }
+
+        if let Some(v) = selected.and_then(|id| self.vehicles.get(&id)) {
+            let (x, y) = self.vehicle_position(v);
+            let outline = size + 6.0;
+            canvas.set_draw_color(Color::RGB(255, 230, 0));
+            canvas.draw_rect(camera.rect(x - outline / 2.0, y - outline / 2.0, outline, outline)).ok();
+        }
+    }
+}
+
+/// Per-junction manager totals for the end-of-run report
+pub fn print_network_summary(network: &RoadNetwork) {
+    println!("\n🕸️  Network {}x{} - junction managers:", network.rows, network.cols);
+    for (i, node) in network.nodes.iter().enumerate() {
+        if node.kind == NodeKind::Intersection {
+            let (c, r) = (i as i32 % network.cols, i as i32 / network.cols);
+            println!(
+                "   ({}, {}) granted {:>5}  rejected {:>6}",
+                c, r, node.manager.granted, node.manager.rejected
+            );
+        }
+    }
+
+    network.bus.print_summary();
+}
+
+// =======================================================
+// 📉 LATENCY SWEEP (how throughput and safety degrade with delay)
+// =======================================================
+
+/// Channel latencies the sweep runs at (milliseconds)
+pub const SWEEP_LATENCIES_MS: [f32; 7] = [0.0, 50.0, 100.0, 200.0, 400.0, 800.0, 1600.0];
+
+/// One run of the sweep
+#[derive(Clone, Debug)]
+pub struct SweepPoint {
+    pub latency: f32, // Seconds
+    pub trips: u32,
+    pub throughput: f32, // Trips per minute
+    pub avg_trip: f32,   // Seconds
+    pub grant_wait: f32, // Mean seconds from request to grant
+    pub lapsed: u32,     // Grants that ran out before the vehicle got to use them
+    pub lost: u32,       // Messages
+    pub unauthorized: u32,
+    pub violations: u32, // All safety invariants
+}
+
+/// The same demand (`seed`) on the `rows` x `cols` grid `config` describes
+/// (fleet and links), for --duration at each latency (seconds). Every link
+/// keeps its own jitter and loss.
+pub fn latency_sweep(config: &SimConfig, rows: i32, cols: i32, latencies: &[f32], seed: u64) -> Vec<SweepPoint> {
+    let steps = (config.duration / STEP).round() as u64;
+    latencies
+        .iter()
+        .map(|&latency| {
+            let mut network = RoadNetwork::from_config(config, rows, cols, seed);
+            network.bus.logging = false;
+            let bus = &mut network.bus;
+            bus.channel.latency = latency;
+            for channel in bus.channels.values_mut() {
+                channel.latency = latency;
+            }
+            let mut rng = StdRng::seed_from_u64(seed);
+            let mut stats = Stats::new();
+            let mut safety = SafetyMonitor::new(false);
+            network.run(steps, &mut rng, &mut stats, &mut safety);
+
+            SweepPoint {
+                latency,
+                trips: stats.network_trips,
+                throughput: stats.network_trips as f32 / (stats.runtime / 60.0),
+                avg_trip: stats.network_travel_time / stats.network_trips.max(1) as f32,
+                grant_wait: stats.avg_grant_wait(),
+                lapsed: stats.network_lapsed_grants,
+                lost: network.bus.total_lost(),
+                unauthorized: stats.unauthorized_entries,
+                violations: stats.safety_violations(),
+            }
+        })
+        .collect()
+}
+
+pub fn print_latency_sweep(points: &[SweepPoint]) {
+    println!("\n📉 Latency sweep:");
+    println!("   latency  trips  trips/min  avg trip  grant wait  lapsed   lost  unauthorized  violations");
+    for p in points {
+        println!(
+            "   {:>5.0}ms  {:>5}  {:>9.1}  {:>7.1}s  {:>9.2}s  {:>6}  {:>5}  {:>12}  {:>10}",
+            p.latency * 1000.0,
+            p.trips,
+            p.throughput,
+            p.avg_trip,
+            p.grant_wait,
+            p.lapsed,
+            p.lost,
+            p.unauthorized,
+            p.violations
+        );
+    }
+}
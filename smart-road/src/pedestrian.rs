@@ -0,0 +1,276 @@
+use sdl2::pixels::Color;
+use sdl2::rect::Rect;
+use sdl2::render::{Canvas, RenderTarget};
+
+use crate::geometry::RoadGeometry;
+use crate::stats::Stats;
+use crate::vehicle::{Direction, Vehicle};
+
+// 🚶 Walking speed in pixels per second (sped up ~3x from 1.4 m/s for visibility)
+const WALK_SPEED: f32 = 40.0;
+// Tiles walked on the pavement before reaching / after leaving the kerb
+const SIDEWALK_TILES: i32 = 2;
+const PEDESTRIAN_SIZE: u32 = 12;
+
+// =======================================================
+// 🦓 CROSSWALKS
+// =======================================================
+
+/// A zebra crossing over one approach road, just outside the intersection.
+/// `side` is the arm of the cross it sits on (Up = north arm, etc.).
+#[derive(Clone, Debug)]
+pub struct Crosswalk {
+    pub side: Direction,
+    pub x0: f32,
+    pub y0: f32,
+    pub x1: f32,
+    pub y1: f32,
+    pub waiting: u32,  // Pedestrians requesting to cross
+    pub crossing: u32, // Pedestrians currently on the zebra
+}
+
+impl Crosswalk {
+    /// Vehicles must yield while someone is waiting at or walking on the zebra
+    pub fn is_occupied(&self) -> bool {
+        self.waiting > 0 || self.crossing > 0
+    }
+
+    /// True if the crosswalk runs across a vertical road (north/south arms)
+    pub fn spans_vertical_road(&self) -> bool {
+        matches!(self.side, Direction::Up | Direction::Down)
+    }
+
+    pub fn contains(&self, x: f32, y: f32, margin: f32) -> bool {
+        x >= self.x0 - margin && x <= self.x1 + margin
+            && y >= self.y0 - margin && y <= self.y1 + margin
+    }
+
+    /// Kerb-side waypoints: sidewalk → kerb A → kerb B → sidewalk
+    fn walk_path(&self, reverse: bool) -> Vec<(f32, f32)> {
+        // The zebra is one tile deep
+        let t = if self.spans_vertical_road() { self.y1 - self.y0 } else { self.x1 - self.x0 };
+        let off = SIDEWALK_TILES as f32 * t;
+
+        let mut path = if self.spans_vertical_road() {
+            let y = (self.y0 + self.y1) / 2.0;
+            vec![
+                (self.x0 - t / 2.0 - off, y),
+                (self.x0 - t / 2.0, y),
+                (self.x1 + t / 2.0, y),
+                (self.x1 + t / 2.0 + off, y),
+            ]
+        } else {
+            let x = (self.x0 + self.x1) / 2.0;
+            vec![
+                (x, self.y0 - t / 2.0 - off),
+                (x, self.y0 - t / 2.0),
+                (x, self.y1 + t / 2.0),
+                (x, self.y1 + t / 2.0 + off),
+            ]
+        };
+
+        if reverse {
+            path.reverse();
+        }
+        path
+    }
+}
+
+pub fn build_crosswalks(g: &RoadGeometry) -> Vec<Crosswalk> {
+    let t = g.lane_width;
+    [Direction::Up, Direction::Down, Direction::Left, Direction::Right]
+        .into_iter()
+        .map(|side| {
+            let (tx0, ty0, tx1, ty1) = g.crosswalk_tiles(side);
+            Crosswalk {
+                side,
+                x0: (tx0 * t) as f32,
+                y0: (ty0 * t) as f32,
+                x1: ((tx1 + 1) * t) as f32,
+                y1: ((ty1 + 1) * t) as f32,
+                waiting: 0,
+                crossing: 0,
+            }
+        })
+        .collect()
+}
+
+/// Paint zebra stripes on top of the crosswalk tiles
+pub fn draw_crosswalks<T: RenderTarget>(canvas: &mut Canvas<T>, crosswalks: &[Crosswalk]) {
+    let stripe = 8;
+    let gap = 8;
+
+    for cw in crosswalks {
+        canvas.set_draw_color(if cw.is_occupied() {
+            Color::RGB(255, 220, 120)
+        } else {
+            Color::RGB(235, 235, 235)
+        });
+
+        let (x0, y0, x1, y1) = (cw.x0 as i32, cw.y0 as i32, cw.x1 as i32, cw.y1 as i32);
+
+        if cw.spans_vertical_road() {
+            // Stripes run along the traffic flow (vertical bars)
+            for x in (x0 + gap / 2..x1).step_by((stripe + gap) as usize) {
+                canvas.fill_rect(Rect::new(x, y0 + 6, stripe as u32, (y1 - y0 - 12) as u32)).ok();
+            }
+        } else {
+            for y in (y0 + gap / 2..y1).step_by((stripe + gap) as usize) {
+                canvas.fill_rect(Rect::new(x0 + 6, y, (x1 - x0 - 12) as u32, stripe as u32)).ok();
+            }
+        }
+    }
+}
+
+// =======================================================
+// 🚶 PEDESTRIANS
+// =======================================================
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PedestrianState {
+    Approaching, // Walking on the pavement towards the kerb
+    Waiting,     // At the kerb, requesting to cross
+    Crossing,    // On the zebra
+    Leaving,     // Back on the pavement, walking away
+}
+
+#[derive(Clone, Debug)]
+pub struct Pedestrian {
+    pub x: f32,
+    pub y: f32,
+    pub crosswalk: usize,
+    pub state: PedestrianState,
+    pub path: Vec<(f32, f32)>,
+    pub current_target: usize,
+    pub wait_time: f32,
+}
+
+impl Pedestrian {
+    pub fn new(crosswalk: usize, cw: &Crosswalk, reverse: bool) -> Self {
+        let path = cw.walk_path(reverse);
+        let (x, y) = path[0];
+
+        Self {
+            x,
+            y,
+            crosswalk,
+            state: PedestrianState::Approaching,
+            path,
+            current_target: 1,
+            wait_time: 0.0,
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.current_target >= self.path.len()
+    }
+
+    /// Walk towards the next waypoint; returns true when it is reached
+    fn walk(&mut self, dt: f32) -> bool {
+        let (tx, ty) = self.path[self.current_target];
+        let dx = tx - self.x;
+        let dy = ty - self.y;
+        let dist = (dx * dx + dy * dy).sqrt();
+        let step = WALK_SPEED * dt;
+
+        if dist <= step {
+            self.x = tx;
+            self.y = ty;
+            self.current_target += 1;
+            true
+        } else {
+            self.x += dx / dist * step;
+            self.y += dy / dist * step;
+            false
+        }
+    }
+
+    pub fn draw<T: RenderTarget>(&self, canvas: &mut Canvas<T>) {
+        canvas.set_draw_color(match self.state {
+            PedestrianState::Waiting => Color::RGB(255, 140, 0),
+            PedestrianState::Crossing => Color::RGB(30, 120, 255),
+            _ => Color::RGB(20, 20, 20),
+        });
+
+        let half = (PEDESTRIAN_SIZE / 2) as f32;
+        canvas
+            .fill_rect(Rect::new(
+                (self.x - half) as i32,
+                (self.y - half) as i32,
+                PEDESTRIAN_SIZE,
+                PEDESTRIAN_SIZE,
+            ))
+            .ok();
+    }
+}
+
+/// Spawn a pedestrian at one end of the given crosswalk (`reverse` picks the far end)
+pub fn spawn_pedestrian(
+    pedestrians: &mut Vec<Pedestrian>,
+    crosswalks: &[Crosswalk],
+    stats: &mut Stats,
+    crosswalk: usize,
+    reverse: bool,
+) {
+    pedestrians.push(Pedestrian::new(crosswalk, &crosswalks[crosswalk], reverse));
+    stats.pedestrians += 1;
+}
+
+/// A crossing may be granted once no vehicle body overlaps the zebra and no
+/// approaching vehicle is too close to stop for it
+fn crosswalk_clear(cw: &Crosswalk, vehicles: &[Vehicle]) -> bool {
+    !vehicles
+        .iter()
+        .any(|v| cw.contains(v.x, v.y, v.half_length()) || v.committed_to_crosswalk(cw))
+}
+
+fn recount(crosswalks: &mut [Crosswalk], pedestrians: &[Pedestrian]) {
+    for cw in crosswalks.iter_mut() {
+        cw.waiting = 0;
+        cw.crossing = 0;
+    }
+    for p in pedestrians {
+        match p.state {
+            PedestrianState::Waiting => crosswalks[p.crosswalk].waiting += 1,
+            PedestrianState::Crossing => crosswalks[p.crosswalk].crossing += 1,
+            _ => {}
+        }
+    }
+}
+
+/// Advance all pedestrians, grant crossings and update crosswalk occupancy
+pub fn update_pedestrians(
+    pedestrians: &mut Vec<Pedestrian>,
+    crosswalks: &mut [Crosswalk],
+    vehicles: &[Vehicle],
+    stats: &mut Stats,
+    dt: f32,
+) {
+    for p in pedestrians.iter_mut() {
+        match p.state {
+            PedestrianState::Approaching => {
+                if p.walk(dt) {
+                    p.state = PedestrianState::Waiting;
+                }
+            }
+            PedestrianState::Waiting => {
+                p.wait_time += dt;
+                if crosswalk_clear(&crosswalks[p.crosswalk], vehicles) {
+                    p.state = PedestrianState::Crossing;
+                    stats.record_pedestrian_wait(p.wait_time);
+                }
+            }
+            PedestrianState::Crossing => {
+                if p.walk(dt) {
+                    p.state = PedestrianState::Leaving;
+                }
+            }
+            PedestrianState::Leaving => {
+                p.walk(dt);
+            }
+        }
+    }
+
+    pedestrians.retain(|p| !p.is_done());
+    recount(crosswalks, pedestrians);
+}
@@ -0,0 +1,171 @@
+use std::borrow::Cow;
+use std::f32::consts::PI;
+
+use crate::spatial::Neighborhood;
+use crate::vehicle::{Direction, Vehicle};
+
+// =======================================================
+// 👁️ PERCEPTION (what a driver's sensors make of the others)
+// =======================================================
+//
+// Car-following works on perceived neighbors rather than the true state:
+// only vehicles within the sensor range and field of view are seen, any of
+// them hidden behind another body can be dropped (occlusion), and positions
+// and speeds carry Gaussian noise. The default model is perfect and hands the
+// true state through untouched.
+//
+// Noise is drawn from a hash of (seed, step, observer, target) instead of a
+// shared generator, so a seeded run stays the same whichever order vehicles
+// are updated in (see the `parallel` feature).
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Perception {
+    pub range: f32,          // Pixels (infinite: whatever the car-following looks at)
+    pub fov: f32,            // Degrees, centered on the heading
+    pub position_noise: f32, // Standard deviation, pixels
+    pub speed_noise: f32,    // Standard deviation, pixels/s
+    pub occlusion: bool,     // Vehicles hide what is behind them
+}
+
+impl Default for Perception {
+    /// Sees everything, exactly
+    fn default() -> Self {
+        Self { range: f32::INFINITY, fov: 360.0, position_noise: 0.0, speed_noise: 0.0, occlusion: false }
+    }
+}
+
+impl Perception {
+    pub fn is_perfect(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// A perception model for one step, with the randomness behind its noise
+#[derive(Clone, Copy, Debug)]
+pub struct Sensor {
+    pub model: Perception,
+    pub seed: u64,
+    pub step: u64,
+}
+
+impl Sensor {
+    pub fn perfect() -> Self {
+        Self { model: Perception::default(), seed: 0, step: 0 }
+    }
+
+    /// Other vehicles within `r` of `observer` as it perceives them: the true
+    /// state when perception is perfect, noisy copies otherwise
+    pub fn perceive<'a>(&self, observer: &Vehicle, neighbors: Neighborhood<'a>, r: f32) -> Vec<Cow<'a, Vehicle>> {
+        let model = &self.model;
+        let others: Vec<&Vehicle> = neighbors
+            .within(observer.x, observer.y, r.min(model.range))
+            .filter(|o| o.id != observer.id)
+            .collect();
+        if model.is_perfect() {
+            return others.into_iter().map(Cow::Borrowed).collect();
+        }
+
+        let heading = heading_of(observer);
+        others
+            .iter()
+            .filter(|o| in_view(observer, heading, model.fov, o))
+            .filter(|o| !model.occlusion || !hidden(observer, o, &others))
+            .map(|&o| {
+                if model.position_noise == 0.0 && model.speed_noise == 0.0 {
+                    return Cow::Borrowed(o);
+                }
+                let mut noise = Noise::new(self.seed, self.step, observer.id, o.id);
+                let mut seen = o.clone();
+                seen.x += noise.gaussian() * model.position_noise;
+                seen.y += noise.gaussian() * model.position_noise;
+                seen.speed = (seen.speed + noise.gaussian() * model.speed_noise).max(0.0);
+                Cow::Owned(seen)
+            })
+            .collect()
+    }
+}
+
+/// Angle the vehicle is moving at (radians, screen coordinates)
+fn heading_of(v: &Vehicle) -> f32 {
+    if let Some(&(tx, ty)) = v.path.get(v.current_target) {
+        let (dx, dy) = (tx - v.x, ty - v.y);
+        if dx != 0.0 || dy != 0.0 {
+            return dy.atan2(dx);
+        }
+    }
+    match v.facing_direction() {
+        Direction::Up => -PI / 2.0,
+        Direction::Down => PI / 2.0,
+        Direction::Left => PI,
+        Direction::Right => 0.0,
+    }
+}
+
+fn in_view(observer: &Vehicle, heading: f32, fov: f32, other: &Vehicle) -> bool {
+    if fov >= 360.0 {
+        return true;
+    }
+    let bearing = (other.y - observer.y).atan2(other.x - observer.x);
+    let off = (bearing - heading + PI).rem_euclid(2.0 * PI) - PI;
+    off.abs() <= fov.to_radians() / 2.0
+}
+
+/// Another body between `observer` and the center of `target` blocks the line of sight
+fn hidden(observer: &Vehicle, target: &Vehicle, others: &[&Vehicle]) -> bool {
+    others
+        .iter()
+        .filter(|o| o.id != target.id)
+        .any(|o| segment_hits_box((observer.x, observer.y), (target.x, target.y), o.footprint()))
+}
+
+/// Slab test of the segment a→b against the box (x0, y0, x1, y1)
+fn segment_hits_box(a: (f32, f32), b: (f32, f32), (x0, y0, x1, y1): (f32, f32, f32, f32)) -> bool {
+    let (mut t0, mut t1) = (0.0f32, 1.0f32);
+    for (start, delta, lo, hi) in [(a.0, b.0 - a.0, x0, x1), (a.1, b.1 - a.1, y0, y1)] {
+        if delta == 0.0 {
+            if start < lo || start > hi {
+                return false;
+            }
+            continue;
+        }
+        let (ta, tb) = ((lo - start) / delta, (hi - start) / delta);
+        t0 = t0.max(ta.min(tb));
+        t1 = t1.min(ta.max(tb));
+        if t0 > t1 {
+            return false;
+        }
+    }
+    true
+}
+
+/// SplitMix64 stream keyed by one observation
+struct Noise(u64);
+
+impl Noise {
+    fn new(seed: u64, step: u64, observer: usize, target: usize) -> Self {
+        let key = seed
+            ^ step.wrapping_mul(0x9E37_79B9_7F4A_7C15)
+            ^ (observer as u64).wrapping_mul(0xC2B2_AE3D_27D4_EB4F)
+            ^ (target as u64).wrapping_mul(0x1656_67B1_9E37_79F9);
+        Self(key)
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in (0, 1]
+    fn uniform(&mut self) -> f32 {
+        ((self.next() >> 40) as f32 + 1.0) / (1u64 << 24) as f32
+    }
+
+    /// Standard normal (Box-Muller)
+    fn gaussian(&mut self) -> f32 {
+        let (u1, u2) = (self.uniform(), self.uniform());
+        (-2.0 * u1.ln()).sqrt() * (2.0 * PI * u2).cos()
+    }
+}
@@ -0,0 +1,166 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+
+use crate::api::{direction_name, parse_level, route_name};
+use crate::config::SimConfig;
+use crate::env::{Action, Env, EnvConfig, Observation, RewardWeights};
+use crate::signal::Phase;
+use crate::vehicle::{VehicleKind, VelocityLevel};
+
+// =======================================================
+// 🐍 PYTHON BINDINGS (--features python, built with maturin)
+// =======================================================
+//
+//     env = smart_road.SmartRoadEnv(["--junction", "roundabout"], decision_interval=0.5)
+//     obs = env.reset(seed=7)
+//     obs, reward, done = env.step("north-south")
+//
+// Actions: None (keep), a phase name or "off" (signals), a list of four
+// level names or None by approach (up, down, left, right), or a dict
+// {vehicle id: level name}. Observations are plain dicts and lists.
+
+#[pyclass(name = "SmartRoadEnv")]
+pub struct PyEnv {
+    env: Env,
+}
+
+#[pymethods]
+impl PyEnv {
+    /// `args` are the simulator's command-line flags (layout, demand, perception)
+    #[new]
+    #[pyo3(signature = (args=Vec::new(), decision_interval=1.0, episode_length=300.0, auto_spawn=true, throughput=1.0, delay=0.1, collisions=10.0))]
+    fn new(
+        args: Vec<String>,
+        decision_interval: f32,
+        episode_length: f32,
+        auto_spawn: bool,
+        throughput: f32,
+        delay: f32,
+        collisions: f32,
+    ) -> PyResult<Self> {
+        let sim = SimConfig::from_arg_list(&args);
+
+        let reward = RewardWeights { throughput, delay, collisions };
+        let config = EnvConfig { sim, decision_interval, episode_length, auto_spawn, reward };
+        Ok(Self { env: Env::new(config) })
+    }
+
+    fn reset<'py>(&mut self, py: Python<'py>, seed: u64) -> PyResult<Bound<'py, PyDict>> {
+        let obs = self.env.reset(seed);
+        observation(py, &obs)
+    }
+
+    #[pyo3(signature = (action=None))]
+    fn step<'py>(&mut self, py: Python<'py>, action: Option<&Bound<'py, PyAny>>) -> PyResult<(Bound<'py, PyDict>, f32, bool)> {
+        let action = match action {
+            Some(a) => parse_action(a)?,
+            None => Action::Keep,
+        };
+        let (obs, reward, done) = self.env.step(&action);
+        Ok((observation(py, &obs)?, reward, done))
+    }
+
+    /// Unweighted reward terms of the last step
+    fn terms<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let t = self.env.last_terms;
+        let d = PyDict::new(py);
+        d.set_item("exited", t.exited)?;
+        d.set_item("delay", t.delay)?;
+        d.set_item("collisions", t.collisions)?;
+        Ok(d)
+    }
+}
+
+fn parse_action(a: &Bound<'_, PyAny>) -> PyResult<Action> {
+    if a.is_none() {
+        return Ok(Action::Keep);
+    }
+    if let Ok(name) = a.extract::<String>() {
+        if name == "off" {
+            return Ok(Action::Signal(None));
+        }
+        return match Phase::ALL.into_iter().find(|p| p.name() == name) {
+            Some(phase) => Ok(Action::Signal(Some(phase))),
+            None => Err(PyValueError::new_err(format!("unknown phase '{}'", name))),
+        };
+    }
+    if let Ok(levels) = a.extract::<Vec<(usize, String)>>() {
+        return vehicle_levels(levels);
+    }
+    if let Ok(dict) = a.cast::<PyDict>() {
+        let levels = dict.iter().map(|(k, v)| Ok((k.extract()?, v.extract()?))).collect::<PyResult<_>>()?;
+        return vehicle_levels(levels);
+    }
+    if let Ok(by_approach) = a.extract::<Vec<Option<String>>>() {
+        let mut levels = [None; 4];
+        if by_approach.len() != levels.len() {
+            return Err(PyValueError::new_err("give one level (or None) per approach: up, down, left, right"));
+        }
+        for (slot, name) in levels.iter_mut().zip(by_approach) {
+            *slot = name.map(|n| parse_level(&n)).transpose().map_err(PyValueError::new_err)?;
+        }
+        return Ok(Action::Approaches(levels));
+    }
+    Err(PyValueError::new_err("action must be None, a phase name, four levels or {id: level}"))
+}
+
+fn vehicle_levels(levels: Vec<(usize, String)>) -> PyResult<Action> {
+    let levels = levels
+        .into_iter()
+        .map(|(id, name)| parse_level(&name).map(|level| (id, level)))
+        .collect::<Result<_, _>>()
+        .map_err(PyValueError::new_err)?;
+    Ok(Action::Vehicles(levels))
+}
+
+fn level_name(level: VelocityLevel) -> &'static str {
+    match level {
+        VelocityLevel::Slow => "slow",
+        VelocityLevel::Medium => "medium",
+        VelocityLevel::Fast => "fast",
+    }
+}
+
+fn observation<'py>(py: Python<'py>, obs: &Observation) -> PyResult<Bound<'py, PyDict>> {
+    let lanes = PyList::empty(py);
+    for q in &obs.lanes {
+        let d = PyDict::new(py);
+        d.set_item("dir", direction_name(q.direction))?;
+        d.set_item("lane", q.lane)?;
+        d.set_item("queued", q.queued)?;
+        d.set_item("approaching", q.approaching)?;
+        lanes.append(d)?;
+    }
+
+    let vehicles = PyList::empty(py);
+    for v in &obs.vehicles {
+        let d = PyDict::new(py);
+        d.set_item("id", v.id)?;
+        d.set_item("x", v.x)?;
+        d.set_item("y", v.y)?;
+        d.set_item("speed", v.speed)?;
+        d.set_item("dir", direction_name(v.direction))?;
+        d.set_item("route", route_name(v.route))?;
+        d.set_item("lane", v.lane)?;
+        d.set_item("kind", if v.kind == VehicleKind::Bicycle { "bicycle" } else { "car" })?;
+        d.set_item("autonomy", v.autonomy.name())?;
+        d.set_item("level", level_name(v.velocity_level))?;
+        d.set_item("in_junction", v.in_junction)?;
+        d.set_item("time_in_system", v.time_in_system)?;
+        vehicles.append(d)?;
+    }
+
+    let d = PyDict::new(py);
+    d.set_item("time", obs.time)?;
+    d.set_item("signal", obs.signal.map(Phase::name))?;
+    d.set_item("queues", obs.queue_lengths())?;
+    d.set_item("lanes", lanes)?;
+    d.set_item("vehicles", vehicles)?;
+    Ok(d)
+}
+
+#[pymodule]
+fn smart_road(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyEnv>()
+}
@@ -0,0 +1,212 @@
+use sdl2::image::{InitFlag, LoadTexture, SaveSurface, Sdl2ImageContext};
+use sdl2::pixels::{Color, PixelFormatEnum};
+use sdl2::rect::Rect;
+use sdl2::render::{Canvas, RenderTarget, Texture, TextureCreator};
+use sdl2::surface::{Surface, SurfaceContext};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::bicycle::draw_bike_lanes;
+use crate::config::JunctionKind;
+use crate::lane_change::draw_weaving_zones;
+use crate::map::{Tile, TileMap};
+use crate::pedestrian::draw_crosswalks;
+use crate::roundabout::draw_roundabout;
+use crate::signal::draw_signals;
+use crate::simulation::Simulation;
+use crate::vehicle::{Direction, Vehicle};
+
+pub type CarTextures<'a> = HashMap<(usize, Direction), Texture<'a>>;
+
+// =======================================================
+// 🎨 SCENE RENDERING (window or offscreen surface)
+// =======================================================
+
+fn asset(relative: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("assets").join(relative)
+}
+
+/// Tile and car sprites, loaded once per renderer
+pub struct SceneTextures<'a> {
+    grass: Texture<'a>,
+    pavement: Texture<'a>,
+    vertical_road: Texture<'a>,
+    horizontal_road: Texture<'a>,
+    intersection: Texture<'a>,
+    cars: CarTextures<'a>,
+}
+
+impl<'a> SceneTextures<'a> {
+    pub fn load<T>(creator: &'a TextureCreator<T>) -> Result<Self, String> {
+        let mut cars: CarTextures = HashMap::new();
+        for car_id in 1..=4 {
+            for dir in [Direction::Up, Direction::Down, Direction::Left, Direction::Right] {
+                let name = match dir {
+                    Direction::Up => "up",
+                    Direction::Down => "down",
+                    Direction::Left => "left",
+                    Direction::Right => "right",
+                };
+                let texture = creator.load_texture(asset(&format!("car{}-{}.png", car_id, name)))?;
+                cars.insert((car_id, dir), texture);
+            }
+        }
+
+        Ok(Self {
+            grass: creator.load_texture(asset("grass.png"))?,
+            pavement: creator.load_texture(asset("roads/pavement.png"))?,
+            vertical_road: creator.load_texture(asset("roads/vertical-road.png"))?,
+            horizontal_road: creator.load_texture(asset("roads/horizontal-road.png"))?,
+            intersection: creator.load_texture(asset("roads/intersection.png"))?,
+            cars,
+        })
+    }
+
+    fn tile(&self, tile: Tile) -> &Texture<'a> {
+        match tile {
+            Tile::Grass => &self.grass,
+            Tile::Pavement => &self.pavement,
+            Tile::VerticalRoad => &self.vertical_road,
+            Tile::HorizontalRoad => &self.horizontal_road,
+            Tile::Intersection | Tile::Crosswalk | Tile::StopLine => &self.intersection,
+        }
+    }
+}
+
+/// Draw one frame of the single-junction simulation
+pub fn draw_scene<T: RenderTarget>(
+    canvas: &mut Canvas<T>,
+    textures: &SceneTextures,
+    map: &TileMap,
+    sim: &Simulation,
+) {
+    let g = &*sim.road;
+    let t = g.lane_width;
+
+    // Grid background
+    for y in 0..map.height {
+        for x in 0..map.width {
+            let tile = map.get(x, y);
+            let dst = Rect::new(x * t, y * t, t as u32, t as u32);
+            canvas.copy(textures.tile(tile), None, dst).unwrap();
+
+            if tile == Tile::StopLine {
+                draw_stop_line(canvas, t, x, y, map.stop_line_is_horizontal(x, y));
+            }
+        }
+    }
+
+    if sim.config.junction == JunctionKind::Roundabout {
+        draw_roundabout(canvas, g);
+    }
+    if sim.config.bike_lanes {
+        draw_bike_lanes(canvas, g);
+    }
+    draw_crosswalks(canvas, &sim.crosswalks);
+    draw_weaving_zones(canvas, g);
+    if let Some(phase) = sim.signal {
+        draw_signals(canvas, g, phase);
+    }
+
+    // Draw cars
+    for v in &sim.vehicles {
+        v.draw(canvas, &textures.cars);
+    }
+
+    // Draw pedestrians
+    for p in &sim.pedestrians {
+        p.draw(canvas);
+    }
+
+    // Draw status info
+    draw_status_overlay(canvas, map.width * t);
+}
+
+fn draw_stop_line<T: RenderTarget>(canvas: &mut Canvas<T>, t: i32, x: i32, y: i32, horizontal: bool) {
+    const THICKNESS: i32 = 6;
+    let (px, py) = (x * t, y * t);
+    let rect = if horizontal {
+        Rect::new(px, py + (t - THICKNESS) / 2, t as u32, THICKNESS as u32)
+    } else {
+        Rect::new(px + (t - THICKNESS) / 2, py, THICKNESS as u32, t as u32)
+    };
+
+    canvas.set_draw_color(Color::RGB(245, 245, 245));
+    canvas.fill_rect(rect).unwrap();
+}
+
+fn draw_status_overlay<T: RenderTarget>(canvas: &mut Canvas<T>, width: i32) {
+    // Draw semi-transparent overlay across the top of the map
+    canvas.set_draw_color(Color::RGBA(0, 0, 0, 180));
+    canvas.fill_rect(Rect::new(0, 0, width as u32, 80)).unwrap();
+}
+
+/// Yellow outline around the vehicle the camera follows
+pub fn draw_selection<T: RenderTarget>(canvas: &mut Canvas<T>, v: &Vehicle) {
+    const MARGIN: f32 = 4.0;
+    let (x0, y0, x1, y1) = v.footprint();
+    let rect = Rect::new(
+        (x0 - MARGIN) as i32,
+        (y0 - MARGIN) as i32,
+        (x1 - x0 + MARGIN * 2.0) as u32,
+        (y1 - y0 + MARGIN * 2.0) as u32,
+    );
+    canvas.set_draw_color(Color::RGB(255, 230, 0));
+    canvas.draw_rect(rect).ok();
+}
+
+// =======================================================
+// 🖼️ FRAME EXPORT (no window needed)
+// =======================================================
+
+/// Software renderer that writes numbered PNG frames into a directory
+pub struct FrameExporter {
+    canvas: Canvas<Surface<'static>>,
+    dir: PathBuf,
+    written: u32,
+    _image: Sdl2ImageContext, // Keeps PNG support loaded
+}
+
+impl FrameExporter {
+    /// `tile` is the lane width of the road layout (px per map tile)
+    pub fn new(map: &TileMap, tile: i32, dir: &Path) -> Result<Self, String> {
+        let image = sdl2::image::init(InitFlag::PNG)?;
+        std::fs::create_dir_all(dir).map_err(|e| format!("cannot create {}: {}", dir.display(), e))?;
+
+        let (w, h) = ((map.width * tile) as u32, (map.height * tile) as u32);
+        let surface = Surface::new(w, h, PixelFormatEnum::RGB888)?;
+        let canvas = surface.into_canvas()?;
+
+        Ok(Self { canvas, dir: dir.to_path_buf(), written: 0, _image: image })
+    }
+
+    pub fn texture_creator(&self) -> TextureCreator<SurfaceContext<'static>> {
+        self.canvas.texture_creator()
+    }
+
+    /// Render `sim` and save it as the next frame_NNNNN.png
+    pub fn export(&mut self, textures: &SceneTextures, map: &TileMap, sim: &Simulation) -> Result<PathBuf, String> {
+        self.canvas.set_draw_color(Color::RGB(0, 0, 0));
+        self.canvas.clear();
+        draw_scene(&mut self.canvas, textures, map, sim);
+        self.canvas.present();
+
+        let path = self.dir.join(format!("frame_{:05}.png", self.written));
+        save_png(&self.canvas, &path)?;
+        self.written += 1;
+        Ok(path)
+    }
+
+    pub fn frames_written(&self) -> u32 {
+        self.written
+    }
+}
+
+/// Save what has been drawn on an offscreen canvas
+pub fn save_png(canvas: &Canvas<Surface>, path: &Path) -> Result<(), String> {
+    // Copy the pixels out: only owned surfaces can be saved as PNG
+    let (w, h) = canvas.output_size()?;
+    let mut pixels = canvas.read_pixels(None, PixelFormatEnum::RGB24)?;
+    let frame = Surface::from_data(&mut pixels, w, h, w * 3, PixelFormatEnum::RGB24)?;
+    frame.save(path)
+}
@@ -0,0 +1,232 @@
+use std::fmt;
+use std::path::Path;
+
+use crate::config::{JunctionKind, SimConfig};
+use crate::simulation::{Command, Simulation, STEP};
+use crate::vehicle::{Direction, Route, VelocityLevel};
+
+// =======================================================
+// 🎞️ RECORD & REPLAY
+// =======================================================
+//
+// A recording is the seed, the command-line flags that shape the run and
+// every input with the step it was applied before. Steps are fixed-length,
+// so replaying the inputs on the same seed reproduces the run exactly.
+//
+//     ; smart-road recording
+//     seed 42
+//     arg --junction
+//     arg roundabout
+//     steps 3600
+//     120 spawn up
+//     200 spawn left uturn
+//     300 velocity fast
+//     310 auto
+
+// Flags that control recording and output rather than the simulation
+const SESSION_FLAGS: [&str; 8] =
+    ["--record", "--replay", "--seed", "--frames", "--frame-interval", "--heatmap", "--v2i-log", "--api"];
+const SESSION_SWITCHES: [&str; 3] = ["--headless", "--abort-on-violation", "--latency-sweep"];
+
+#[derive(Debug)]
+pub enum RecordingError {
+    Io(std::io::Error),
+    BadLine { line: usize, text: String },
+    MissingSeed,
+}
+
+impl fmt::Display for RecordingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RecordingError::Io(e) => write!(f, "cannot access recording: {}", e),
+            RecordingError::BadLine { line, text } => write!(f, "line {}: cannot read '{}'", line, text),
+            RecordingError::MissingSeed => write!(f, "recording has no seed"),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Recording {
+    pub seed: u64,
+    pub args: Vec<String>,
+    pub events: Vec<(u64, Command)>,
+    pub steps: u64, // Length of the recorded run
+}
+
+impl Recording {
+    /// Capture a finished (or running) simulation started with `args`
+    pub fn capture(sim: &Simulation, args: &[String]) -> Self {
+        Self {
+            seed: sim.config.seed.expect("simulations always pick a seed"),
+            args: simulation_args(args),
+            events: sim.log.clone(),
+            steps: sim.steps,
+        }
+    }
+
+    /// Configuration of the recorded run
+    pub fn config(&self) -> SimConfig {
+        let mut config = SimConfig::from_arg_list(&self.args);
+        config.seed = Some(self.seed);
+        // The road comes from --map as in the recorded run (main has already checked the map loads)
+        config.load_map().ok();
+        config
+    }
+
+    pub fn duration(&self) -> f32 {
+        self.steps as f32 * STEP
+    }
+
+    /// Apply the inputs recorded for the next step, then take it
+    pub fn advance(&self, sim: &mut Simulation) {
+        let step = sim.steps;
+        let start = self.events.partition_point(|(s, _)| *s < step);
+        for &(_, command) in self.events[start..].iter().take_while(|(s, _)| *s == step) {
+            sim.apply(command);
+        }
+        sim.step(STEP);
+    }
+
+    /// A fresh run replayed up to `step` (scrubbing re-simulates from the start)
+    pub fn simulate_to(&self, step: u64) -> Simulation {
+        let mut sim = Simulation::new(self.config());
+        while sim.steps < step.min(self.steps) {
+            self.advance(&mut sim);
+        }
+        sim
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), RecordingError> {
+        let mut text = String::from("; smart-road recording\n");
+        text += &format!("seed {}\n", self.seed);
+        for arg in &self.args {
+            text += &format!("arg {}\n", arg);
+        }
+        text += &format!("steps {}\n", self.steps);
+        for (step, command) in &self.events {
+            text += &format!("{} {}\n", step, command_to_text(*command));
+        }
+
+        std::fs::write(path, text).map_err(RecordingError::Io)
+    }
+
+    pub fn load(path: &Path) -> Result<Recording, RecordingError> {
+        let text = std::fs::read_to_string(path).map_err(RecordingError::Io)?;
+        Recording::parse(&text)
+    }
+
+    pub fn parse(text: &str) -> Result<Recording, RecordingError> {
+        let mut seed = None;
+        let mut args = Vec::new();
+        let mut events = Vec::new();
+        let mut steps = 0;
+
+        for (i, raw) in text.lines().enumerate() {
+            let row = raw.trim_end();
+            if row.is_empty() || row.starts_with(';') {
+                continue;
+            }
+            let bad = || RecordingError::BadLine { line: i + 1, text: row.to_string() };
+
+            let (key, rest) = row.split_once(' ').ok_or_else(bad)?;
+            match key {
+                "seed" => seed = Some(rest.parse().map_err(|_| bad())?),
+                "arg" => args.push(rest.to_string()),
+                "steps" => steps = rest.parse().map_err(|_| bad())?,
+                _ => {
+                    let step: u64 = key.parse().map_err(|_| bad())?;
+                    let command = command_from_text(rest).ok_or_else(bad)?;
+                    events.push((step, command));
+                }
+            }
+        }
+
+        // Inputs are looked up by step
+        events.sort_by_key(|(step, _)| *step);
+        let steps = steps.max(events.last().map_or(0, |(s, _)| s + 1));
+
+        Ok(Recording { seed: seed.ok_or(RecordingError::MissingSeed)?, args, events, steps })
+    }
+}
+
+/// Command-line flags minus the ones about recording, replaying and seeding
+fn simulation_args(args: &[String]) -> Vec<String> {
+    let mut kept = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if SESSION_FLAGS.contains(&arg.as_str()) {
+            iter.next(); // Skip the flag's value too
+        } else if !SESSION_SWITCHES.contains(&arg.as_str()) {
+            kept.push(arg.clone());
+        }
+    }
+    kept
+}
+
+fn direction_name(dir: Direction) -> &'static str {
+    match dir {
+        Direction::Up => "up",
+        Direction::Down => "down",
+        Direction::Left => "left",
+        Direction::Right => "right",
+    }
+}
+
+fn route_name(route: Route) -> &'static str {
+    match route {
+        Route::Right => "right",
+        Route::Straight => "straight",
+        Route::Left => "left",
+        Route::UTurn => "uturn",
+    }
+}
+
+fn command_to_text(command: Command) -> String {
+    match command {
+        Command::SpawnRandom => "spawn".into(),
+        Command::SpawnVehicle(dir) => format!("spawn {}", direction_name(dir)),
+        Command::SpawnRoute(dir, route) => format!("spawn {} {}", direction_name(dir), route_name(route)),
+        Command::SpawnUTurn => "uturn".into(),
+        Command::SpawnPedestrian => "pedestrian".into(),
+        Command::SpawnBicycle => "bicycle".into(),
+        Command::SetVelocity(VelocityLevel::Slow) => "velocity slow".into(),
+        Command::SetVelocity(VelocityLevel::Medium) => "velocity medium".into(),
+        Command::SetVelocity(VelocityLevel::Fast) => "velocity fast".into(),
+        Command::ToggleAutoSpawn => "auto".into(),
+        Command::SetJunction(JunctionKind::Cross) => "junction cross".into(),
+        Command::SetJunction(JunctionKind::Roundabout) => "junction roundabout".into(),
+    }
+}
+
+fn command_from_text(text: &str) -> Option<Command> {
+    let mut words = text.split_whitespace();
+    let command = match (words.next()?, words.next()) {
+        ("spawn", Some(dir)) => {
+            let dir = [Direction::Up, Direction::Down, Direction::Left, Direction::Right]
+                .into_iter()
+                .find(|d| direction_name(*d) == dir)?;
+            match words.next() {
+                None => Command::SpawnVehicle(dir),
+                Some(route) => {
+                    let route = [Route::Right, Route::Straight, Route::Left, Route::UTurn]
+                        .into_iter()
+                        .find(|r| route_name(*r) == route)?;
+                    Command::SpawnRoute(dir, route)
+                }
+            }
+        }
+        ("spawn", None) => Command::SpawnRandom,
+        ("uturn", None) => Command::SpawnUTurn,
+        ("pedestrian", None) => Command::SpawnPedestrian,
+        ("bicycle", None) => Command::SpawnBicycle,
+        ("velocity", Some("slow")) => Command::SetVelocity(VelocityLevel::Slow),
+        ("velocity", Some("medium")) => Command::SetVelocity(VelocityLevel::Medium),
+        ("velocity", Some("fast")) => Command::SetVelocity(VelocityLevel::Fast),
+        ("auto", None) => Command::ToggleAutoSpawn,
+        ("junction", Some("cross")) => Command::SetJunction(JunctionKind::Cross),
+        ("junction", Some("roundabout")) => Command::SetJunction(JunctionKind::Roundabout),
+        _ => return None,
+    };
+
+    words.next().is_none().then_some(command)
+}
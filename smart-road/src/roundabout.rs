@@ -0,0 +1,226 @@
+use sdl2::pixels::Color;
+use sdl2::rect::Rect;
+use sdl2::render::{Canvas, RenderTarget};
+
+use crate::config::JunctionKind;
+use crate::geometry::RoadGeometry;
+use crate::spatial::Neighborhood;
+use crate::vehicle::{exit_side, progress_along, Direction, Route, Vehicle, VelocityLevel};
+
+// ⭕ Ring geometry, inset from the junction box edge (pixels)
+const RING_EDGE_INSET: f32 = 7.0; // Outer ring edge inside the box
+const RING_HALF_WIDTH: f32 = 40.0; // Circulating lane centerline to outer edge
+const ISLAND_GAP: f32 = 38.0; // Circulating lane centerline to island
+const MIN_ISLAND_RADIUS: f32 = 10.0;
+const ENTRY_ANGLE_OFFSET: f32 = 22.0; // Degrees between an arm's axis and its entry/exit point
+const ARC_STEP: f32 = 15.0; // Degrees between ring waypoints
+
+// 🛑 Yield on entry
+const YIELD_ARC: f32 = 80.0; // Circulating vehicles this far upstream of an entry have priority
+const MERGE_ARC: f32 = 15.0; // ...as do vehicles that have only just passed it
+const HUMAN_YIELD_FACTOR: f32 = 1.5; // Autonomous cars leave human drivers on the ring a wider berth
+const YIELD_STOP_MARGIN: f32 = 6.0;
+const YIELD_SLOWDOWN_DISTANCE: f32 = 100.0;
+
+fn center(g: &RoadGeometry) -> (f32, f32) {
+    let c = g.junction_center();
+    (c, c)
+}
+
+/// (island, circulating centerline, outer edge) radii, scaled to the junction box
+fn radii(g: &RoadGeometry) -> (f32, f32, f32) {
+    let (lo, hi) = g.junction_edges();
+    let outer = (hi - lo) / 2.0 - RING_EDGE_INSET;
+    let ring = outer - RING_HALF_WIDTH;
+    ((ring - ISLAND_GAP).max(MIN_ISLAND_RADIUS), ring, outer)
+}
+
+/// Math angle (degrees, counter-clockwise, y up) of the middle of each arm
+fn side_angle(side: Direction) -> f32 {
+    match side {
+        Direction::Right => 0.0,
+        Direction::Up => 90.0,
+        Direction::Left => 180.0,
+        Direction::Down => -90.0,
+    }
+}
+
+fn ring_point(g: &RoadGeometry, angle: f32) -> (f32, f32) {
+    let (cx, cy) = center(g);
+    let (_, ring, _) = radii(g);
+    let a = angle.to_radians();
+    (cx + ring * a.cos(), cy - ring * a.sin())
+}
+
+/// Traffic circulates counter-clockwise and enters on the right half of its arm
+fn entry_angle(dir: Direction) -> f32 {
+    side_angle(dir.opposite()) + ENTRY_ANGLE_OFFSET
+}
+
+fn exit_angle(side: Direction) -> f32 {
+    side_angle(side) - ENTRY_ANGLE_OFFSET
+}
+
+// =======================================================
+// 🧭 PATH GENERATION
+// =======================================================
+
+/// Approach → yield line → counter-clockwise arc → exit lane. The route picks the
+/// exit: curb turn = 1st, straight = 2nd, far turn = 3rd, U-turn = 4th.
+pub fn build_roundabout_path(g: &RoadGeometry, dir: Direction, route: Route, lane: i32) -> Vec<(f32, f32)> {
+    let (lo, hi) = g.junction_edges();
+    let lane_c = g.lane_center(lane);
+    let spawn = g.spawn_coord(dir);
+
+    let mut path = match dir {
+        Direction::Up => vec![g.tile_center(lane, spawn), (lane_c, hi)],
+        Direction::Down => vec![g.tile_center(lane, spawn), (lane_c, lo)],
+        Direction::Left => vec![g.tile_center(spawn, lane), (hi, lane_c)],
+        Direction::Right => vec![g.tile_center(spawn, lane), (lo, lane_c)],
+    };
+
+    // Circulate
+    let side = exit_side(dir, route);
+    let start = entry_angle(dir);
+    let mut end = exit_angle(side);
+    while end <= start {
+        end += 360.0;
+    }
+    let mut a = start;
+    while a < end {
+        path.push(ring_point(g, a));
+        a += ARC_STEP;
+    }
+    path.push(ring_point(g, end));
+
+    // Leave on the exit lane the entry lane feeds
+    let out = g.exit_lane_tile(dir, route, lane);
+    let out_c = g.lane_center(out);
+    let gone = g.despawn_coord(side);
+    match side {
+        Direction::Up => path.extend([(out_c, lo), g.tile_center(out, gone)]),
+        Direction::Down => path.extend([(out_c, hi), g.tile_center(out, gone)]),
+        Direction::Left => path.extend([(lo, out_c), g.tile_center(gone, out)]),
+        Direction::Right => path.extend([(hi, out_c), g.tile_center(gone, out)]),
+    }
+
+    path
+}
+
+// =======================================================
+// 🛑 YIELD ON ENTRY
+// =======================================================
+
+impl Vehicle {
+    /// Angle of this vehicle around the ring center (degrees)
+    fn ring_angle(&self) -> f32 {
+        let (cx, cy) = center(&self.road);
+        (-(self.y - cy)).atan2(self.x - cx).to_degrees()
+    }
+
+    pub fn is_circulating(&self) -> bool {
+        let (cx, cy) = center(&self.road);
+        let (island, _, outer) = radii(&self.road);
+        let r = ((self.x - cx).powi(2) + (self.y - cy).powi(2)).sqrt();
+        self.junction == JunctionKind::Roundabout && r > island && r < outer
+    }
+
+    /// A circulating vehicle will pass `angle`, at most `arc` degrees ahead,
+    /// before reaching its exit
+    fn passes_ring_angle(&self, angle: f32, arc: f32) -> bool {
+        let here = self.ring_angle();
+        let to_exit = (exit_angle(exit_side(self.direction, self.route)) - here).rem_euclid(360.0);
+        let to_point = (angle - here).rem_euclid(360.0);
+
+        (to_point <= to_exit && to_point <= arc) || to_point >= 360.0 - MERGE_ARC
+    }
+
+    /// Speed cap from giving way to circulating traffic at the yield line
+    pub fn roundabout_speed_limit(&self, others: Neighborhood) -> f32 {
+        if self.junction != JunctionKind::Roundabout || self.entered_intersection {
+            return f32::MAX;
+        }
+
+        let front = progress_along(self.direction, self.x, self.y) + self.half_length();
+        let gap = self.road.stop_line(self.direction) - front; // Yield line
+        if gap < 0.0 {
+            return f32::MAX; // Already committed to the ring
+        }
+
+        let entry = entry_angle(self.direction);
+        let (cx, cy) = center(&self.road);
+        let (_, _, outer) = radii(&self.road);
+        // Human drivers judge the gap by eye; autonomous ones play safe around them
+        let arc = |o: &Vehicle| match (self.driver.is_some(), o.driver.is_some()) {
+            (true, _) => YIELD_ARC * self.gap_acceptance(),
+            (false, true) => YIELD_ARC * HUMAN_YIELD_FACTOR,
+            (false, false) => YIELD_ARC,
+        };
+        let must_yield = others
+            .within(cx, cy, outer)
+            .any(|o| o.id != self.id && o.is_circulating() && o.passes_ring_angle(entry, arc(o)));
+
+        if !must_yield {
+            f32::MAX
+        } else if gap < self.braking_distance() + YIELD_STOP_MARGIN * 2.0 {
+            0.0
+        } else if gap < YIELD_SLOWDOWN_DISTANCE {
+            self.kind.speed(VelocityLevel::Slow)
+        } else {
+            f32::MAX
+        }
+    }
+}
+
+// =======================================================
+// 🎨 DRAWING
+// =======================================================
+
+fn fill_circle<T: RenderTarget>(canvas: &mut Canvas<T>, cx: f32, cy: f32, r: f32) {
+    for dy in -(r as i32)..=(r as i32) {
+        let half = (r * r - (dy * dy) as f32).max(0.0).sqrt() as i32;
+        canvas
+            .fill_rect(Rect::new(cx as i32 - half, cy as i32 + dy, (half * 2) as u32, 1))
+            .ok();
+    }
+}
+
+/// Paint the ring, the central island and yield markings over the junction tiles
+pub fn draw_roundabout<T: RenderTarget>(canvas: &mut Canvas<T>, g: &RoadGeometry) {
+    let (cx, cy) = center(g);
+    let (island, _, outer) = radii(g);
+
+    canvas.set_draw_color(Color::RGB(55, 55, 55));
+    fill_circle(canvas, cx, cy, outer);
+
+    // Dashed lane edge around the island
+    canvas.set_draw_color(Color::RGB(200, 200, 200));
+    let mut a: f32 = 0.0;
+    while a < 360.0 {
+        let r = island + 6.0;
+        let (x, y) = (cx + r * a.to_radians().cos(), cy - r * a.to_radians().sin());
+        canvas.fill_rect(Rect::new(x as i32 - 1, y as i32 - 1, 3, 3)).ok();
+        a += 6.0;
+    }
+
+    canvas.set_draw_color(Color::RGB(70, 140, 60));
+    fill_circle(canvas, cx, cy, island);
+
+    // Yield "shark teeth" across each entry half
+    canvas.set_draw_color(Color::RGB(240, 240, 240));
+    let (lo, hi) = g.junction_edges();
+    for dir in [Direction::Up, Direction::Down, Direction::Left, Direction::Right] {
+        for lane in g.entry_lanes(dir) {
+            let c = g.lane_center(lane) as i32;
+            for k in [-15, 0, 15] {
+                let rect = match dir {
+                    Direction::Up => Rect::new(c + k - 4, hi as i32 - 8, 8, 6),
+                    Direction::Down => Rect::new(c + k - 4, lo as i32 + 2, 8, 6),
+                    Direction::Left => Rect::new(hi as i32 - 8, c + k - 4, 6, 8),
+                    Direction::Right => Rect::new(lo as i32 + 2, c + k - 4, 6, 8),
+                };
+                canvas.fill_rect(rect).ok();
+            }
+        }
+    }
+}
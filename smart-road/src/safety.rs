@@ -0,0 +1,241 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::driver::Autonomy;
+use crate::network::{NetState, RoadNetwork};
+use crate::spatial::{Neighborhood, SpatialHash};
+use crate::stats::Stats;
+use crate::vehicle::{Vehicle, VelocityLevel, VEHICLE_HALF_LENGTH};
+
+// =======================================================
+// 🛡️ SAFETY MONITOR (invariants checked after every step)
+// =======================================================
+//
+// Catches policy regressions the moment they happen instead of at the end
+// of a run. Each violation is logged with the vehicle's full state and
+// counted once per episode: a pair that overlaps for a second is one
+// violation, not sixty.
+//
+// Authorization is checked where a manager hands out reservations: the
+// network junctions, and the single cross for autonomous cars (drivers and
+// cyclists never ask). Under signal control the light binds everyone too:
+// entering on red or without a grant is unauthorized unless the vehicle was
+// already too close to stop.
+
+pub const STALL_TIMEOUT: f32 = 30.0; // Simulated seconds without reaching the next waypoint
+const OVERLAP_TOLERANCE: f32 = 0.5; // Bodies may touch, not overlap (px)
+const BACKWARD_TOLERANCE: f32 = 0.01; // Float noise in a step's displacement (px)
+const SPEED_TOLERANCE: f32 = 0.01;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Invariant {
+    Overlap,      // Two vehicle footprints intersect
+    Backwards,    // Moved away from the waypoint it was heading for
+    Speed,        // Speed outside [0, fastest cruise speed]
+    Unauthorized, // Inside a junction without a reservation, or entered on red
+    Stalled,      // `current_target` did not advance within STALL_TIMEOUT, unless held
+}
+
+impl Invariant {
+    pub fn name(self) -> &'static str {
+        match self {
+            Invariant::Overlap => "overlap",
+            Invariant::Backwards => "backwards",
+            Invariant::Speed => "speed",
+            Invariant::Unauthorized => "unauthorized",
+            Invariant::Stalled => "stalled",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Violation {
+    pub step: u64,
+    pub invariant: Invariant,
+    pub vehicle: usize,
+    pub involved: Vec<Autonomy>, // Classes of the cars involved (cyclists aside)
+    pub state: String,           // Everything we know about the vehicle(s) at the time
+}
+
+pub struct SafetyMonitor {
+    pub abort_on_violation: bool,
+    pub violations: Vec<Violation>,
+    grid: SpatialHash,
+    progress: HashMap<usize, (usize, f32)>, // Id → (current_target, seconds spent on it)
+    active: HashSet<(Invariant, usize, usize)>, // Ongoing episodes, reported once
+    stoppable_on_red: HashSet<usize>, // Saw red while it could still stop before the line
+}
+
+impl SafetyMonitor {
+    pub fn new(abort_on_violation: bool) -> Self {
+        Self {
+            abort_on_violation,
+            violations: Vec::new(),
+            grid: SpatialHash::new(),
+            progress: HashMap::new(),
+            active: HashSet::new(),
+            stoppable_on_red: HashSet::new(),
+        }
+    }
+
+    /// A violation happened and the run should stop
+    pub fn aborted(&self) -> bool {
+        self.abort_on_violation && !self.violations.is_empty()
+    }
+
+    /// Check one single-junction step: `before` is last step's state,
+    /// `after` the same vehicles once updated
+    pub fn check(&mut self, step: u64, before: &[Vehicle], after: &[Vehicle], dt: f32, stats: &mut Stats) {
+        let mut found = Vec::new();
+
+        for (old, v) in before.iter().zip(after).filter(|(old, v)| old.id == v.id) {
+            if let Some(&(tx, ty)) = old.path.get(old.current_target) {
+                let (dx, dy) = (tx - old.x, ty - old.y);
+                let len = (dx * dx + dy * dy).sqrt();
+                let moved = ((v.x - old.x) * dx + (v.y - old.y) * dy) / len.max(f32::EPSILON);
+                if moved < -BACKWARD_TOLERANCE {
+                    let detail = format!("moved {:.2}px away from waypoint {} ({:.1}, {:.1})", -moved, old.current_target, tx, ty);
+                    found.push((Invariant::Backwards, v.id, v.id, format!("{}\n    {}", detail, describe(v))));
+                }
+            }
+
+            // A red light binds whoever could still stop for it
+            if !old.red_light {
+                self.stoppable_on_red.remove(&v.id);
+            } else if old.can_stop_at_signal() {
+                self.stoppable_on_red.insert(v.id);
+            }
+            if v.entered_intersection && !old.entered_intersection && old.red_light && self.stoppable_on_red.contains(&v.id) {
+                found.push((Invariant::Unauthorized, v.id, v.id, format!("entered the junction on red\n    {}", describe(v))));
+            }
+            if v.entered_intersection && !old.entered_intersection && old.talks_v2i() && !old.v2i.granted && old.can_stop_at_signal() {
+                found.push((Invariant::Unauthorized, v.id, v.id, format!("entered the junction without a grant\n    {}", describe(v))));
+            }
+        }
+
+        let by_id: HashMap<usize, &Vehicle> = after.iter().map(|v| (v.id, v)).collect();
+        for v in after {
+            let max = v.kind.speed(VelocityLevel::Fast) + SPEED_TOLERANCE;
+            if !(0.0..=max).contains(&v.speed) {
+                let detail = format!("speed {:.2} outside [0, {:.0}]", v.speed, max);
+                found.push((Invariant::Speed, v.id, v.id, format!("{}\n    {}", detail, describe(v))));
+            }
+
+            let (target, waited) = self.progress.entry(v.id).or_insert((v.current_target, 0.0));
+            if *target == v.current_target && v.current_target < v.path.len() {
+                // Waiting out a red light or a crossing is not being stuck
+                if !held(v, &by_id) {
+                    *waited += dt;
+                }
+            } else {
+                *target = v.current_target;
+                *waited = 0.0;
+            }
+            if *waited > STALL_TIMEOUT {
+                let detail = format!("stuck on waypoint {} for {:.1}s", v.current_target, waited);
+                found.push((Invariant::Stalled, v.id, v.id, format!("{}\n    {}", detail, describe(v))));
+            }
+        }
+        let present: HashSet<usize> = after.iter().map(|v| v.id).collect();
+        self.progress.retain(|id, _| present.contains(id));
+        self.stoppable_on_red.retain(|id| present.contains(id));
+
+        self.grid.rebuild(after);
+        let neighbors = Neighborhood::new(after, &self.grid);
+        for v in after {
+            for o in neighbors.within(v.x, v.y, VEHICLE_HALF_LENGTH * 3.0) {
+                let gap = v.footprint_gap(o);
+                if o.id > v.id && gap < -OVERLAP_TOLERANCE {
+                    let detail = format!("footprints overlap by {:.1}px", -gap);
+                    let state = format!("{}\n    {}\n    {}", detail, describe(v), describe(o));
+                    found.push((Invariant::Overlap, v.id, o.id, state));
+                }
+            }
+        }
+
+        let classes = after.iter().filter(|v| !v.is_bicycle()).map(|v| (v.id, v.autonomy())).collect();
+        self.report(step, found, &classes, stats);
+    }
+
+    /// Check a network step: every vehicle crossing a junction must hold a
+    /// reservation from its manager
+    pub fn check_network(&mut self, step: u64, network: &RoadNetwork, stats: &mut Stats) {
+        let mut found = Vec::new();
+
+        for v in network.vehicles.values() {
+            if v.speed < -SPEED_TOLERANCE || !v.speed.is_finite() {
+                found.push((Invariant::Speed, v.id, v.id, format!("speed {:.2} below 0\n    {:?}", v.speed, v)));
+            }
+            if let NetState::Crossing { .. } = v.state {
+                let junction = network.links[v.links[v.leg]].to;
+                if !network.nodes[junction].manager.is_authorized(v.id) {
+                    let detail = format!("crossing junction {} without a reservation", junction);
+                    found.push((Invariant::Unauthorized, v.id, v.id, format!("{}\n    {:?}", detail, v)));
+                }
+            }
+        }
+
+        let classes = network.vehicles.values().map(|v| (v.id, v.autonomy())).collect();
+        self.report(step, found, &classes, stats);
+    }
+
+    /// Log and count episodes that just started; forget the ones that ended
+    fn report(
+        &mut self,
+        step: u64,
+        found: Vec<(Invariant, usize, usize, String)>,
+        classes: &HashMap<usize, Autonomy>,
+        stats: &mut Stats,
+    ) {
+        let mut still = HashSet::new();
+
+        for (invariant, a, b, state) in found {
+            let key = (invariant, a, b);
+            if still.insert(key) && !self.active.contains(&key) {
+                eprintln!("🛡️  Safety violation at step {} ({}): vehicle {}: {}", step, invariant.name(), a, state);
+                let ids = if a == b { vec![a] } else { vec![a, b] };
+                let involved: Vec<Autonomy> = ids.iter().filter_map(|id| classes.get(id).copied()).collect();
+                stats.record_violation(invariant, &involved);
+                self.violations.push(Violation { step, invariant, vehicle: a, involved, state });
+            }
+        }
+        self.active = still;
+    }
+}
+
+/// Held by a light, crosswalk or cyclist, or queued behind someone who is
+fn held(v: &Vehicle, by_id: &HashMap<usize, &Vehicle>) -> bool {
+    let mut v = v;
+    for _ in 0..by_id.len() {
+        if v.held {
+            return true;
+        }
+        match v.leader.and_then(|id| by_id.get(&id)) {
+            Some(leader) => v = leader,
+            None => return false,
+        }
+    }
+    false // Leaders waiting on each other in a loop
+}
+
+/// One-line dump of everything that drives a vehicle's motion
+fn describe(v: &Vehicle) -> String {
+    format!(
+        "#{} {:?} ({}) {:?} {:?} lane {} at ({:.1}, {:.1}) speed {:.1}/{:.1} waypoint {}/{} leader {:?} held {} lane change {} in junction {}",
+        v.id,
+        v.kind,
+        v.autonomy().name(),
+        v.direction,
+        v.route,
+        v.lane,
+        v.x,
+        v.y,
+        v.speed,
+        v.target_speed,
+        v.current_target,
+        v.path.len(),
+        v.leader,
+        v.held,
+        v.lane_change.is_some(),
+        v.is_in_intersection(),
+    )
+}
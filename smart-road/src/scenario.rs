@@ -0,0 +1,268 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::config::SimConfig;
+use crate::geometry::ExitLane;
+use crate::safety::{Invariant, Violation};
+use crate::simulation::{Simulation, STEP};
+use crate::stats::Stats;
+use crate::vehicle::{Direction, Route, Vehicle, VehicleKind};
+
+// =======================================================
+// 🧪 SCENARIOS (scripted headless runs for tests)
+// =======================================================
+//
+// A scenario is a seed, a layout, a spawn list and a time limit. Running it
+// headless yields an `Outcome` that tests make assertions on:
+//
+//     let outcome = Scenario::new(7)
+//         .with_spawn(0.0, Spawn::Vehicle(Direction::Up, Route::Left))
+//         .run();
+//     outcome.assert_exits(0, Direction::Left);
+//     outcome.assert_no_collisions();
+//
+// Scripted vehicles are referred to by their index in the spawn list. The
+// road geometry is process-wide, so scenarios in one test binary share it.
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Spawn {
+    Vehicle(Direction, Route),
+    Bicycle(Direction),
+    Pedestrian,
+}
+
+#[derive(Clone, Debug)]
+pub struct Scenario {
+    pub config: SimConfig,         // Seed, junction layout (the policy), duration, bike lanes...
+    pub spawns: Vec<(f32, Spawn)>, // What enters when (simulated seconds)
+    pub auto_spawn: bool,          // Random demand on top, as in headless runs
+}
+
+/// How a vehicle left the map
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Exit {
+    pub side: Direction,        // Edge of the map it drove off
+    pub lane: Option<ExitLane>, // None if it left beside the exit lanes
+    pub heading: Direction,     // Way it was last moving
+    pub time: f32,              // Simulated seconds since the start of the run
+}
+
+/// One vehicle's run through the junction
+#[derive(Clone, Debug)]
+pub struct Trip {
+    pub spawn: Option<usize>, // Index in `Scenario::spawns`; None if auto-spawned
+    pub kind: VehicleKind,
+    pub direction: Direction,
+    pub route: Route,
+    pub planned_exit: ExitLane, // Where its route leads (updated if it had to reroute)
+    pub spawned_at: f32,
+    pub exit: Option<Exit>,
+    pub delay: Option<f32>, // Time lost against driving the path alone at cruise speed
+}
+
+pub struct Outcome {
+    pub trips: Vec<Trip>,
+    pub violations: Vec<Violation>,
+    pub stats: Stats,
+    pub time: f32, // Simulated seconds actually run
+}
+
+/// Last state seen of a vehicle still on the map
+struct Tracked {
+    trip: usize,
+    x: f32,
+    y: f32,
+    heading: Direction, // Of the last movement
+    time_in_system: f32,
+    free_flow: f32, // Seconds the path takes at cruise speed
+}
+
+impl Scenario {
+    /// Empty scenario on the standard cross, one simulated minute at most
+    pub fn new(seed: u64) -> Self {
+        let config = SimConfig { seed: Some(seed), duration: 60.0, ..SimConfig::default() };
+        Self { config, spawns: Vec::new(), auto_spawn: false }
+    }
+
+    pub fn with_spawn(mut self, at: f32, spawn: Spawn) -> Self {
+        self.spawns.push((at, spawn));
+        self
+    }
+
+    /// Run to the time limit, or until every scripted vehicle has left
+    pub fn run(&self) -> Outcome {
+        let mut sim = Simulation::new(self.config.clone());
+        sim.auto_spawn = self.auto_spawn;
+
+        let mut pending: Vec<usize> = (0..self.spawns.len()).collect();
+        pending.sort_by(|&a, &b| self.spawns[a].0.total_cmp(&self.spawns[b].0));
+        pending.reverse(); // Next spawn at the back
+
+        let mut trips = Vec::new();
+        let mut tracked: HashMap<usize, Tracked> = HashMap::new();
+        let steps = (self.config.duration / STEP).round() as u64;
+
+        while sim.steps < steps {
+            // A blocked entry keeps the spawn queued for the next step
+            while let Some(&i) = pending.last() {
+                let (at, spawn) = self.spawns[i];
+                if at > sim.time() || !try_spawn(&mut sim, spawn) {
+                    break;
+                }
+                pending.pop();
+                if spawn != Spawn::Pedestrian {
+                    let v = sim.vehicles.last().unwrap();
+                    tracked.insert(v.id, start_trip(&mut trips, v, Some(i), sim.time()));
+                }
+            }
+
+            sim.step(STEP);
+
+            for v in &sim.vehicles {
+                let t = tracked.entry(v.id).or_insert_with(|| start_trip(&mut trips, v, None, sim.time()));
+                if let Some(heading) = heading_of(v.x - t.x, v.y - t.y) {
+                    t.heading = heading;
+                }
+                (t.x, t.y, t.time_in_system) = (v.x, v.y, v.time_in_system);
+                trips[t.trip].planned_exit = v.exit;
+            }
+
+            // Vehicles only leave by driving off the map
+            let time = sim.time();
+            let present: HashSet<usize> = sim.vehicles.iter().map(|v| v.id).collect();
+            tracked.retain(|id, t| {
+                if present.contains(id) {
+                    return true;
+                }
+                let trip = &mut trips[t.trip];
+                let g = &sim.road;
+                let side = g.edge_past(t.x, t.y).unwrap_or(t.heading);
+                trip.exit = Some(Exit { side, lane: g.exit_lane_at(t.x, t.y), heading: t.heading, time });
+                trip.delay = Some((t.time_in_system - t.free_flow).max(0.0));
+                false
+            });
+
+            if !self.auto_spawn && pending.is_empty() && sim.vehicles.is_empty() {
+                break;
+            }
+        }
+
+        sim.finish();
+        Outcome {
+            trips,
+            violations: std::mem::take(&mut sim.safety.violations),
+            time: sim.time(),
+            stats: std::mem::take(&mut sim.stats),
+        }
+    }
+}
+
+/// False if the entry was too crowded to spawn
+fn try_spawn(sim: &mut Simulation, spawn: Spawn) -> bool {
+    let before = sim.vehicles.len();
+    match spawn {
+        Spawn::Vehicle(dir, route) => sim.spawn_vehicle(route, dir),
+        Spawn::Bicycle(dir) => sim.spawn_bicycle_from(dir),
+        Spawn::Pedestrian => {
+            sim.spawn_random_pedestrian();
+            return true;
+        }
+    }
+    sim.vehicles.len() > before
+}
+
+fn start_trip(trips: &mut Vec<Trip>, v: &Vehicle, spawn: Option<usize>, time: f32) -> Tracked {
+    let length: f32 = v.path.windows(2).map(|w| ((w[1].0 - w[0].0).powi(2) + (w[1].1 - w[0].1).powi(2)).sqrt()).sum();
+    trips.push(Trip {
+        spawn,
+        kind: v.kind,
+        direction: v.direction,
+        route: v.route,
+        planned_exit: v.exit,
+        spawned_at: time,
+        exit: None,
+        delay: None,
+    });
+    Tracked {
+        trip: trips.len() - 1,
+        x: v.x,
+        y: v.y,
+        heading: v.facing_direction(),
+        time_in_system: v.time_in_system,
+        free_flow: length / v.kind.speed(v.velocity_level),
+    }
+}
+
+/// Main axis of a step's movement; None when standing still
+fn heading_of(dx: f32, dy: f32) -> Option<Direction> {
+    if dx == 0.0 && dy == 0.0 {
+        None
+    } else if dx.abs() > dy.abs() {
+        Some(if dx > 0.0 { Direction::Right } else { Direction::Left })
+    } else {
+        Some(if dy > 0.0 { Direction::Down } else { Direction::Up })
+    }
+}
+
+impl Outcome {
+    /// Trip of the vehicle from spawn-list entry `spawn`
+    pub fn trip(&self, spawn: usize) -> &Trip {
+        self.trips
+            .iter()
+            .find(|t| t.spawn == Some(spawn))
+            .unwrap_or_else(|| panic!("spawn {} never entered the map", spawn))
+    }
+
+    pub fn collisions(&self) -> impl Iterator<Item = &Violation> {
+        self.violations.iter().filter(|v| v.invariant == Invariant::Overlap)
+    }
+
+    /// Mean delay over the vehicles that left the map
+    pub fn mean_delay(&self) -> f32 {
+        let delays: Vec<f32> = self.trips.iter().filter_map(|t| t.delay).collect();
+        if delays.is_empty() {
+            0.0
+        } else {
+            delays.iter().sum::<f32>() / delays.len() as f32
+        }
+    }
+
+    // ---------------------------------------------------
+    // ✅ Assertions (panic with the offending state)
+    // ---------------------------------------------------
+
+    pub fn assert_exits(&self, spawn: usize, side: Direction) {
+        let trip = self.trip(spawn);
+        match trip.exit {
+            Some(exit) => assert_eq!(exit.side, side, "spawn {} left on the wrong side: {:?}", spawn, trip),
+            None => panic!("spawn {} still on the map after {:.1}s: {:?}", spawn, self.time, trip),
+        }
+    }
+
+    /// Every vehicle that left did so by the lane its route leads to
+    pub fn assert_planned_exit_lanes(&self) {
+        for trip in &self.trips {
+            if let Some(exit) = trip.exit {
+                assert_eq!(exit.lane, Some(trip.planned_exit), "left by the wrong lane: {:?}", trip);
+            }
+        }
+    }
+
+    pub fn assert_all_exited(&self) {
+        let stuck: Vec<&Trip> = self.trips.iter().filter(|t| t.exit.is_none()).collect();
+        assert!(stuck.is_empty(), "{} vehicle(s) still on the map after {:.1}s: {:?}", stuck.len(), self.time, stuck);
+    }
+
+    pub fn assert_no_collisions(&self) {
+        let collisions: Vec<&Violation> = self.collisions().collect();
+        assert!(collisions.is_empty(), "{} collision(s): {:#?}", collisions.len(), collisions);
+    }
+
+    pub fn assert_no_violations(&self) {
+        assert!(self.violations.is_empty(), "{} safety violation(s): {:#?}", self.violations.len(), self.violations);
+    }
+
+    pub fn assert_mean_delay_below(&self, seconds: f32) {
+        let delay = self.mean_delay();
+        assert!(delay < seconds, "mean delay {:.2}s, expected below {:.2}s", delay, seconds);
+    }
+}
@@ -0,0 +1,102 @@
+use sdl2::pixels::Color;
+use sdl2::rect::Rect;
+use sdl2::render::{Canvas, RenderTarget};
+
+use crate::geometry::RoadGeometry;
+use crate::simulation::DIRECTIONS;
+use crate::vehicle::{progress_along, Direction, Vehicle, VelocityLevel};
+
+// =======================================================
+// 🚦 SIGNAL PHASES (external control, e.g. the RL environment)
+// =======================================================
+//
+// The junction normally runs without lights. A controller may switch one on:
+// approaches without green hold at the outer edge of their crosswalk. A car
+// too close to stop when its light turns red drives on, as it would on amber.
+
+const SIGNAL_STOP_MARGIN: f32 = 6.0;
+const SIGNAL_SLOWDOWN_DISTANCE: f32 = 100.0;
+const SIGNAL_BAR_WIDTH: u32 = 5;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Phase {
+    NorthSouth, // Up and Down traffic has green
+    EastWest,   // Left and Right traffic has green
+    AllRed,
+}
+
+impl Phase {
+    pub const ALL: [Phase; 3] = [Phase::NorthSouth, Phase::EastWest, Phase::AllRed];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Phase::NorthSouth => "north-south",
+            Phase::EastWest => "east-west",
+            Phase::AllRed => "all-red",
+        }
+    }
+
+    /// Traffic travelling `dir` may enter the junction
+    pub fn green(self, dir: Direction) -> bool {
+        match self {
+            Phase::NorthSouth => matches!(dir, Direction::Up | Direction::Down),
+            Phase::EastWest => matches!(dir, Direction::Left | Direction::Right),
+            Phase::AllRed => false,
+        }
+    }
+}
+
+impl Vehicle {
+    /// Speed cap from a red light ahead (`red_light` is set by the simulation)
+    pub fn signal_speed_limit(&self) -> f32 {
+        if !self.red_light || !self.can_stop_at_signal() {
+            return f32::MAX; // Green, past the line, or too late to stop before it
+        }
+
+        let gap = self.gap_to_stop_line();
+        if gap < self.braking_distance() + SIGNAL_STOP_MARGIN * 2.0 {
+            0.0
+        } else if gap < SIGNAL_SLOWDOWN_DISTANCE {
+            self.kind.speed(VelocityLevel::Slow)
+        } else {
+            f32::MAX
+        }
+    }
+
+    /// Room to brake before the stop line, give or take the margin a braking
+    /// car may overshoot it by (and still be held there)
+    pub fn can_stop_at_signal(&self) -> bool {
+        !self.entered_intersection && self.gap_to_stop_line() + SIGNAL_STOP_MARGIN >= self.braking_distance()
+    }
+
+    pub(crate) fn gap_to_stop_line(&self) -> f32 {
+        let front = progress_along(self.direction, self.x, self.y) + self.half_length();
+        self.road.approach_end(self.direction) - front
+    }
+}
+
+/// A red or green bar across each approach's entry lanes at its stop line
+pub fn draw_signals<T: RenderTarget>(canvas: &mut Canvas<T>, g: &RoadGeometry, phase: Phase) {
+    let t = g.lane_width;
+    for dir in DIRECTIONS {
+        let lanes = g.entry_lanes(dir);
+        let (Some(&first), Some(&last)) = (lanes.iter().min(), lanes.iter().max()) else {
+            continue;
+        };
+        let (from, len) = (first * t, ((last - first + 1) * t) as u32);
+
+        // Stop line coordinate along the road, bar drawn on the approach side
+        let end = g.approach_end(dir);
+        let at = match dir {
+            Direction::Up | Direction::Left => -end as i32,
+            Direction::Down | Direction::Right => end as i32 - SIGNAL_BAR_WIDTH as i32,
+        };
+        let bar = match dir {
+            Direction::Up | Direction::Down => Rect::new(from, at, len, SIGNAL_BAR_WIDTH),
+            Direction::Left | Direction::Right => Rect::new(at, from, SIGNAL_BAR_WIDTH, len),
+        };
+
+        canvas.set_draw_color(if phase.green(dir) { Color::RGB(40, 200, 70) } else { Color::RGB(220, 40, 40) });
+        canvas.fill_rect(bar).ok();
+    }
+}
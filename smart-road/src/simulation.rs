@@ -0,0 +1,299 @@
+use rand::prelude::IndexedRandom;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::sync::Arc;
+
+use crate::admission::JunctionController;
+use crate::bicycle::{spawn_bicycle, BikeSafetyTracker};
+use crate::config::{JunctionKind, SimConfig};
+use crate::driver::HumanDriver;
+use crate::geometry::RoadGeometry;
+use crate::heatmap::Heatmap;
+use crate::pedestrian::{build_crosswalks, spawn_pedestrian, update_pedestrians, Crosswalk, Pedestrian};
+use crate::perception::Sensor;
+use crate::safety::SafetyMonitor;
+use crate::signal::Phase;
+use crate::spatial::{Neighborhood, SpatialHash};
+use crate::stats::Stats;
+use crate::vehicle::{Direction, Route, Vehicle, VelocityLevel};
+
+pub const DIRECTIONS: [Direction; 4] = [Direction::Up, Direction::Down, Direction::Left, Direction::Right];
+pub const ROUTES: [Route; 3] = [Route::Right, Route::Straight, Route::Left];
+
+// ⏱️ Fixed simulation step (seconds); the window catches up in whole steps
+pub const STEP: f32 = 1.0 / 60.0;
+
+// ⏱️ Auto-spawn intervals (simulated seconds)
+const VEHICLE_SPAWN_INTERVAL: f32 = 0.8;
+const PEDESTRIAN_SPAWN_INTERVAL: f32 = 3.0;
+const BICYCLE_SPAWN_INTERVAL: f32 = 2.0;
+
+// =======================================================
+// 🌐 SIMULATION STATE (single junction)
+// =======================================================
+//
+// Vehicles are double-buffered: `front` holds last step's state and is only
+// read (through the spatial hash) while `vehicles` is updated in place.
+// Paths are shared between the buffers and human drivers' pending decisions
+// are left out of the copy (see driver.rs), so it stays cheap.
+
+/// A user input, applied before the next step (and logged for replays)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Command {
+    SpawnRandom,             // Random movement from a random approach
+    SpawnVehicle(Direction), // Random movement from this approach
+    SpawnRoute(Direction, Route),
+    SpawnUTurn,
+    SpawnPedestrian,
+    SpawnBicycle,
+    SetVelocity(VelocityLevel),
+    ToggleAutoSpawn,
+    SetJunction(JunctionKind), // New cars take the new layout; cars already in keep theirs
+}
+
+pub struct Simulation {
+    pub config: SimConfig,
+    pub road: Arc<RoadGeometry>, // config.road, shared with every vehicle
+    pub vehicles: Vec<Vehicle>,
+    front: Vec<Vehicle>,
+    grid: SpatialHash,
+    pub pedestrians: Vec<Pedestrian>,
+    pub crosswalks: Vec<Crosswalk>,
+    pub stats: Stats,
+    pub bike_tracker: BikeSafetyTracker,
+    pub heatmap: Heatmap,
+    pub safety: SafetyMonitor,
+    pub rng: StdRng,
+    pub auto_spawn: bool,
+    pub signal: Option<Phase>, // Lights set by an external controller (None: no lights)
+    pub controller: JunctionController, // Admission to the cross junction, over V2I
+    spawn_timers: [f32; 3], // Vehicles, pedestrians, cyclists
+    pub steps: u64,
+    pub log: Vec<(u64, Command)>, // Inputs by the step they were applied before
+}
+
+impl Simulation {
+    /// Seeded demand so junction layouts can be compared on identical traffic;
+    /// unseeded runs pick a seed so they can still be recorded
+    pub fn new(mut config: SimConfig) -> Self {
+        let seed = *config.seed.get_or_insert_with(rand::random);
+        let safety = SafetyMonitor::new(config.abort_on_violation);
+        let road = Arc::new(config.road.clone());
+        let controller = JunctionController::new(&config, &road);
+
+        Self {
+            config,
+            vehicles: Vec::new(),
+            front: Vec::new(),
+            grid: SpatialHash::new(),
+            pedestrians: Vec::new(),
+            crosswalks: build_crosswalks(&road),
+            stats: Stats::new(),
+            bike_tracker: BikeSafetyTracker::new(),
+            heatmap: Heatmap::new(&road),
+            road,
+            safety,
+            rng: StdRng::seed_from_u64(seed),
+            auto_spawn: false,
+            signal: None,
+            controller,
+            spawn_timers: [0.0; 3],
+            steps: 0,
+            log: Vec::new(),
+        }
+    }
+
+    pub fn spawn_vehicle(&mut self, route: Route, dir: Direction) {
+        // Any entry lane - the car weaves into its route lane before the intersection
+        let g = &self.road;
+        let lane_tile = *g.spawn_lanes(dir).choose(&mut self.rng).unwrap();
+        let lane_c = g.lane_center(lane_tile);
+        let far = (g.pixel_size() + 50) as f32;
+
+        let (x, y): (f32, f32) = match dir {
+            Direction::Up => (lane_c, far),
+            Direction::Down => (lane_c, -50.0),
+            Direction::Left => (far, lane_c),
+            Direction::Right => (-50.0, lane_c),
+        };
+
+        // Check if spawn position is too close to existing vehicles
+        const MIN_SPAWN_DISTANCE: f32 = 120.0;
+        let blocked = self
+            .vehicles
+            .iter()
+            .any(|v| ((v.x - x).powi(2) + (v.y - y).powi(2)).sqrt() < MIN_SPAWN_DISTANCE);
+        if blocked {
+            return;
+        }
+
+        let car_id = self.rng.random_range(1..=4);
+        let mut vehicle = Vehicle::new(&self.road, dir, route, car_id, lane_tile, self.config.junction);
+        vehicle.driver = HumanDriver::at_penetration(&mut self.rng, self.config.av_penetration);
+
+        if !vehicle.path.is_empty() {
+            Arc::make_mut(&mut vehicle.path)[0] = (x, y);
+            vehicle.x = x;
+            vehicle.y = y;
+        }
+
+        self.stats.record_spawn(dir, route);
+        self.stats.class(vehicle.autonomy()).spawned += 1;
+        self.vehicles.push(vehicle);
+    }
+
+    /// Random movement from the given approach
+    pub fn spawn_from(&mut self, dir: Direction) {
+        let route = *ROUTES.choose(&mut self.rng).unwrap();
+        self.spawn_vehicle(route, dir);
+    }
+
+    pub fn spawn_random_vehicle(&mut self) {
+        let dir = *DIRECTIONS.choose(&mut self.rng).unwrap();
+        self.spawn_from(dir);
+    }
+
+    pub fn spawn_random_pedestrian(&mut self) {
+        let crosswalk = self.rng.random_range(0..self.crosswalks.len());
+        let forward = self.rng.random_bool(0.5);
+        spawn_pedestrian(&mut self.pedestrians, &self.crosswalks, &mut self.stats, crosswalk, forward);
+    }
+
+    pub fn spawn_bicycle_from(&mut self, dir: Direction) {
+        spawn_bicycle(&mut self.vehicles, &mut self.stats, &self.road, dir, &self.config);
+    }
+
+    pub fn spawn_random_bicycle(&mut self) {
+        let dir = *DIRECTIONS.choose(&mut self.rng).unwrap();
+        self.spawn_bicycle_from(dir);
+    }
+
+    pub fn spawn_u_turn(&mut self) {
+        let dir = *DIRECTIONS.choose(&mut self.rng).unwrap();
+        self.spawn_vehicle(Route::UTurn, dir);
+    }
+
+    pub fn apply(&mut self, command: Command) {
+        self.log.push((self.steps, command));
+
+        match command {
+            Command::SpawnRandom => self.spawn_random_vehicle(),
+            Command::SpawnVehicle(dir) => self.spawn_from(dir),
+            Command::SpawnRoute(dir, route) => self.spawn_vehicle(route, dir),
+            Command::SpawnUTurn => self.spawn_u_turn(),
+            Command::SpawnPedestrian => self.spawn_random_pedestrian(),
+            Command::SpawnBicycle => self.spawn_random_bicycle(),
+            Command::SetVelocity(level) => {
+                for v in &mut self.vehicles {
+                    v.set_velocity_level(level);
+                }
+            }
+            Command::ToggleAutoSpawn => self.auto_spawn = !self.auto_spawn,
+            Command::SetJunction(kind) => self.config.junction = kind,
+        }
+    }
+
+    /// Simulated time so far (seconds)
+    pub fn time(&self) -> f32 {
+        self.steps as f32 * STEP
+    }
+
+    fn update_auto_spawn(&mut self, dt: f32) {
+        if !self.auto_spawn {
+            return;
+        }
+        for t in &mut self.spawn_timers {
+            *t += dt;
+        }
+
+        if self.spawn_timers[0] > VEHICLE_SPAWN_INTERVAL {
+            self.spawn_random_vehicle();
+            self.spawn_timers[0] = 0.0;
+        }
+        if self.spawn_timers[1] > PEDESTRIAN_SPAWN_INTERVAL {
+            self.spawn_random_pedestrian();
+            self.spawn_timers[1] = 0.0;
+        }
+        if self.spawn_timers[2] > BICYCLE_SPAWN_INTERVAL {
+            self.spawn_random_bicycle();
+            self.spawn_timers[2] = 0.0;
+        }
+    }
+
+    /// Advance everything by `dt` seconds
+    pub fn step(&mut self, dt: f32) {
+        self.steps += 1;
+        self.stats.runtime += dt;
+        self.update_auto_spawn(dt);
+
+        let signal = self.signal;
+        for v in &mut self.vehicles {
+            v.red_light = signal.is_some_and(|phase| !phase.green(v.direction));
+        }
+        let now = self.time();
+        self.controller.update(now, &mut self.vehicles);
+
+        // Swap in last step's state as the read-only front buffer
+        self.front.clone_from(&self.vehicles);
+        self.grid.rebuild(&self.front);
+        let neighbors = Neighborhood::new(&self.front, &self.grid);
+        let sensor = Sensor { model: self.config.perception, seed: self.config.seed.unwrap_or(0), step: self.steps };
+
+        update_vehicles(&mut self.vehicles, dt, neighbors, &self.crosswalks, &sensor);
+        self.heatmap.record(&self.front, &self.vehicles, neighbors, dt);
+        self.safety.check(self.steps, &self.front, &self.vehicles, dt, &mut self.stats);
+
+        self.bike_tracker.update(&self.vehicles, &mut self.stats);
+
+        // Remove vehicles that drove off the map, checking they left by their exit lane
+        for v in self.vehicles.iter().filter(|v| v.has_left()) {
+            self.controller.forget(now, v);
+            self.stats.record_lane_changes(v);
+            let exit = self.road.exit_lane_at(v.x, v.y);
+            if exit != Some(v.exit) {
+                eprintln!("🚧 Vehicle #{} ({:?} {:?}) left by {:?}, expected {:?}", v.id, v.direction, v.route, exit, v.exit);
+                self.stats.wrong_exits += 1;
+            }
+            if !v.is_bicycle() {
+                let side = self.road.edge_past(v.x, v.y).unwrap_or(v.exit.side);
+                self.stats.record_exit(v.direction, side);
+                self.stats.record_trip(v.autonomy(), v.time_in_system);
+            }
+        }
+        self.vehicles.retain(|v| !v.has_left());
+
+        // Grants crossings once the zebra is clear
+        update_pedestrians(&mut self.pedestrians, &mut self.crosswalks, &self.vehicles, &mut self.stats, dt);
+    }
+
+    /// Vehicles still on the map count towards the lane-change totals too
+    pub fn finish(&mut self) {
+        for v in &self.vehicles {
+            self.stats.record_lane_changes(v);
+        }
+    }
+}
+
+// =======================================================
+// 🧵 VEHICLE UPDATE (serial or parallel)
+// =======================================================
+//
+// Each update only writes its own vehicle and reads the front buffer, so
+// the order vehicles are visited in cannot change the outcome: a seeded run
+// gives the same result with or without the `parallel` feature.
+
+#[cfg(not(feature = "parallel"))]
+fn update_vehicles(vehicles: &mut [Vehicle], dt: f32, neighbors: Neighborhood, crosswalks: &[Crosswalk], sensor: &Sensor) {
+    for v in vehicles {
+        v.update(dt, neighbors, crosswalks, sensor);
+    }
+}
+
+#[cfg(feature = "parallel")]
+fn update_vehicles(vehicles: &mut [Vehicle], dt: f32, neighbors: Neighborhood, crosswalks: &[Crosswalk], sensor: &Sensor) {
+    use rayon::prelude::*;
+
+    vehicles
+        .par_iter_mut()
+        .for_each(|v| v.update(dt, neighbors, crosswalks, sensor));
+}
@@ -0,0 +1,85 @@
+use std::collections::HashMap;
+
+use crate::vehicle::{Direction, Vehicle};
+
+// =======================================================
+// 🧭 SPATIAL HASH (neighbor queries)
+// =======================================================
+
+// Cell edge in pixels; most queries look a few car lengths around a vehicle
+pub const CELL_SIZE: f32 = 100.0;
+
+/// Uniform grid over vehicle positions, rebuilt once per step. Cells keep
+/// their allocations between rebuilds.
+#[derive(Default)]
+pub struct SpatialHash {
+    cells: HashMap<(i32, i32), Vec<usize>>,
+}
+
+fn cell_of(x: f32, y: f32) -> (i32, i32) {
+    ((x / CELL_SIZE).floor() as i32, (y / CELL_SIZE).floor() as i32)
+}
+
+impl SpatialHash {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Index every vehicle by its cell
+    pub fn rebuild(&mut self, vehicles: &[Vehicle]) {
+        for cell in self.cells.values_mut() {
+            cell.clear();
+        }
+        for (i, v) in vehicles.iter().enumerate() {
+            self.cells.entry(cell_of(v.x, v.y)).or_default().push(i);
+        }
+    }
+
+    /// Indices of vehicles in the cells overlapping a square around (x, y)
+    fn candidates(&self, x: f32, y: f32, r: f32) -> impl Iterator<Item = usize> + '_ {
+        let (x0, y0) = cell_of(x - r, y - r);
+        let (x1, y1) = cell_of(x + r, y + r);
+
+        (x0..=x1)
+            .flat_map(move |cx| (y0..=y1).map(move |cy| (cx, cy)))
+            .filter_map(|c| self.cells.get(&c))
+            .flatten()
+            .copied()
+    }
+}
+
+/// Read-only view of the previous step's vehicles (the front buffer) for
+/// neighbor queries while the back buffer is being updated
+#[derive(Clone, Copy)]
+pub struct Neighborhood<'a> {
+    vehicles: &'a [Vehicle],
+    grid: &'a SpatialHash,
+}
+
+impl<'a> Neighborhood<'a> {
+    pub fn new(vehicles: &'a [Vehicle], grid: &'a SpatialHash) -> Self {
+        Self { vehicles, grid }
+    }
+
+    /// Vehicles whose center is within `r` of (x, y)
+    pub fn within(&self, x: f32, y: f32, r: f32) -> impl Iterator<Item = &'a Vehicle> + '_ {
+        let vehicles = self.vehicles;
+        self.grid
+            .candidates(x, y, r)
+            .map(move |i| &vehicles[i])
+            .filter(move |v| (v.x - x).powi(2) + (v.y - y).powi(2) <= r * r)
+    }
+
+    /// Vehicles within `r` on approach `dir` that occupy (or are moving into) `lane`
+    pub fn in_lane(
+        &self,
+        dir: Direction,
+        lane: i32,
+        x: f32,
+        y: f32,
+        r: f32,
+    ) -> impl Iterator<Item = &'a Vehicle> + '_ {
+        self.within(x, y, r)
+            .filter(move |v| v.direction == dir && v.occupies_lane(lane))
+    }
+}
@@ -1,5 +1,9 @@
 // src/stats.rs
 
+use crate::driver::Autonomy;
+use crate::safety::Invariant;
+use crate::vehicle::{Direction, Route, Vehicle};
+
 #[derive(Debug)]
 pub struct Stats {
     pub total_vehicles: u32,
@@ -10,12 +14,76 @@ pub struct Stats {
     pub left_turn: u32,
     pub straight: u32,
     pub right_turn: u32,
+    pub u_turn: u32,
+    pub vehicles_exited: u32, // Cars that left the map (throughput)
+    pub od: [[u32; 4]; 4],    // Cars that left, [origin approach][exit side] (Up, Down, Left, Right)
+    pub wrong_exits: u32,     // Vehicles that left by a lane other than the one their route leads to
     pub runtime: f32,
     
     // 📊 Physics tracking
     pub total_distance: f32,
     pub avg_intersection_time: f32,
     pub collision_avoided: u32,
+
+    // 🚶 Pedestrian tracking
+    pub pedestrians: u32,
+    pub pedestrians_crossed: u32,
+    pub pedestrian_wait_total: f32,
+    pub pedestrian_wait_max: f32,
+
+    // 🚲 Bicycle tracking (not included in total_vehicles)
+    pub bicycles: u32,
+    pub bike_yields: u32,      // Cars that held a turn for a cyclist
+    pub bike_close_calls: u32, // Cyclists a car came much closer to than when passing normally
+
+    // ↔️ Lane changing (collected as vehicles leave the map)
+    pub lane_changes: u32,
+    pub aborted_lane_changes: u32,
+
+    // 🕸️ Multi-intersection network mode
+    pub network_spawned: u32,
+    pub network_spawn_blocked: u32, // Entry link backed up to the gateway
+    pub network_trips: u32,
+    pub network_travel_time: f32,
+    pub network_spillbacks: u32, // Vehicles held at a stop line by a full downstream link
+    pub network_grants: u32,     // Grants taken by vehicles
+    pub network_grant_wait: f32, // Total seconds from first request to grant
+    pub network_lapsed_grants: u32, // Grants given up for not reaching the junction in time
+
+    // 🧑 Mixed traffic (cars only, either mode), indexed by Autonomy::index
+    pub by_class: [ClassStats; 2],
+
+    // 🛡️ Safety monitor (violation episodes)
+    pub overlaps: u32,
+    pub backward_moves: u32,
+    pub speed_violations: u32,
+    pub unauthorized_entries: u32,
+    pub stalls: u32,
+}
+
+/// Totals for one autonomy class
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ClassStats {
+    pub spawned: u32,
+    pub trips: u32,       // Left the map (single junction) or reached their destination (network)
+    pub travel_time: f32, // Seconds, over completed trips
+    pub violations: u32,  // Safety episodes the class was involved in
+}
+
+impl ClassStats {
+    pub fn avg_travel_time(&self) -> f32 {
+        if self.trips == 0 {
+            0.0
+        } else {
+            self.travel_time / self.trips as f32
+        }
+    }
+}
+
+impl Default for Stats {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Stats {
@@ -29,14 +97,142 @@ impl Stats {
             left_turn: 0,
             straight: 0,
             right_turn: 0,
+            u_turn: 0,
+            vehicles_exited: 0,
+            od: [[0; 4]; 4],
+            wrong_exits: 0,
             runtime: 0.0,
             total_distance: 0.0,
             avg_intersection_time: 0.0,
             collision_avoided: 0,
+            pedestrians: 0,
+            pedestrians_crossed: 0,
+            pedestrian_wait_total: 0.0,
+            pedestrian_wait_max: 0.0,
+            bicycles: 0,
+            bike_yields: 0,
+            bike_close_calls: 0,
+            lane_changes: 0,
+            aborted_lane_changes: 0,
+            network_spawned: 0,
+            network_spawn_blocked: 0,
+            network_trips: 0,
+            network_travel_time: 0.0,
+            network_spillbacks: 0,
+            network_grants: 0,
+            network_grant_wait: 0.0,
+            network_lapsed_grants: 0,
+            by_class: [ClassStats::default(); 2],
+            overlaps: 0,
+            backward_moves: 0,
+            speed_violations: 0,
+            unauthorized_entries: 0,
+            stalls: 0,
+        }
+    }
+
+    /// Count a spawned car by approach and route
+    pub fn record_spawn(&mut self, dir: Direction, route: Route) {
+        self.total_vehicles += 1;
+        match dir {
+            Direction::Up => self.up += 1,
+            Direction::Down => self.down += 1,
+            Direction::Left => self.left += 1,
+            Direction::Right => self.right += 1,
+        }
+        match route {
+            Route::Right => self.right_turn += 1,
+            Route::Straight => self.straight += 1,
+            Route::Left => self.left_turn += 1,
+            Route::UTurn => self.u_turn += 1,
+        }
+    }
+
+    /// Count a car that left the map, by where it came from and where it went
+    pub fn record_exit(&mut self, origin: Direction, side: Direction) {
+        self.vehicles_exited += 1;
+        self.od[side_index(origin)][side_index(side)] += 1;
+    }
+
+    /// Cars leaving the map per minute of runtime
+    pub fn throughput(&self) -> f32 {
+        if self.runtime > 0.0 {
+            self.vehicles_exited as f32 * 60.0 / self.runtime
+        } else {
+            0.0
+        }
+    }
+
+    pub fn record_lane_changes(&mut self, v: &Vehicle) {
+        self.lane_changes += v.lane_changes;
+        self.aborted_lane_changes += v.aborted_lane_changes;
+    }
+
+    pub fn class(&mut self, autonomy: Autonomy) -> &mut ClassStats {
+        &mut self.by_class[autonomy.index()]
+    }
+
+    /// Count a car that finished its trip after `time` seconds
+    pub fn record_trip(&mut self, autonomy: Autonomy, time: f32) {
+        let class = self.class(autonomy);
+        class.trips += 1;
+        class.travel_time += time;
+    }
+
+    /// Count a violation episode, once for each class involved
+    pub fn record_violation(&mut self, invariant: Invariant, involved: &[Autonomy]) {
+        for (i, &autonomy) in involved.iter().enumerate() {
+            if !involved[..i].contains(&autonomy) {
+                self.class(autonomy).violations += 1;
+            }
+        }
+        match invariant {
+            Invariant::Overlap => self.overlaps += 1,
+            Invariant::Backwards => self.backward_moves += 1,
+            Invariant::Speed => self.speed_violations += 1,
+            Invariant::Unauthorized => self.unauthorized_entries += 1,
+            Invariant::Stalled => self.stalls += 1,
+        }
+    }
+
+    pub fn safety_violations(&self) -> u32 {
+        self.overlaps + self.backward_moves + self.speed_violations + self.unauthorized_entries + self.stalls
+    }
+
+    /// Mean time network vehicles waited between asking a junction and getting a grant
+    pub fn avg_grant_wait(&self) -> f32 {
+        if self.network_grants == 0 {
+            0.0
+        } else {
+            self.network_grant_wait / self.network_grants as f32
+        }
+    }
+
+    /// Record how long a pedestrian waited at the kerb before crossing
+    pub fn record_pedestrian_wait(&mut self, wait: f32) {
+        self.pedestrians_crossed += 1;
+        self.pedestrian_wait_total += wait;
+        self.pedestrian_wait_max = self.pedestrian_wait_max.max(wait);
+    }
+
+    pub fn avg_pedestrian_wait(&self) -> f32 {
+        if self.pedestrians_crossed > 0 {
+            self.pedestrian_wait_total / self.pedestrians_crossed as f32
+        } else {
+            0.0
         }
     }
 }
 
+fn side_index(dir: Direction) -> usize {
+    match dir {
+        Direction::Up => 0,
+        Direction::Down => 1,
+        Direction::Left => 2,
+        Direction::Right => 3,
+    }
+}
+
 pub fn show_stats_window(stats: &Stats) {
     println!("\n=====================================");
     println!("📊  FINAL SIMULATION STATISTICS");
@@ -53,8 +249,21 @@ pub fn show_stats_window(stats: &Stats) {
     println!("↩️ Right Turns : {}", stats.right_turn);
     println!("⬆️ Straight    : {}", stats.straight);
     println!("⬅️ Left Turns  : {}", stats.left_turn);
+    println!("🔄 U-Turns     : {}", stats.u_turn);
 
     println!("\n🚗 Total Vehicles: {}", stats.total_vehicles);
+    println!("🏁 Exited        : {} ({:.1} veh/min)", stats.vehicles_exited, stats.throughput());
+    if stats.wrong_exits > 0 {
+        println!("🚧 Wrong Exit Lane : {}", stats.wrong_exits);
+    }
+
+    // Rows by origin approach, columns by the side of the map left through
+    println!("\n🧭 Origin → Exit     N     S     W     E");
+    let origins = [("from South", 0), ("from North", 1), ("from East ", 2), ("from West ", 3)];
+    for (name, o) in origins {
+        let row = &stats.od[o];
+        println!("   {}    {:>5} {:>5} {:>5} {:>5}", name, row[0], row[1], row[2], row[3]);
+    }
     
     println!("\n⚡ Physics Data:");
     println!("📏 Total Distance Traveled: {:.2} m", stats.total_distance / 10.0); // assuming 10px = 1m
@@ -66,6 +275,58 @@ pub fn show_stats_window(stats: &Stats) {
         println!("⏱️  Avg Intersection Time: {:.2} s", stats.avg_intersection_time);
     }
     println!("🛡️  Collisions Avoided: {}", stats.collision_avoided);
+
+    println!("\n🚶 Pedestrians:");
+    println!("👣 Spawned / Crossed  : {} / {}", stats.pedestrians, stats.pedestrians_crossed);
+    println!("⏳ Avg Wait at Kerb    : {:.2} s", stats.avg_pedestrian_wait());
+    println!("⌛ Max Wait at Kerb    : {:.2} s", stats.pedestrian_wait_max);
+
+    println!("\n🚲 Bicycles:");
+    println!("🚴 Cyclists Spawned      : {}", stats.bicycles);
+    println!("✋ Cars Yielding to Bikes : {}", stats.bike_yields);
+    println!("⚠️  Car↔Bike Close Calls  : {}", stats.bike_close_calls);
+
+    println!("\n↔️  Lane Changes:");
+    println!("✅ Completed : {}", stats.lane_changes);
+    println!("❌ Aborted   : {}", stats.aborted_lane_changes);
+
+    if stats.network_spawned > 0 {
+        println!("\n🕸️  Network:");
+        println!("🚗 Spawned / Blocked at Gateway : {} / {}", stats.network_spawned, stats.network_spawn_blocked);
+        println!("🏁 Completed Trips              : {}", stats.network_trips);
+        if stats.network_trips > 0 {
+            println!("⏱️  Avg Trip Time                : {:.2} s",
+                stats.network_travel_time / stats.network_trips as f32);
+        }
+        println!("🧱 Spillback Holds              : {}", stats.network_spillbacks);
+        println!("📡 Avg Wait for a Grant         : {:.2} s", stats.avg_grant_wait());
+        println!("⌛ Lapsed Grants                : {}", stats.network_lapsed_grants);
+    }
+
+    let humans = stats.by_class[Autonomy::Human.index()];
+    if humans.spawned > 0 {
+        println!("\n🧑 Mixed Traffic        spawned   trips  avg trip  violations");
+        for autonomy in Autonomy::ALL {
+            let class = stats.by_class[autonomy.index()];
+            println!(
+                "   {:<18} {:>8} {:>7} {:>8.1}s {:>11}",
+                autonomy.name(),
+                class.spawned,
+                class.trips,
+                class.avg_travel_time(),
+                class.violations
+            );
+        }
+    }
+
+    println!("\n🛡️  Safety Monitor: {} violation(s)", stats.safety_violations());
+    if stats.safety_violations() > 0 {
+        println!("💥 Overlaps      : {}", stats.overlaps);
+        println!("⏪ Backward Moves : {}", stats.backward_moves);
+        println!("🏎️ Speed          : {}", stats.speed_violations);
+        println!("⛔ Unauthorized   : {}", stats.unauthorized_entries);
+        println!("🧊 Stalls         : {}", stats.stalls);
+    }
     
     println!("=====================================\n");
 }
\ No newline at end of file
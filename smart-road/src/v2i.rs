@@ -0,0 +1,273 @@
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::path::Path;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::manager::Movement;
+
+// =======================================================
+// 📡 V2I MESSAGE BUS (vehicles ↔ junction controllers)
+// =======================================================
+//
+// Network vehicles and junction controllers only learn about each other's
+// intentions through messages. A vehicle near the stop line sends a
+// Request; the controller answers Grant or Reject (plus a SpeedAdvisory
+// while it has to wait) and the vehicle sends Cancel once it has cleared
+// the junction or gives the slot up. Heartbeats keep the controller informed
+// of vehicles holding or awaiting a reservation.
+//
+// A grant is a lease: the vehicle has to enter the junction before `until`
+// or give the grant up, and the controller keeps the slot for at least the
+// longest crossing after that, whatever it hears or fails to hear. A Cancel
+// releases everything asked for before it was sent, so vehicles ignore
+// grants answering requests older than their last Cancel.
+//
+// Messages sent during a step are delivered at the start of the next one at
+// the earliest, so every exchange takes at least one step. The radio link to
+// each junction is a `Channel`: it adds latency and jitter and loses messages,
+// which can also reorder them. Vehicles never enter a junction without a
+// valid grant in hand, so late or lost messages cost time rather than safety.
+//
+// The network junctions and the single cross junction (see admission.rs)
+// both run this protocol; only autonomous cars carry a radio.
+
+pub const HEARTBEAT_INTERVAL: f32 = 0.5;
+const HEARTBEAT_TIMEOUT: f32 = 2.0; // Controllers drop reservations of silent vehicles (once the lease is over)
+
+/// Sender or receiver of a message
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Endpoint {
+    Vehicle(usize),
+    Junction(usize), // Node index
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Payload {
+    Request(Movement),
+    Grant { until: f32, asked: f32 }, // Enter before `until`; answers the request sent at `asked`
+    Reject,
+    Cancel,              // Release a reservation (or withdraw a request)
+    SpeedAdvisory(f32),  // Approach no faster than this (world units/s)
+    Heartbeat { pos: f32, speed: f32 }, // Position along the current link
+}
+
+impl Payload {
+    pub const KINDS: [&'static str; 6] = ["request", "grant", "reject", "cancel", "advisory", "heartbeat"];
+
+    fn kind(&self) -> usize {
+        match self {
+            Payload::Request(_) => 0,
+            Payload::Grant { .. } => 1,
+            Payload::Reject => 2,
+            Payload::Cancel => 3,
+            Payload::SpeedAdvisory(_) => 4,
+            Payload::Heartbeat { .. } => 5,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        Self::KINDS[self.kind()]
+    }
+
+    /// Payload fields for the log
+    fn detail(&self) -> String {
+        match self {
+            Payload::Request(m) => format!("{:?}->{:?}", m.from, m.to),
+            Payload::Grant { until, asked } => format!("until {:.2} (asked {:.2})", until, asked),
+            Payload::SpeedAdvisory(speed) => format!("{:.1}", speed),
+            Payload::Heartbeat { pos, speed } => format!("pos {:.1} speed {:.1}", pos, speed),
+            Payload::Reject | Payload::Cancel => String::new(),
+        }
+    }
+}
+
+/// Radio link quality: every message is delayed by `latency` plus a uniform
+/// offset within ±`jitter` (never below zero), and lost with probability `loss`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Channel {
+    pub latency: f32, // Seconds
+    pub jitter: f32,  // Seconds
+    pub loss: f32,    // 0..1
+}
+
+impl Default for Channel {
+    /// Instant and lossless (delivery on the next step)
+    fn default() -> Self {
+        Self { latency: 0.0, jitter: 0.0, loss: 0.0 }
+    }
+}
+
+impl Channel {
+    /// "LATENCY/JITTER/LOSS" with times in milliseconds, e.g. "200/50/0.1";
+    /// trailing fields may be left out
+    pub fn parse(spec: &str) -> Result<Channel, String> {
+        let fields: Vec<&str> = spec.split('/').collect();
+        if fields.len() > 3 {
+            return Err(format!("'{}' has more than LATENCY/JITTER/LOSS", spec));
+        }
+        let field = |i: usize| -> Result<f32, String> {
+            match fields.get(i) {
+                None => Ok(0.0),
+                Some(f) => f.parse::<f32>().ok().filter(|x| *x >= 0.0).ok_or_else(|| format!("invalid number '{}'", f)),
+            }
+        };
+
+        let channel = Channel { latency: field(0)? / 1000.0, jitter: field(1)? / 1000.0, loss: field(2)? };
+        if channel.loss >= 1.0 {
+            return Err(format!("loss {} must be below 1", channel.loss));
+        }
+        Ok(channel)
+    }
+
+    /// Delay of one message, None if it is lost
+    fn sample(&self, rng: &mut StdRng) -> Option<f32> {
+        if self.loss > 0.0 && rng.random::<f32>() < self.loss {
+            return None;
+        }
+        let jitter = if self.jitter > 0.0 { rng.random_range(-self.jitter..=self.jitter) } else { 0.0 };
+        Some((self.latency + jitter).max(0.0))
+    }
+}
+
+/// A junction controller's view of one reservation holder
+#[derive(Clone, Copy, Debug)]
+pub struct Lease {
+    pub asked: f32, // When the latest granted request was sent
+    pub until: f32, // Entry deadline of the latest grant
+    pub heard: f32, // Last request or heartbeat received
+}
+
+impl Lease {
+    /// Nobody can still be inside on this grant (crossing takes at most
+    /// `allowance` seconds), and the holder has gone quiet
+    pub fn expired(&self, now: f32, allowance: f32) -> bool {
+        now > self.until + allowance && now - self.heard > HEARTBEAT_TIMEOUT
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Message {
+    pub sent: f32,            // Simulated time
+    pub arrives: Option<f32>, // None if the channel lost it
+    pub from: Endpoint,
+    pub to: Endpoint,
+    pub payload: Payload,
+}
+
+#[derive(Debug)]
+pub struct MessageBus {
+    in_flight: Vec<Message>,
+    rng: StdRng,
+    pub channel: Channel,                  // Link to every junction without its own entry
+    pub channels: HashMap<usize, Channel>, // Per junction (node index)
+    pub logging: bool,                     // Keep every message in `log` (for inspection and export)
+    pub log: Vec<Message>,
+    pub sent: [u32; 6], // By payload kind, see Payload::KINDS
+    pub lost: [u32; 6],
+    pub delivered: u32,
+    total_delay: f32,
+}
+
+impl MessageBus {
+    /// Ideal channel everywhere; `seed` drives jitter and losses
+    pub fn new(seed: u64) -> Self {
+        Self {
+            in_flight: Vec::new(),
+            rng: StdRng::seed_from_u64(seed),
+            channel: Channel::default(),
+            channels: HashMap::new(),
+            logging: false,
+            log: Vec::new(),
+            sent: [0; 6],
+            lost: [0; 6],
+            delivered: 0,
+            total_delay: 0.0,
+        }
+    }
+
+    /// The radio link a message travels over: that of the junction at either end
+    pub fn channel_between(&self, a: Endpoint, b: Endpoint) -> Channel {
+        let junction = match (a, b) {
+            (Endpoint::Junction(j), _) | (_, Endpoint::Junction(j)) => Some(j),
+            _ => None,
+        };
+        junction.and_then(|j| self.channels.get(&j)).copied().unwrap_or(self.channel)
+    }
+
+    pub fn send(&mut self, now: f32, from: Endpoint, to: Endpoint, payload: Payload) {
+        let delay = self.channel_between(from, to).sample(&mut self.rng);
+        let message = Message { sent: now, arrives: delay.map(|d| now + d), from, to, payload };
+        self.sent[payload.kind()] += 1;
+        if self.logging {
+            self.log.push(message);
+        }
+        match delay {
+            Some(_) => self.in_flight.push(message),
+            None => self.lost[payload.kind()] += 1,
+        }
+    }
+
+    /// Everything arriving before `now`, in order of arrival
+    pub fn deliver(&mut self, now: f32) -> Vec<Message> {
+        let (mut due, waiting): (Vec<Message>, Vec<Message>) =
+            self.in_flight.drain(..).partition(|m| m.arrives.is_some_and(|t| t < now));
+        self.in_flight = waiting;
+        due.sort_by(|a, b| a.arrives.unwrap().total_cmp(&b.arrives.unwrap()));
+
+        for m in &due {
+            self.delivered += 1;
+            self.total_delay += m.arrives.unwrap() - m.sent;
+        }
+        due
+    }
+
+    /// Mean channel delay of delivered messages (seconds, without the step of delivery)
+    pub fn mean_delay(&self) -> f32 {
+        if self.delivered == 0 {
+            0.0
+        } else {
+            self.total_delay / self.delivered as f32
+        }
+    }
+
+    pub fn total_lost(&self) -> u32 {
+        self.lost.iter().sum()
+    }
+
+    /// Message counts for the end-of-run report
+    pub fn print_summary(&self) {
+        let counts: Vec<String> = Payload::KINDS.iter().zip(&self.sent).map(|(k, n)| format!("{} {}", k, n)).collect();
+        println!("📡 V2I messages: {}", counts.join(", "));
+
+        let sent: u32 = self.sent.iter().sum();
+        if self.total_lost() > 0 || self.mean_delay() > 0.0 {
+            println!(
+                "   lost {} of {} ({:.1}%), mean delay {:.0} ms",
+                self.total_lost(),
+                sent,
+                100.0 * self.total_lost() as f32 / sent.max(1) as f32,
+                self.mean_delay() * 1000.0
+            );
+        }
+    }
+
+    /// Write the log as CSV (`arrives` is empty for lost messages)
+    pub fn export(&self, path: &Path) -> Result<(), String> {
+        let mut csv = String::from("sent,arrives,from,to,type,detail\n");
+        for m in &self.log {
+            let arrives = m.arrives.map(|t| format!("{:.3}", t)).unwrap_or_default();
+            let (from, to) = (endpoint(m.from), endpoint(m.to));
+            writeln!(csv, "{:.3},{},{},{},{},{}", m.sent, arrives, from, to, m.payload.name(), m.payload.detail()).unwrap();
+        }
+        std::fs::write(path, csv).map_err(|e| format!("cannot write {}: {}", path.display(), e))
+    }
+}
+
+fn endpoint(e: Endpoint) -> String {
+    match e {
+        Endpoint::Vehicle(id) => format!("vehicle {}", id),
+        Endpoint::Junction(node) => format!("junction {}", node),
+    }
+}
@@ -1,16 +1,36 @@
-use sdl2::{rect::Rect, render::Canvas, video::Window};
+use sdl2::{pixels::Color, rect::Rect, render::{Canvas, RenderTarget}};
 use sdl2::render::Texture;
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
 
-// 🔒 Import grid constants from main.rs (crate root)
-use crate::{TILE_SIZE, GRID_W, GRID_H, MID_TILE, ROAD_HALF_TILES};
-const INTERSECTION_MIN: i32 = MID_TILE - ROAD_HALF_TILES;
-const INTERSECTION_MAX: i32 = MID_TILE + ROAD_HALF_TILES;
+use crate::admission::V2iState;
+use crate::pedestrian::Crosswalk;
+use crate::lane_change::LaneChange;
+use crate::bicycle::{build_bike_path, draw_bicycle, BIKE_HALF_LENGTH, BIKE_HALF_WIDTH, BIKE_LANE_CLEARANCE};
+
+use crate::config::JunctionKind;
+use crate::driver::{Autonomy, HumanDriver};
+use crate::perception::Sensor;
+use crate::roundabout::build_roundabout_path;
+use crate::spatial::Neighborhood;
+
+use crate::geometry::{ExitLane, RoadGeometry};
 
 // 🚦 Safety distance in pixels
-const SAFETY_DISTANCE: f32 = 120.0; // Reduced to prevent unnecessary stopping
+pub const SAFETY_DISTANCE: f32 = 120.0; // Reduced to prevent unnecessary stopping
+pub const LATERAL_THRESHOLD: f32 = 50.0; // Half-width of the look-ahead corridor
 const EMERGENCY_BRAKE_DISTANCE: f32 = 50.0; // Distance for emergency stop
 const MIN_CRAWL_SPEED: f32 = 20.0; // Minimum speed to keep vehicles moving
+const ACCELERATION: f32 = 200.0; // pixels/s² - DOUBLED for faster recovery
+const CROSSWALK_STOP_MARGIN: f32 = 10.0; // Gap left in front of a zebra when yielding
+pub const QUEUE_GAP: f32 = 12.0; // Bumper-to-bumper gap behind a stopped vehicle
+const BIKE_YIELD_WINDOW: f32 = 150.0; // How far behind the turn point a bike makes a car wait
+const BIKE_CLEAR_DISTANCE: f32 = 30.0; // How far past the turn point a bike must be to be clear
+
+// 🚗 Half the drawn car length and width (sprites are scaled to ~32x55 px)
+pub const VEHICLE_HALF_LENGTH: f32 = 28.0;
+pub const VEHICLE_HALF_WIDTH: f32 = 16.0;
 
 // 🎯 Velocity levels for traffic control
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -21,7 +41,7 @@ pub enum VelocityLevel {
 }
 
 impl VelocityLevel {
-    pub fn to_speed(&self) -> f32 {
+    pub fn to_speed(self) -> f32 {
         match self {
             VelocityLevel::Slow => 60.0,
             VelocityLevel::Medium => 120.0,
@@ -38,11 +58,65 @@ pub enum Direction {
     Right,
 }
 
+impl Direction {
+    pub fn opposite(self) -> Direction {
+        match self {
+            Direction::Up => Direction::Down,
+            Direction::Down => Direction::Up,
+            Direction::Left => Direction::Right,
+            Direction::Right => Direction::Left,
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Route {
     Right,
     Straight,
     Left,
+    UTurn,
+}
+
+// 🚲 Agent types sharing the road
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum VehicleKind {
+    Car,
+    Bicycle,
+}
+
+impl VehicleKind {
+    /// Cruise speed for a velocity level (bicycles have their own slow profile)
+    pub fn speed(&self, level: VelocityLevel) -> f32 {
+        match self {
+            VehicleKind::Car => level.to_speed(),
+            VehicleKind::Bicycle => match level {
+                VelocityLevel::Slow => 30.0,
+                VelocityLevel::Medium => 45.0,
+                VelocityLevel::Fast => 60.0,
+            },
+        }
+    }
+
+    pub fn acceleration(&self) -> f32 {
+        match self {
+            VehicleKind::Car => ACCELERATION,
+            VehicleKind::Bicycle => 80.0,
+        }
+    }
+
+    pub fn half_length(&self) -> f32 {
+        match self {
+            VehicleKind::Car => VEHICLE_HALF_LENGTH,
+            VehicleKind::Bicycle => BIKE_HALF_LENGTH,
+        }
+    }
+
+    pub fn half_width(&self) -> f32 {
+        match self {
+            VehicleKind::Car => VEHICLE_HALF_WIDTH,
+            VehicleKind::Bicycle => BIKE_HALF_WIDTH,
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -52,10 +126,29 @@ pub struct Vehicle {
     pub speed: f32,
     pub target_speed: f32, // Desired speed based on velocity level
     pub velocity_level: VelocityLevel,
-    pub path: Vec<(f32, f32)>,
+    pub path: Arc<Vec<(f32, f32)>>, // Shared with snapshot copies until modified
     pub current_target: usize,
     pub car_id: usize,
     pub id: usize, // Unique vehicle ID for comparison
+    pub kind: VehicleKind,
+    pub direction: Direction,
+    pub route: Route,
+    pub exit: ExitLane, // Where the route is meant to leave the map
+    pub junction: JunctionKind,
+    pub road: Arc<RoadGeometry>, // Layout of the simulation this vehicle drives in
+    pub in_bike_lane: bool, // Bicycle riding on the curb-side bike lane
+    pub yielding_to_bike: bool,
+    pub leader: Option<usize>, // Id of the vehicle we are braking for
+    pub driver: Option<HumanDriver>, // Human at the wheel (None: autonomous)
+    pub red_light: bool, // Our approach has no green (only under signal control)
+    pub held: bool,      // Speed set by a red light, a crosswalk, a cyclist or the junction manager, not by traffic
+    pub v2i: V2iState,   // Exchange with the junction manager (autonomous cars at the cross)
+
+    // ↔️ Lane changing on the approach
+    pub lane: i32, // Entry lane tile currently occupied
+    pub lane_change: Option<LaneChange>,
+    pub lane_changes: u32,
+    pub aborted_lane_changes: u32,
     
     // 📊 Physics tracking
     pub distance_traveled: f32,
@@ -66,75 +159,48 @@ pub struct Vehicle {
 }
 
 // =======================================================
-// 🔧 TILE HELPERS
+// 🔧 ROUTE HELPERS
 // =======================================================
 
-/// Convert tile coords → pixel center
-fn tile_center(tx: i32, ty: i32) -> (f32, f32) {
-    (
-        (tx * TILE_SIZE + TILE_SIZE / 2) as f32,
-        (ty * TILE_SIZE + TILE_SIZE / 2) as f32,
-    )
+/// The turn towards the curb (a right turn for the driver); it uses the curb lane
+/// and cuts across the bike lane
+pub fn curb_route(dir: Direction) -> Route {
+    match dir {
+        Direction::Up | Direction::Right => Route::Right,
+        Direction::Down | Direction::Left => Route::Left,
+    }
 }
 
-fn entry_lane_tile(dir: Direction, route: Route) -> i32 {
-    match dir {
-        Direction::Down => match route {
-            Route::Left => MID_TILE - 3,
-            Route::Straight => MID_TILE - 2,
-            Route::Right => MID_TILE - 1,
-        },
-        Direction::Up => match route {
-            Route::Left => MID_TILE + 1,
-            Route::Straight => MID_TILE + 2,
-            Route::Right => MID_TILE + 3,
-        },
-        Direction::Right => match route {
-            Route::Left => MID_TILE + 1,
-            Route::Straight => MID_TILE + 2,
-            Route::Right => MID_TILE + 3,
-        },
-        Direction::Left => match route {
-            Route::Left => MID_TILE - 3,
-            Route::Straight => MID_TILE - 2,
-            Route::Right => MID_TILE - 1,
-        },
+/// Side of the junction a vehicle leaves through (Up = north side, etc.)
+pub fn exit_side(dir: Direction, route: Route) -> Direction {
+    match (dir, route) {
+        (_, Route::Straight) => dir,
+        (Direction::Up | Direction::Down, Route::Right) => Direction::Right,
+        (Direction::Up | Direction::Down, Route::Left) => Direction::Left,
+        (Direction::Left | Direction::Right, Route::Left) => Direction::Up,
+        (Direction::Left | Direction::Right, Route::Right) => Direction::Down,
+        (Direction::Up, Route::UTurn) => Direction::Down,
+        (Direction::Down, Route::UTurn) => Direction::Up,
+        (Direction::Left, Route::UTurn) => Direction::Right,
+        (Direction::Right, Route::UTurn) => Direction::Left,
     }
 }
 
-fn exit_lane_tile(dir: Direction, route: Route) -> i32 {
+/// Position along the direction of travel (grows as a vehicle advances)
+pub fn progress_along(dir: Direction, x: f32, y: f32) -> f32 {
     match dir {
-        Direction::Down => match route {
-            Route::Left => MID_TILE - 10,
-            Route::Straight => MID_TILE + 2,
-            Route::Right => MID_TILE + 12,
-        },
-        Direction::Left => match route {
-            Route::Left => MID_TILE - 10,
-            Route::Straight => MID_TILE - 2,
-            Route::Right => MID_TILE + 10,
-        },
-        Direction::Up => match route {
-            Route::Left => MID_TILE - 12,
-            Route::Straight => MID_TILE - 2,
-            Route::Right => MID_TILE + 10,
-        },
-        Direction::Right => match route {
-            Route::Left => MID_TILE - 10,
-            Route::Straight => MID_TILE + 2,
-            Route::Right => MID_TILE + 10,
-        },
+        Direction::Up => -y,
+        Direction::Down => y,
+        Direction::Left => -x,
+        Direction::Right => x,
     }
 }
 
-// Global vehicle ID counter
-static mut VEHICLE_ID_COUNTER: usize = 0;
+// Global vehicle ID counter (shared by simulations running on other threads, e.g. tests)
+static VEHICLE_ID_COUNTER: AtomicUsize = AtomicUsize::new(0);
 
 fn get_next_vehicle_id() -> usize {
-    unsafe {
-        VEHICLE_ID_COUNTER += 1;
-        VEHICLE_ID_COUNTER
-    }
+    VEHICLE_ID_COUNTER.fetch_add(1, Ordering::Relaxed) + 1
 }
 
 // =======================================================
@@ -142,8 +208,10 @@ fn get_next_vehicle_id() -> usize {
 // =======================================================
 
 impl Vehicle {
-    pub fn new(direction: Direction, route: Route, car_id: usize) -> Self {
-        let path = build_path(direction, route);
+    /// Spawn in any entry `lane`; the car changes lanes upstream to reach its route lane
+    pub fn new(road: &Arc<RoadGeometry>, direction: Direction, route: Route, car_id: usize, lane: i32, junction: JunctionKind) -> Self {
+        // Drive the approach in the spawn lane
+        let path = build_junction_path(road, direction, route, junction, lane);
         let (x, y) = path[0];
         
         // Start with medium velocity by default
@@ -156,10 +224,71 @@ impl Vehicle {
             speed: target_speed,
             target_speed,
             velocity_level,
-            path,
+            path: Arc::new(path),
             current_target: 1,
             car_id,
             id: get_next_vehicle_id(), // Unique ID for each vehicle
+            kind: VehicleKind::Car,
+            direction,
+            route,
+            exit: road.exit_lane(direction, route, lane),
+            junction,
+            road: Arc::clone(road),
+            in_bike_lane: false,
+            yielding_to_bike: false,
+            leader: None,
+            driver: None,
+            red_light: false,
+            held: false,
+            v2i: V2iState::default(),
+            lane,
+            lane_change: None,
+            lane_changes: 0,
+            aborted_lane_changes: 0,
+            distance_traveled: 0.0,
+            time_in_system: 0.0,
+            entered_intersection: false,
+            intersection_entry_time: 0.0,
+            intersection_exit_time: 0.0,
+        }
+    }
+
+    /// A cyclist riding straight through, on the bike lane if there is one
+    pub fn new_bicycle(road: &Arc<RoadGeometry>, direction: Direction, bike_lane: bool, junction: JunctionKind) -> Self {
+        let path = build_bike_path(road, direction, bike_lane, junction);
+        let (x, y) = path[0];
+
+        let kind = VehicleKind::Bicycle;
+        let velocity_level = VelocityLevel::Medium;
+        let target_speed = kind.speed(velocity_level);
+
+        Self {
+            x,
+            y,
+            speed: target_speed,
+            target_speed,
+            velocity_level,
+            path: Arc::new(path),
+            current_target: 1,
+            car_id: 0,
+            id: get_next_vehicle_id(),
+            kind,
+            direction,
+            route: Route::Straight,
+            exit: ExitLane { side: direction, lane: road.exit_curb_lane(direction) },
+            junction,
+            road: Arc::clone(road),
+            in_bike_lane: bike_lane,
+            yielding_to_bike: false,
+            leader: None,
+            driver: None,
+            red_light: false,
+            held: false,
+            v2i: V2iState::default(),
+            lane: road.curb_lane(direction),
+            lane_change: None,
+            lane_changes: 0,
+            aborted_lane_changes: 0,
             distance_traveled: 0.0,
             time_in_system: 0.0,
             entered_intersection: false,
@@ -168,13 +297,50 @@ impl Vehicle {
         }
     }
 
+    pub fn is_bicycle(&self) -> bool {
+        self.kind == VehicleKind::Bicycle
+    }
+
+    pub fn half_length(&self) -> f32 {
+        self.kind.half_length()
+    }
+
+    pub fn autonomy(&self) -> Autonomy {
+        if self.driver.is_some() { Autonomy::Human } else { Autonomy::Autonomous }
+    }
+
+    /// How much of the autonomous gap standard this vehicle insists on
+    pub fn gap_acceptance(&self) -> f32 {
+        self.driver.as_ref().map_or(1.0, |d| d.gap_acceptance)
+    }
+
+    /// Body rectangle (x0, y0, x1, y1), long side along the way we are facing
+    pub fn footprint(&self) -> (f32, f32, f32, f32) {
+        let (hl, hw) = (self.kind.half_length(), self.kind.half_width());
+        let (hx, hy) = match self.facing_direction() {
+            Direction::Up | Direction::Down => (hw, hl),
+            Direction::Left | Direction::Right => (hl, hw),
+        };
+        (self.x - hx, self.y - hy, self.x + hx, self.y + hy)
+    }
+
+    /// Clearance between two bodies; negative when they overlap
+    pub fn footprint_gap(&self, other: &Vehicle) -> f32 {
+        let (ax0, ay0, ax1, ay1) = self.footprint();
+        let (bx0, by0, bx1, by1) = other.footprint();
+        let gap_x = (ax0 - bx1).max(bx0 - ax1);
+        let gap_y = (ay0 - by1).max(by0 - ay1);
+        gap_x.max(gap_y)
+    }
+
+    /// Cruise speed for the current velocity level
+    fn cruise_speed(&self) -> f32 {
+        self.kind.speed(self.velocity_level)
+    }
+
     /// Check if vehicle is in the intersection zone
     pub fn is_in_intersection(&self) -> bool {
-        let tile_x = (self.x / TILE_SIZE as f32) as i32;
-        let tile_y = (self.y / TILE_SIZE as f32) as i32;
-        
-        tile_x >= INTERSECTION_MIN && tile_x <= INTERSECTION_MAX &&
-        tile_y >= INTERSECTION_MIN && tile_y <= INTERSECTION_MAX
+        self.road.in_junction(self.x, self.y)
     }
 
     /// Calculate distance to another vehicle
@@ -187,7 +353,11 @@ impl Vehicle {
     /// Check if another vehicle is ahead on the path
     pub fn is_vehicle_ahead(&self, other: &Vehicle) -> bool {
         // Check if vehicles are on similar paths (within same lane corridor)
-        let lateral_threshold = 50.0; // Tightened to reduce false positives
+        let lateral_threshold = if self.in_bike_lane || other.in_bike_lane {
+            BIKE_LANE_CLEARANCE // Bike lane is physically separate from the car lane
+        } else {
+            LATERAL_THRESHOLD // Tightened to reduce false positives
+        };
         
         let dir = self.facing_direction();
         let distance = self.distance_to(other);
@@ -217,15 +387,121 @@ impl Vehicle {
         }
     }
 
+    /// Distance from the front bumper to a crosswalk ahead in our lane, if any
+    fn gap_to_crosswalk(&self, cw: &Crosswalk) -> Option<f32> {
+        let gap = match self.facing_direction() {
+            Direction::Up if self.x >= cw.x0 && self.x <= cw.x1 => {
+                self.y - self.half_length() - cw.y1
+            }
+            Direction::Down if self.x >= cw.x0 && self.x <= cw.x1 => {
+                cw.y0 - (self.y + self.half_length())
+            }
+            Direction::Left if self.y >= cw.y0 && self.y <= cw.y1 => {
+                self.x - self.half_length() - cw.x1
+            }
+            Direction::Right if self.y >= cw.y0 && self.y <= cw.y1 => {
+                cw.x0 - (self.x + self.half_length())
+            }
+            _ => return None,
+        };
+
+        // Already on the zebra (gap < 0): keep going and clear it
+        if gap < 0.0 { None } else { Some(gap) }
+    }
+
+    /// Too close to stop in front of the crosswalk: this vehicle drives
+    /// through it even if someone starts crossing
+    pub fn committed_to_crosswalk(&self, cw: &Crosswalk) -> bool {
+        self.gap_to_crosswalk(cw).is_some_and(|gap| gap + CROSSWALK_STOP_MARGIN < self.braking_distance())
+    }
+
+    /// Speed cap from yielding to occupied crosswalks ahead
+    fn crosswalk_speed_limit(&self, crosswalks: &[Crosswalk]) -> f32 {
+        let braking_distance = self.braking_distance();
+        let mut limit = f32::MAX;
+
+        for cw in crosswalks.iter().filter(|cw| cw.is_occupied()) {
+            let Some(gap) = self.gap_to_crosswalk(cw) else {
+                continue;
+            };
+
+            // Too late to stop in front of it - drive through instead of stopping on it
+            if self.committed_to_crosswalk(cw) {
+                continue;
+            }
+
+            if gap < braking_distance + CROSSWALK_STOP_MARGIN * 2.0 {
+                limit = 0.0;
+            } else if gap < SAFETY_DISTANCE {
+                limit = limit.min(self.kind.speed(VelocityLevel::Slow));
+            }
+        }
+
+        limit
+    }
+
+    pub fn braking_distance(&self) -> f32 {
+        self.speed * self.speed / (2.0 * self.kind.acceleration())
+    }
+
+    /// Waypoint where a curb-turning car leaves its lane, while not yet reached
+    fn pending_curb_turn(&self) -> Option<(f32, f32)> {
+        // Turn paths end with [.., turn point, exit]; lane changes insert waypoints earlier
+        let turn_waypoint = self.path.len().checked_sub(2)?;
+
+        if self.kind != VehicleKind::Car
+            || self.junction != JunctionKind::Cross
+            || self.route != curb_route(self.direction)
+            || self.current_target > turn_waypoint
+        {
+            return None;
+        }
+        self.path.get(turn_waypoint).copied()
+    }
+
+    /// 🚲 Right-hook check: a car turning across the bike lane holds at its turn
+    /// point while a cyclist on the same approach is about to pass it
+    fn bike_lane_speed_limit(&mut self, neighbors: Neighborhood) -> f32 {
+        self.yielding_to_bike = false;
+
+        let Some((tx, ty)) = self.pending_curb_turn() else {
+            return f32::MAX;
+        };
+
+        let turn_progress = progress_along(self.direction, tx, ty);
+        let reach = BIKE_YIELD_WINDOW + self.road.lane_width as f32; // Yield window plus the lane offset
+        let bike_in_conflict = neighbors.within(tx, ty, reach).any(|b| {
+            let p = progress_along(self.direction, b.x, b.y);
+            b.in_bike_lane
+                && b.direction == self.direction
+                && p > turn_progress - BIKE_YIELD_WINDOW
+                && p < turn_progress + BIKE_CLEAR_DISTANCE
+        });
+
+        if !bike_in_conflict {
+            return f32::MAX;
+        }
+
+        let gap = ((tx - self.x).powi(2) + (ty - self.y).powi(2)).sqrt();
+        if gap < self.braking_distance() + CROSSWALK_STOP_MARGIN * 2.0 {
+            self.yielding_to_bike = true;
+            0.0
+        } else if gap < SAFETY_DISTANCE {
+            self.kind.speed(VelocityLevel::Slow)
+        } else {
+            f32::MAX
+        }
+    }
+
     /// Set velocity level for traffic control
     pub fn set_velocity_level(&mut self, level: VelocityLevel) {
         self.velocity_level = level;
-        self.target_speed = level.to_speed();
+        self.target_speed = self.kind.speed(level);
     }
 
     /// Update speed smoothly (acceleration/deceleration)
     fn update_speed(&mut self, dt: f32) {
-        let acceleration = 200.0; // pixels/s² - DOUBLED for faster recovery
+        let acceleration = self.kind.acceleration();
         let speed_diff = self.target_speed - self.speed;
         
         if speed_diff.abs() < acceleration * dt {
@@ -240,8 +516,9 @@ impl Vehicle {
         self.speed = self.speed.max(0.0);
     }
 
-    /// Move vehicle along tile-based path with collision avoidance
-    pub fn update(&mut self, dt: f32, other_vehicles: &[Vehicle]) {
+    /// Move vehicle along tile-based path with collision avoidance; the vehicles
+    /// to follow are the ones `sensor` perceives
+    pub fn update(&mut self, dt: f32, neighbors: Neighborhood, crosswalks: &[Crosswalk], sensor: &Sensor) {
         if self.current_target >= self.path.len() {
             return;
         }
@@ -249,42 +526,76 @@ impl Vehicle {
         // 📊 Track time in system
         self.time_in_system += dt;
 
-        // 🚦 Enhanced collision avoidance - check ALL nearby vehicles
+        // ↔️ Weave towards the lane our route needs
+        self.update_lane_change(neighbors);
+
+        // 🚦 Enhanced collision avoidance - check every vehicle perceived in range
         let mut should_slow_down = false;
         let mut closest_distance = f32::MAX;
+        let mut leader_stopped = false;
+        let mut leader_half_length = 0.0;
+        self.leader = None;
         
-        for other in other_vehicles {
-            // Use unique ID to skip self (works with cloned snapshots)
-            if self.id == other.id {
-                continue;
-            }
-            
+        for other in sensor.perceive(self, neighbors, SAFETY_DISTANCE) {
+            let other = other.as_ref();
             let distance = self.distance_to(other);
             
             // ONLY consider vehicles that are actually ahead in our lane
             if self.is_vehicle_ahead(other) && distance < SAFETY_DISTANCE {
                 should_slow_down = true;
-                closest_distance = closest_distance.min(distance);
+                if distance < closest_distance {
+                    closest_distance = distance;
+                    leader_stopped = other.speed < 1.0;
+                    leader_half_length = other.half_length();
+                    self.leader = Some(other.id);
+                }
             }
         }
 
         if should_slow_down {
             // Emergency brake if too close
-            if closest_distance < EMERGENCY_BRAKE_DISTANCE {
+            if leader_stopped && closest_distance < self.half_length() + leader_half_length + QUEUE_GAP {
+                self.target_speed = 0.0; // Queue up behind a stopped vehicle
+            } else if closest_distance < EMERGENCY_BRAKE_DISTANCE {
                 self.target_speed = MIN_CRAWL_SPEED; // Keep moving slowly instead of full stop
             } else if closest_distance < SAFETY_DISTANCE * 0.5 {
                 // Heavy braking but maintain minimum movement
-                self.target_speed = VelocityLevel::Slow.to_speed() * 0.6;
+                self.target_speed = self.kind.speed(VelocityLevel::Slow) * 0.6;
             } else {
                 // Gradual slowdown proportional to distance
                 let slow_factor = (closest_distance / SAFETY_DISTANCE).max(0.5);
-                self.target_speed = self.velocity_level.to_speed() * slow_factor;
+                self.target_speed = self.cruise_speed() * slow_factor;
             }
         } else {
             // Resume normal speed if no obstacles
-            self.target_speed = self.velocity_level.to_speed();
+            self.target_speed = self.cruise_speed();
         }
 
+        // 🧑 Human drivers act a reaction time late, pulling away and braking
+        // alike, unless the car ahead is about to get closer than they can
+        // still stop in: then they brake at once
+        let bumper_gap = closest_distance - self.half_length() - leader_half_length;
+        let last_moment = should_slow_down && bumper_gap < self.braking_distance() + QUEUE_GAP;
+        if let Some(driver) = &mut self.driver {
+            let acted = driver.react(self.time_in_system, self.target_speed);
+            self.target_speed = if last_moment { self.target_speed.min(acted) } else { acted };
+        }
+
+        // 🚲 Turning cars yield to cyclists on the bike lane
+        self.target_speed = self.target_speed.min(self.bike_lane_speed_limit(neighbors));
+
+        // 🦓 Yield to pedestrians on (or waiting at) crosswalks
+        let crosswalk_limit = self.crosswalk_speed_limit(crosswalks);
+        self.target_speed = self.target_speed.min(crosswalk_limit);
+
+        // ⭕ Give way to circulating traffic before entering a roundabout
+        self.target_speed = self.target_speed.min(self.roundabout_speed_limit(neighbors));
+
+        // 🚦 Hold at a red light, and at the stop line until the junction grants entry
+        let signal_limit = self.signal_speed_limit().min(self.admission_speed_limit());
+        self.target_speed = self.target_speed.min(signal_limit);
+        self.held = self.yielding_to_bike || crosswalk_limit.min(signal_limit) <= self.target_speed;
+
         // 🎯 Update speed smoothly
         self.update_speed(dt);
 
@@ -305,7 +616,7 @@ impl Vehicle {
         let dy = ty - self.y;
         let dist = (dx * dx + dy * dy).sqrt();
 
-        if dist < self.speed * dt {
+        if dist < self.speed * dt || dist < f32::EPSILON {
             self.current_target += 1;
             return;
         }
@@ -318,12 +629,18 @@ impl Vehicle {
         self.distance_traveled += movement;
     }
 
-    pub fn draw(
+    pub fn draw<T: RenderTarget>(
         &self,
-        canvas: &mut Canvas<Window>,
+        canvas: &mut Canvas<T>,
         textures: &HashMap<(usize, Direction), Texture>,
     ) {
         let dir = self.facing_direction();
+
+        if self.is_bicycle() {
+            draw_bicycle(canvas, self.x, self.y, dir);
+            return;
+        }
+
         let texture = &textures[&(self.car_id, dir)];
 
         use sdl2::render::TextureQuery;
@@ -341,6 +658,12 @@ impl Vehicle {
         );
 
         canvas.copy(texture, None, dst).unwrap();
+
+        // 🧑 Human-driven cars carry an orange roof marker
+        if self.driver.is_some() {
+            canvas.set_draw_color(Color::RGB(255, 140, 0));
+            canvas.fill_rect(Rect::new(self.x as i32 - 4, self.y as i32 - 4, 8, 8)).ok();
+        }
     }
 
     pub fn facing_direction(&self) -> Direction {
@@ -368,115 +691,27 @@ impl Vehicle {
     }
 
     pub fn is_out_of_bounds(&self) -> bool {
-        self.x < -200.0 || self.x > (GRID_W * TILE_SIZE + 200) as f32
-            || self.y < -200.0 || self.y > (GRID_H * TILE_SIZE + 200) as f32
-    }
-
-    /// Get intersection traversal time
-    pub fn get_intersection_time(&self) -> f32 {
-        if self.intersection_exit_time > 0.0 {
-            self.intersection_exit_time - self.intersection_entry_time
-        } else if self.entered_intersection {
-            self.time_in_system - self.intersection_entry_time
-        } else {
-            0.0
-        }
+        let size = self.road.pixel_size() as f32;
+        self.x < -200.0 || self.x > size + 200.0
+            || self.y < -200.0 || self.y > size + 200.0
     }
 
-    /// Get average velocity
-    pub fn get_average_velocity(&self) -> f32 {
-        if self.time_in_system > 0.0 {
-            self.distance_traveled / self.time_in_system
-        } else {
-            0.0
-        }
+    /// Done with the run: paths end just off the map, so reaching the last
+    /// waypoint means the vehicle has driven off
+    pub fn has_left(&self) -> bool {
+        self.current_target >= self.path.len() || self.is_out_of_bounds()
     }
 }
 
 // =======================================================
-// 🧭 TILE-BASED PATH GENERATION
+// 🧭 PATH GENERATION
 // =======================================================
 
-pub fn build_path(dir: Direction, route: Route) -> Vec<(f32, f32)> {
-    let entry = entry_lane_tile(dir, route);
-    let exit = exit_lane_tile(dir, route);
-
-    let mut tiles = Vec::new();
-
-    match dir {
-        Direction::Up => {
-            tiles.push((entry, GRID_H + 1));
-            tiles.push((entry, INTERSECTION_MAX));
-
-            match route {
-                Route::Straight => {
-                    tiles.push((entry, -2));
-                }
-                Route::Left => {
-                    tiles.push((entry, INTERSECTION_MAX - 4));
-                    tiles.push((exit, INTERSECTION_MAX - 4));
-                }
-                Route::Right => {
-                    tiles.push((entry, INTERSECTION_MAX));
-                    tiles.push((exit, INTERSECTION_MAX));
-                }
-            }
-        }
-        Direction::Down => {
-            tiles.push((entry, -2));
-            tiles.push((entry, INTERSECTION_MIN));
-
-            match route {
-                Route::Straight => {
-                    tiles.push((entry, GRID_H + 1));
-                }
-                Route::Left => {
-                    tiles.push((entry, INTERSECTION_MIN));
-                    tiles.push((exit, INTERSECTION_MIN));
-                }
-                Route::Right => {
-                    tiles.push((entry, INTERSECTION_MIN + 4));
-                    tiles.push((exit, INTERSECTION_MIN + 4));
-                }
-            }
-        }
-        Direction::Left => {
-            tiles.push((GRID_W + 1, entry));
-            tiles.push((INTERSECTION_MAX, entry));
-
-            match route {
-                Route::Straight => {
-                    tiles.push((-2, entry));
-                }
-                Route::Left => {
-                    tiles.push((INTERSECTION_MAX, entry));
-                    tiles.push((INTERSECTION_MAX, exit));
-                }
-                Route::Right => {
-                    tiles.push((INTERSECTION_MAX - 4, entry));
-                    tiles.push((INTERSECTION_MAX - 4, exit));
-                }
-            }
-        }
-        Direction::Right => {
-            tiles.push((-2, entry));
-            tiles.push((INTERSECTION_MIN, entry));
-
-            match route {
-                Route::Straight => {
-                    tiles.push((GRID_W + 1, entry));
-                }
-                Route::Left => {
-                    tiles.push((INTERSECTION_MIN + 4, entry));
-                    tiles.push((INTERSECTION_MIN + 4, exit));
-                }
-                Route::Right => {
-                    tiles.push((INTERSECTION_MIN, entry));
-                    tiles.push((INTERSECTION_MIN, exit));
-                }
-            }
-        }
+/// Path through whichever junction layout is in use, approaching in `lane`
+pub fn build_junction_path(g: &RoadGeometry, dir: Direction, route: Route, junction: JunctionKind, lane: i32) -> Vec<(f32, f32)> {
+    match junction {
+        JunctionKind::Cross => g.cross_path(dir, route, lane),
+        JunctionKind::Roundabout => build_roundabout_path(g, dir, route, lane),
     }
+}
 
-    tiles.into_iter().map(|(x, y)| tile_center(x, y)).collect()
-}
\ No newline at end of file
@@ -0,0 +1,248 @@
+// Control API: line-delimited JSON over a local socket
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpStream;
+use std::time::{Duration, Instant};
+
+use smart_road::api::{self, parse_request, ApiServer, Request, RunControl};
+use smart_road::config::{JunctionKind, SimConfig};
+use smart_road::network::RoadNetwork;
+use smart_road::replay::Recording;
+use smart_road::simulation::{Simulation, STEP};
+use smart_road::stats::Stats;
+use smart_road::vehicle::{Direction, Route, VelocityLevel};
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+#[test]
+fn requests_parse_from_flat_json() {
+    assert_eq!(
+        parse_request(r#"{"cmd":"spawn","dir":"up","route":"uturn"}"#),
+        Ok(Request::Spawn { dir: Some(Direction::Up), route: Some(Route::UTurn) })
+    );
+    assert_eq!(parse_request(r#" { "cmd" : "spawn" } "#), Ok(Request::Spawn { dir: None, route: None }));
+    assert_eq!(parse_request(r#"{"cmd":"step","n":30}"#), Ok(Request::Step(30)));
+    assert_eq!(parse_request(r#"{"cmd":"step"}"#), Ok(Request::Step(1)));
+    assert_eq!(parse_request(r#"{"cmd":"speed","level":"fast"}"#), Ok(Request::Speed(VelocityLevel::Fast)));
+    assert_eq!(parse_request(r#"{"cmd":"auto_spawn","on":false}"#), Ok(Request::AutoSpawn(false)));
+    assert_eq!(parse_request(r#"{"cmd":"policy","junction":"roundabout"}"#), Ok(Request::Policy(JunctionKind::Roundabout)));
+    assert_eq!(parse_request(r#"{"cmd":"quit"}"#), Ok(Request::Quit));
+
+    for bad in [
+        "",
+        "spawn",
+        r#"{"cmd":"fly"}"#,
+        r#"{"dir":"up"}"#,
+        r#"{"cmd":"spawn","dir":"north"}"#,
+        r#"{"cmd":"step","n":-2}"#,
+        r#"{"cmd":"step","n":1.5}"#,
+        r#"{"cmd":"auto_spawn","on":"yes"}"#,
+        r#"{"cmd":"spawn","dir":{"x":1}}"#,
+        r#"{"cmd":"pause"} extra"#,
+    ] {
+        assert!(parse_request(bad).is_err(), "accepted {:?}", bad);
+    }
+}
+
+#[test]
+fn paused_runs_take_only_the_steps_asked_for() {
+    let mut control = RunControl::default();
+    let mut sim = Simulation::new(SimConfig { seed: Some(1), ..SimConfig::default() });
+    for request in [Request::Pause, Request::Step(3)] {
+        api::apply_to_simulation(&mut sim, &mut control, request, false).unwrap();
+    }
+    let taken = (0..10).filter(|_| control.take_step()).count();
+    assert_eq!(taken, 3);
+
+    api::apply_to_simulation(&mut sim, &mut control, Request::Resume, false).unwrap();
+    assert!(control.take_step());
+}
+
+#[test]
+fn api_inputs_are_recorded_and_replays_refuse_them() {
+    let mut control = RunControl::default();
+    let args = ["--av-penetration".to_string(), "0.5".to_string()];
+    let mut sim = Simulation::new(SimConfig { seed: Some(1), ..SimConfig::from_arg_list(&args) });
+    let spawn = Request::Spawn { dir: Some(Direction::Left), route: Some(Route::Left) };
+    api::apply_to_simulation(&mut sim, &mut control, spawn, false).unwrap();
+    api::apply_to_simulation(&mut sim, &mut control, Request::Policy(JunctionKind::Roundabout), false).unwrap();
+
+    assert_eq!(sim.vehicles.len(), 1);
+    assert_eq!((sim.vehicles[0].direction, sim.vehicles[0].route), (Direction::Left, Route::Left));
+    assert_eq!(sim.config.junction, JunctionKind::Roundabout);
+    assert_eq!(sim.log.len(), 2);
+
+    assert!(api::apply_to_simulation(&mut sim, &mut control, spawn, true).is_err());
+    assert!(api::apply_to_simulation(&mut sim, &mut control, Request::Pause, true).is_ok());
+    assert_eq!(sim.log.len(), 2);
+
+    // Random spawns draw from the run's RNG when applied, so a replay puts
+    // every vehicle back where it was
+    let random = Request::Spawn { dir: None, route: None };
+    for _ in 0..6 {
+        api::apply_to_simulation(&mut sim, &mut control, random, false).unwrap();
+        for _ in 0..40 {
+            sim.step(STEP);
+        }
+    }
+    let rec = Recording::parse(&recording_text(&Recording::capture(&sim, &args))).unwrap();
+    let replayed = rec.simulate_to(sim.steps);
+    let positions = |sim: &Simulation| -> Vec<_> { sim.vehicles.iter().map(|v| (v.direction, v.route, v.x, v.y)).collect() };
+    assert!(sim.vehicles.len() > 2);
+    assert_eq!(positions(&replayed), positions(&sim));
+}
+
+/// A recording as it would be saved to a file
+fn recording_text(rec: &Recording) -> String {
+    let path = std::env::temp_dir().join(format!("smart-road-api-{}.rec", std::process::id()));
+    rec.save(&path).unwrap();
+    let text = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).ok();
+    text
+}
+
+#[test]
+fn stats_without_a_value_are_null() {
+    let mut stats = Stats::new();
+    stats.runtime = f32::NAN;
+    stats.total_distance = f32::INFINITY;
+    let json = api::stats_json(&stats);
+    assert!(json.contains(r#""runtime":null"#) && json.contains(r#""total_distance":null"#), "{}", json);
+    assert!(!json.contains("NaN") && !json.contains("inf"), "{}", json);
+}
+
+#[test]
+fn network_rejects_what_it_cannot_do() {
+    let mut network = RoadNetwork::grid(2, 2);
+    let (mut control, mut auto_spawn) = (RunControl::default(), true);
+    let mut rng = StdRng::seed_from_u64(2);
+    let mut stats = Stats::new();
+    let mut apply = |request| api::apply_to_network(&mut network, &mut control, &mut auto_spawn, &mut rng, &mut stats, request);
+
+    assert!(apply(Request::Spawn { dir: None, route: None }).is_ok());
+    assert!(apply(Request::Policy(JunctionKind::Roundabout)).is_err());
+    assert!(apply(Request::SpawnPedestrian).is_err());
+    assert!(apply(Request::AutoSpawn(false)).is_ok());
+    assert!(!auto_spawn);
+    assert_eq!(stats.network_spawned, 1);
+}
+
+/// Poll the server until the client has `n` more lines to read
+fn read_lines(server: &mut ApiServer, sim: &mut Simulation, control: &mut RunControl, reader: &mut BufReader<TcpStream>, n: usize) -> Vec<String> {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    let mut lines = Vec::new();
+    while lines.len() < n {
+        assert!(Instant::now() < deadline, "timed out with {:?}", lines);
+        server.serve(|request| api::apply_to_simulation(sim, control, request, false));
+        let mut line = String::new();
+        if reader.read_line(&mut line).is_ok() && !line.is_empty() {
+            lines.push(line.trim().to_string());
+        }
+    }
+    lines
+}
+
+#[test]
+fn clients_drive_the_simulation_and_get_ticks() {
+    let mut server = ApiServer::bind("127.0.0.1:0").unwrap();
+    let mut client = TcpStream::connect(server.local_addr().unwrap()).unwrap();
+    client.set_read_timeout(Some(Duration::from_millis(20))).unwrap();
+    let mut reader = BufReader::new(client.try_clone().unwrap());
+
+    let mut sim = Simulation::new(SimConfig { seed: Some(5), ..SimConfig::default() });
+    let mut control = RunControl::default();
+
+    writeln!(client, r#"{{"cmd":"spawn","dir":"up"}}"#).unwrap();
+    writeln!(client, "not json").unwrap();
+    writeln!(client, r#"{{"cmd":"stream","every":2}}"#).unwrap();
+    let replies = read_lines(&mut server, &mut sim, &mut control, &mut reader, 3);
+    assert_eq!(replies[0], r#"{"type":"ok","cmd":"spawn"}"#);
+    assert!(replies[1].starts_with(r#"{"type":"error","message":"#), "{}", replies[1]);
+    assert_eq!(replies[2], r#"{"type":"ok","cmd":"stream"}"#);
+    assert_eq!(sim.vehicles.len(), 1);
+    assert_eq!(server.client_count(), 1);
+
+    // Every second step is streamed
+    for _ in 0..4 {
+        sim.step(STEP);
+        server.tick(sim.steps, || api::simulation_tick(&sim));
+    }
+    let ticks = read_lines(&mut server, &mut sim, &mut control, &mut reader, 2);
+    assert!(ticks[0].starts_with(r#"{"type":"tick","step":2,"#), "{}", ticks[0]);
+    assert!(ticks[1].starts_with(r#"{"type":"tick","step":4,"#), "{}", ticks[1]);
+    assert!(ticks[1].contains(r#""dir":"up""#) && ticks[1].contains(r#""total_vehicles":1"#), "{}", ticks[1]);
+
+    writeln!(client, r#"{{"cmd":"quit"}}"#).unwrap();
+    read_lines(&mut server, &mut sim, &mut control, &mut reader, 1);
+    assert!(control.quit);
+
+    drop(reader);
+    drop(client);
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while server.client_count() > 0 && Instant::now() < deadline {
+        server.serve(|_| Ok(()));
+    }
+    assert_eq!(server.client_count(), 0);
+}
+
+#[test]
+fn slow_readers_get_whole_lines_until_they_fall_too_far_behind() {
+    let mut server = ApiServer::bind("127.0.0.1:0").unwrap();
+    let client = TcpStream::connect(server.local_addr().unwrap()).unwrap();
+    client.set_read_timeout(Some(Duration::from_millis(20))).unwrap();
+    let mut reader = BufReader::new(client.try_clone().unwrap());
+    server.serve(|_| Ok(()));
+
+    // More than the socket buffers while nobody reads
+    let tick = |step: u64| format!("{{\"type\":\"tick\",\"step\":{},\"pad\":\"{}\"}}", step, "x".repeat(16 << 10));
+    for step in 1..=200 {
+        server.tick(step, || tick(step));
+    }
+    assert_eq!(server.client_count(), 1);
+
+    let deadline = Instant::now() + Duration::from_secs(5);
+    for step in 1..=200 {
+        let mut line = String::new();
+        while line.is_empty() || !line.ends_with('\n') {
+            assert!(Instant::now() < deadline, "stuck before step {}", step);
+            server.serve(|_| Ok(()));
+            let _ = reader.read_line(&mut line);
+        }
+        assert_eq!(line.trim_end(), tick(step));
+    }
+
+    // Too far behind: dropped
+    let big = "x".repeat(1 << 20);
+    for step in 0..(api::MAX_OUTGOING >> 20) as u64 + 64 {
+        server.tick(step, || big.clone());
+    }
+    server.serve(|_| Ok(()));
+    assert_eq!(server.client_count(), 0);
+}
+
+#[test]
+fn clients_sending_endless_lines_are_dropped() {
+    let mut server = ApiServer::bind("127.0.0.1:0").unwrap();
+    let mut client = TcpStream::connect(server.local_addr().unwrap()).unwrap();
+    client.set_read_timeout(Some(Duration::from_millis(20))).unwrap();
+    let mut reader = BufReader::new(client.try_clone().unwrap());
+
+    // Replies name the command that was parsed, wherever "cmd" sits
+    client.write_all(b"{\"dir\":\"up\",\"note\":\"cmd\",\"cmd\":\"spawn\"}\n").unwrap();
+    let mut line = String::new();
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while !line.ends_with('\n') {
+        assert!(Instant::now() < deadline, "no reply");
+        server.serve(|_| Ok(()));
+        let _ = reader.read_line(&mut line);
+    }
+    assert_eq!(line.trim_end(), r#"{"type":"ok","cmd":"spawn"}"#);
+
+    // No newline in sight: the server stops buffering and hangs up
+    let _ = client.write_all(&vec![b' '; api::MAX_PENDING + 1]);
+    while server.client_count() > 0 {
+        assert!(Instant::now() < deadline, "client kept");
+        server.serve(|_| Ok(()));
+    }
+}
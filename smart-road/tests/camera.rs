@@ -0,0 +1,108 @@
+// Camera: zoom at the cursor, drag to pan, click to follow
+
+use sdl2::event::Event;
+use sdl2::keyboard::{Keycode, Mod};
+use sdl2::mouse::{MouseButton, MouseState, MouseWheelDirection};
+
+use smart_road::camera::{Camera, CameraControl};
+
+fn wheel(notches: f32, x: i32, y: i32) -> Event {
+    Event::MouseWheel {
+        timestamp: 0,
+        window_id: 0,
+        which: 0,
+        x: 0,
+        y: notches as i32,
+        direction: MouseWheelDirection::Normal,
+        precise_x: 0.0,
+        precise_y: notches,
+        mouse_x: x,
+        mouse_y: y,
+    }
+}
+
+fn button(down: bool, x: i32, y: i32) -> Event {
+    let (timestamp, window_id, which, mouse_btn, clicks) = (0, 0, 0, MouseButton::Left, 1);
+    if down {
+        Event::MouseButtonDown { timestamp, window_id, which, mouse_btn, clicks, x, y }
+    } else {
+        Event::MouseButtonUp { timestamp, window_id, which, mouse_btn, clicks, x, y }
+    }
+}
+
+fn motion(x: i32, y: i32) -> Event {
+    let mousestate = MouseState::from_sdl_state(1);
+    Event::MouseMotion { timestamp: 0, window_id: 0, which: 0, mousestate, x, y, xrel: 0, yrel: 0 }
+}
+
+fn key(keycode: Keycode) -> Event {
+    Event::KeyDown { timestamp: 0, window_id: 0, keycode: Some(keycode), scancode: None, keymod: Mod::NOMOD, repeat: false }
+}
+
+fn control() -> CameraControl {
+    CameraControl::new(Camera::fit(0.0, 0.0, 900.0, 900.0, 900, 900), 900, 900)
+}
+
+#[test]
+fn wheel_zooms_around_the_cursor() {
+    let mut view = control();
+    let under = view.camera.to_world(200, 600);
+    view.handle(&wheel(3.0, 200, 600));
+
+    assert!(view.camera.zoom > 1.4);
+    let (x, y) = view.camera.to_world(200, 600);
+    assert!((x - under.0).abs() < 0.01 && (y - under.1).abs() < 0.01);
+
+    // Reverse scroll zooms back out; Z restores the whole map
+    view.handle(&wheel(-3.0, 200, 600));
+    assert!((view.camera.zoom - 1.0).abs() < 1e-3);
+    view.handle(&wheel(5.0, 10, 10));
+    view.handle(&key(Keycode::Z));
+    assert_eq!(view.camera, Camera::fit(0.0, 0.0, 900.0, 900.0, 900, 900));
+}
+
+#[test]
+fn dragging_pans_and_clicking_picks() {
+    let mut view = control();
+    view.camera.zoom = 2.0;
+
+    for event in [button(true, 100, 100), motion(104, 100), motion(150, 140)] {
+        assert_eq!(view.handle(&event), None);
+    }
+    assert_eq!(view.handle(&button(false, 150, 140)), None, "a drag is not a click");
+    // The map moved with the mouse: 50x40 screen px at zoom 2
+    assert_eq!((view.camera.x, view.camera.y), (-25.0, -20.0));
+
+    view.handle(&button(true, 300, 300));
+    view.handle(&motion(302, 301)); // Hand jitter
+    let clicked = view.handle(&button(false, 302, 301)).expect("a click");
+    assert_eq!(clicked, view.camera.to_world(302, 301));
+
+    let vehicles = [(1, clicked.0 + 30.0, clicked.1), (2, clicked.0 + 5.0, clicked.1 + 5.0)];
+    assert_eq!(view.pick(clicked, vehicles.into_iter()), Some(2));
+    assert_eq!(view.pick((0.0, 0.0), vehicles.into_iter()), None);
+}
+
+#[test]
+fn following_keeps_the_vehicle_centered_until_it_leaves() {
+    let mut view = control();
+    view.camera.zoom = 2.0;
+    view.following = Some(7);
+
+    assert_eq!(view.follow(|_| Some((400.0, 300.0))), None);
+    assert_eq!(view.camera.to_screen(400.0, 300.0), (450, 450));
+
+    // Zooming while following stays on the vehicle, not the cursor
+    view.handle(&wheel(2.0, 0, 0));
+    assert_eq!(view.camera.to_screen(400.0, 300.0), (450, 450));
+
+    assert_eq!(view.follow(|_| None), Some(7));
+    assert_eq!(view.following, None);
+
+    // Dragging takes the camera back
+    view.following = Some(8);
+    for event in [button(true, 0, 0), motion(40, 0), button(false, 40, 0)] {
+        view.handle(&event);
+    }
+    assert_eq!(view.following, None);
+}
@@ -0,0 +1,165 @@
+// RL environment: reset/step, actions, observations and rewards
+
+use std::sync::Arc;
+
+use smart_road::config::JunctionKind;
+use smart_road::env::{Action, Env, EnvConfig, Observation, RewardTerms, RewardWeights};
+use smart_road::geometry::RoadGeometry;
+use smart_road::safety::{Invariant, SafetyMonitor};
+use smart_road::signal::Phase;
+use smart_road::simulation::{DIRECTIONS, STEP};
+use smart_road::stats::Stats;
+use smart_road::vehicle::{Direction, Route, Vehicle, VehicleKind, VelocityLevel};
+
+fn env(episode_length: f32) -> Env {
+    Env::new(EnvConfig { episode_length, ..EnvConfig::default() })
+}
+
+#[test]
+fn episodes_replay_from_the_seed_and_actions() {
+    let actions = [Action::Keep, Action::Signal(Some(Phase::EastWest)), Action::Approaches([Some(VelocityLevel::Fast); 4])];
+    let run = |seed| {
+        let mut e = env(15.0);
+        e.reset(seed);
+        let mut rewards = Vec::new();
+        for step in 0.. {
+            let (obs, reward, done) = e.step(&actions[step % actions.len()]);
+            rewards.push((reward, obs.queue_lengths(), obs.vehicles.len()));
+            if done {
+                break;
+            }
+        }
+        rewards
+    };
+
+    let first = run(11);
+    assert_eq!(first.len(), 15);
+    assert_eq!(first, run(11));
+    assert_ne!(first, run(12));
+}
+
+#[test]
+fn observations_cover_every_entry_lane() {
+    let mut e = env(20.0);
+    let obs = e.reset(3);
+    let lanes: usize = DIRECTIONS.iter().map(|&d| e.sim.road.entry_lanes(d).len()).sum();
+    assert_eq!(obs.lanes.len(), lanes);
+    assert!(obs.vehicles.is_empty() && obs.signal.is_none());
+
+    let (obs, _, _) = e.step(&Action::Keep);
+    assert_eq!(obs.queue_lengths().len(), lanes);
+    assert!((obs.time - 1.0).abs() < 1e-3);
+
+    // Cyclists are left out of the lane counts
+    let bike_approaching = |obs: &Observation| obs.vehicles.iter().any(|v| !v.in_junction && v.kind == VehicleKind::Bicycle);
+    let obs = (0..20).map(|_| e.step(&Action::Keep).0).find(bike_approaching).expect("a cyclist");
+    let approaching: u32 = obs.lanes.iter().map(|l| l.approaching).sum();
+    let cars = obs.vehicles.iter().filter(|v| !v.in_junction && v.kind != VehicleKind::Bicycle);
+    assert_eq!(approaching as usize, cars.count());
+}
+
+#[test]
+fn red_lights_hold_traffic_at_the_stop_line() {
+    let mut e = env(60.0);
+    e.reset(5);
+    e.step(&Action::Signal(Some(Phase::AllRed)));
+    // Whoever was too close to stop has cleared by now
+    for _ in 0..10 {
+        e.step(&Action::Keep);
+    }
+    let exited = e.sim.stats.vehicles_exited;
+
+    let mut queued = 0;
+    for _ in 0..20 {
+        let (obs, _, _) = e.step(&Action::Keep);
+        assert!(obs.vehicles.iter().all(|v| !v.in_junction), "ran a red light");
+        queued = obs.queue_lengths().iter().sum::<f32>() as u32;
+    }
+    assert_eq!(e.sim.stats.vehicles_exited, exited);
+    assert!(queued > 4, "{} queued", queued);
+    assert_eq!(e.sim.stats.unauthorized_entries, 0);
+
+    // Green for north-south lets only that axis go
+    let (obs, _, _) = e.step(&Action::Signal(Some(Phase::NorthSouth)));
+    assert!(obs.vehicles.iter().filter(|v| v.in_junction).all(|v| matches!(v.direction, Direction::Up | Direction::Down)));
+    assert!(e.sim.stats.vehicles_exited >= exited);
+}
+
+#[test]
+fn queues_at_a_long_red_are_not_stalled() {
+    let mut e = env(60.0);
+    e.reset(4);
+    e.step(&Action::Signal(Some(Phase::AllRed)));
+    let mut queued = 0.0;
+    while !e.done() {
+        let (obs, _, _) = e.step(&Action::Keep);
+        queued = obs.queue_lengths().iter().sum();
+    }
+    assert!(queued > 8.0, "{} queued", queued);
+    assert_eq!(e.sim.stats.stalls, 0);
+}
+
+/// Does the safety monitor flag a fast car that was `gap` px short of the
+/// stop line when its light went red, then entered the junction?
+fn entry_on_red_flagged(gap: f32) -> bool {
+    let g = Arc::new(RoadGeometry::default());
+    let lane = g.spawn_lanes(Direction::Up)[0];
+    let mut car = Vehicle::new(&g, Direction::Up, Route::Straight, 1, lane, JunctionKind::Cross);
+    car.set_velocity_level(VelocityLevel::Fast);
+    car.speed = car.target_speed;
+    car.red_light = true;
+    car.y = -g.approach_end(Direction::Up) + car.half_length() + gap;
+
+    let mut entered = car.clone();
+    entered.y -= gap + car.half_length() * 2.0;
+    entered.entered_intersection = true;
+
+    let (mut monitor, mut stats) = (SafetyMonitor::new(false), Stats::new());
+    monitor.check(1, std::slice::from_ref(&car), &[entered], STEP, &mut stats);
+    monitor.violations.iter().any(|v| v.invariant == Invariant::Unauthorized)
+}
+
+#[test]
+fn entering_on_red_is_unauthorized_unless_too_close_to_stop() {
+    assert!(entry_on_red_flagged(150.0));
+    // Fast cars need 81 px to stop: this one drives on as if on amber
+    assert!(!entry_on_red_flagged(20.0));
+}
+
+#[test]
+fn velocity_actions_reach_the_right_vehicles() {
+    let mut e = env(30.0);
+    e.reset(9);
+    let (obs, _, _) = (0..4).map(|_| e.step(&Action::Keep)).last().unwrap();
+    let target = obs.vehicles[0].id;
+
+    e.step(&Action::Vehicles(vec![(target, VelocityLevel::Slow), (usize::MAX, VelocityLevel::Fast)]));
+    let v = e.sim.vehicles.iter().find(|v| v.id == target).unwrap();
+    assert_eq!(v.velocity_level, VelocityLevel::Slow);
+
+    let mut levels = [None; 4];
+    levels[0] = Some(VelocityLevel::Fast); // Up
+    let (obs, _, _) = e.step(&Action::Approaches(levels));
+    let set_before_entry = obs.vehicles.iter().filter(|v| v.direction == Direction::Up && v.velocity_level == VelocityLevel::Fast);
+    assert!(set_before_entry.count() > 0);
+    assert!(obs.vehicles.iter().filter(|v| v.direction != Direction::Up).all(|v| v.velocity_level != VelocityLevel::Fast));
+}
+
+#[test]
+fn reward_weighs_throughput_delay_and_collisions() {
+    let terms = RewardTerms { exited: 3, delay: 20.0, collisions: 1 };
+    let weights = RewardWeights { throughput: 2.0, delay: 0.5, collisions: 4.0 };
+    assert_eq!(terms.reward(&weights), 6.0 - 10.0 - 4.0);
+
+    let mut e = env(40.0);
+    e.reset(2);
+    let mut done = false;
+    while !done {
+        let (_, reward, end) = e.step(&Action::Keep);
+        assert_eq!(reward, e.last_terms.reward(&e.config.reward));
+        assert!(e.last_terms.delay >= 0.0);
+        done = end;
+    }
+    assert!((e.sim.time() - 40.0).abs() < 1e-3);
+    assert!(e.sim.stats.vehicles_exited > 0);
+}
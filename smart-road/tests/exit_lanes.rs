@@ -0,0 +1,107 @@
+// Exit lanes on a layout with uneven lane counts
+
+use smart_road::config::{JunctionKind, MAX_LANES};
+use smart_road::geometry::RoadGeometry;
+use smart_road::lane_use::Approach;
+use smart_road::scenario::{Scenario, Spawn};
+use smart_road::simulation::{DIRECTIONS, ROUTES};
+use smart_road::vehicle::{exit_side, Direction, Route};
+
+/// Northbound: a double left turn, only two lanes out; westbound: a pocket for
+/// the turn across the median
+fn uneven_layout() -> RoadGeometry {
+    let mut road = RoadGeometry::new(3);
+    road.set_approach(Direction::Up, Approach::parse("L,L,TR/2", MAX_LANES).unwrap());
+    road.set_approach(Direction::Left, Approach::parse("Lp,T,TR/3", MAX_LANES).unwrap());
+    road
+}
+
+#[test]
+fn planned_exit_lanes_exist_on_the_exit_side() {
+    let g = uneven_layout();
+    for dir in DIRECTIONS {
+        for route in ROUTES.into_iter().chain([Route::UTurn]) {
+            for lane in g.entry_lanes(dir) {
+                let exit = g.exit_lane(dir, route, lane);
+                assert_eq!(exit.side, exit_side(dir, route));
+                assert!(g.exit_lanes(exit.side).contains(&exit.lane), "{:?} {:?} from {}: {:?}", dir, route, lane, exit);
+            }
+        }
+    }
+}
+
+#[test]
+fn double_left_turns_leave_side_by_side() {
+    let g = uneven_layout();
+    let [inner, outer] = g.entry_lanes(Direction::Up)[..2] else { unreachable!() };
+    assert_ne!(g.exit_lane(Direction::Up, Route::Left, inner), g.exit_lane(Direction::Up, Route::Left, outer));
+}
+
+#[test]
+fn every_movement_leaves_by_its_planned_lane() {
+    for junction in [JunctionKind::Cross, JunctionKind::Roundabout] {
+        for dir in DIRECTIONS {
+            for route in ROUTES {
+                let mut scenario = Scenario::new(11).with_spawn(0.0, Spawn::Vehicle(dir, route));
+                scenario.config.junction = junction;
+                scenario.config.road = uneven_layout();
+                let outcome = scenario.run();
+
+                outcome.assert_exits(0, exit_side(dir, route));
+                outcome.assert_planned_exit_lanes();
+                assert_eq!(outcome.stats.wrong_exits, 0, "{:?} {:?} {:?}", junction, dir, route);
+            }
+        }
+    }
+}
+
+#[test]
+fn layouts_and_lane_widths_coexist_in_one_process() {
+    let mut wide = uneven_layout();
+    wide.lane_width = 70;
+    assert_eq!(wide.pixel_size(), wide.grid_size() * 70);
+    assert_eq!(wide.lane_center(2), 175.0);
+
+    // Both layouts run at the same time, each by its own lanes
+    let runs: Vec<_> = [RoadGeometry::default(), wide]
+        .into_iter()
+        .map(|road| {
+            std::thread::spawn(move || {
+                let mut scenario = Scenario::new(5)
+                    .with_spawn(0.0, Spawn::Vehicle(Direction::Up, Route::Left))
+                    .with_spawn(0.0, Spawn::Vehicle(Direction::Left, Route::Straight));
+                scenario.config.road = road;
+                scenario.run()
+            })
+        })
+        .collect();
+
+    for outcome in runs.into_iter().map(|run| run.join().unwrap()) {
+        outcome.assert_all_exited();
+        outcome.assert_planned_exit_lanes();
+        assert_eq!(outcome.stats.wrong_exits, 0);
+    }
+}
+
+#[test]
+fn approach_specs_are_checked() {
+    // A single lane has no inner lanes to hold a pocket
+    let single = Approach::parse("LTR/1", MAX_LANES).unwrap();
+    assert_eq!((single.lanes.len(), single.exit_lanes), (1, 1));
+    assert!(Approach::parse("Lp/1", MAX_LANES).is_err());
+
+    assert!(Approach::parse("Lp,TR/2", MAX_LANES).is_ok());
+    assert!(Approach::parse("L,Tp,TR/2", MAX_LANES).is_err());
+    assert!(Approach::parse("L,T/2", MAX_LANES).is_err());
+
+    // A one-lane approach still serves every movement
+    let mut road = RoadGeometry::new(3);
+    road.set_approach(Direction::Up, single);
+    for route in ROUTES {
+        let mut scenario = Scenario::new(2).with_spawn(0.0, Spawn::Vehicle(Direction::Up, route));
+        scenario.config.road = road.clone();
+        let outcome = scenario.run();
+        outcome.assert_exits(0, exit_side(Direction::Up, route));
+        outcome.assert_planned_exit_lanes();
+    }
+}
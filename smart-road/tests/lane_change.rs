@@ -0,0 +1,59 @@
+// MOBIL lane changes towards the lane a car's route needs
+
+use std::sync::Arc;
+
+use smart_road::config::JunctionKind;
+use smart_road::geometry::RoadGeometry;
+use smart_road::lane_change::weaving_zone;
+use smart_road::spatial::{Neighborhood, SpatialHash};
+use smart_road::vehicle::{Direction, Route, Vehicle};
+
+/// Whether a stopped northbound car that has to turn left from the curb lane
+/// starts moving over, `room` pixels before the weaving zone ends, with
+/// stopped cars `own` and `next` pixels (bumper to bumper) ahead in its
+/// lane and in the next lane over
+fn moves_over(room: f32, own: Option<f32>, next: Option<f32>) -> bool {
+    let g = Arc::new(RoadGeometry::default());
+    let lanes = g.entry_lanes(Direction::Up);
+    let (curb, next_lane) = (lanes[lanes.len() - 1], lanes[lanes.len() - 2]);
+    assert!(!g.lane_allows(Direction::Up, curb, Route::Left));
+
+    let (_, zone_end) = weaving_zone(&g, Direction::Up);
+    let y = -(zone_end - room);
+    let car = |lane, route, y: f32| {
+        let mut v = Vehicle::new(&g, Direction::Up, route, 1, lane, JunctionKind::Cross);
+        v.x = g.lane_center(lane);
+        v.y = y;
+        v.speed = 0.0;
+        v
+    };
+
+    let mut cars = vec![car(curb, Route::Left, y)];
+    let length = 2.0 * cars[0].half_length();
+    for (lane, gap) in [(curb, own), (next_lane, next)] {
+        if let Some(gap) = gap {
+            cars.push(car(lane, Route::Straight, y - length - gap));
+        }
+    }
+
+    let mut grid = SpatialHash::new();
+    grid.rebuild(&cars);
+    let others = cars.clone();
+    cars[0].update_lane_change(Neighborhood::new(&others, &grid));
+    cars[0].lane_change.is_some()
+}
+
+#[test]
+fn early_changes_have_to_pay_off() {
+    // Behind a stopped car next door, moving over gains nothing yet
+    assert!(!moves_over(160.0, None, Some(40.0)));
+    // Stuck behind one in our own lane, it does
+    assert!(moves_over(160.0, Some(15.0), None));
+}
+
+#[test]
+fn the_route_lane_wins_near_the_end_of_the_zone() {
+    assert!(moves_over(70.0, None, Some(40.0)));
+    // Still never into a gap too short to fit
+    assert!(!moves_over(70.0, None, Some(5.0)));
+}
@@ -0,0 +1,73 @@
+// Loaded tile maps set the road layout vehicles drive on
+
+use std::path::PathBuf;
+
+use smart_road::config::SimConfig;
+use smart_road::geometry::RoadGeometry;
+use smart_road::map::{MapError, Tile, TileMap};
+use smart_road::scenario::{Scenario, Spawn};
+use smart_road::simulation::{Command, Simulation, DIRECTIONS, ROUTES};
+use smart_road::vehicle::exit_side;
+
+fn map_config(file: &str) -> SimConfig {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("assets/maps").join(file);
+    SimConfig::from_arg_list(&["--map".to_string(), path.display().to_string()])
+}
+
+#[test]
+fn the_bundled_cross_is_the_default_layout() {
+    let mut config = map_config("cross.txt");
+    config.load_map().unwrap();
+    assert_eq!(config.road, RoadGeometry::default());
+}
+
+#[test]
+fn a_two_lane_map_drives_on_two_lanes() {
+    let mut config = map_config("cross-2.txt");
+    let map = config.load_map().unwrap();
+    let road = RoadGeometry::new(2);
+    assert_eq!(config.road, road);
+    assert_eq!(map.width, road.grid_size());
+
+    // Vehicles appear just off the map in the map's lanes and plan its exits
+    config.seed = Some(3);
+    let mut sim = Simulation::new(config.clone());
+    for dir in DIRECTIONS {
+        for route in ROUTES {
+            sim.apply(Command::SpawnRoute(dir, route));
+            let v = sim.vehicles.pop().unwrap();
+            assert!(road.spawn_lanes(dir).contains(&v.lane), "{:?} spawned in lane {}", dir, v.lane);
+            assert_eq!(road.edge_past(v.x, v.y), Some(dir.opposite()));
+            // Every waypoint on the map is on a road tile of the map
+            let t = road.lane_width as f32;
+            let on_map = v.path.iter().filter(|&&(x, y)| road.edge_past(x, y).is_none());
+            assert!(on_map.into_iter().all(|&(x, y)| map.get((x / t) as i32, (y / t) as i32).is_road()));
+            assert_eq!(v.exit, road.exit_lane(dir, route, v.lane));
+        }
+    }
+
+    // ...and leave the map by those exits
+    let mut scenario = Scenario::new(3);
+    scenario.config = config;
+    for (i, dir) in DIRECTIONS.into_iter().enumerate() {
+        for (j, route) in ROUTES.into_iter().enumerate() {
+            scenario = scenario.with_spawn((i * 3 + j) as f32 * 1.5, Spawn::Vehicle(dir, route));
+        }
+    }
+    let outcome = scenario.run();
+    outcome.assert_all_exited();
+    outcome.assert_planned_exit_lanes();
+    for trip in &outcome.trips {
+        outcome.assert_exits(trip.spawn.unwrap(), exit_side(trip.direction, trip.route));
+    }
+}
+
+#[test]
+fn maps_of_no_known_layout_are_refused() {
+    let mut map = RoadGeometry::default().tile_map();
+    map.set(0, 0, Tile::HorizontalRoad);
+    assert!(matches!(RoadGeometry::default().for_tile_map(&map), Err(MapError::NoLayout)));
+
+    let map: TileMap = RoadGeometry::new(4).tile_map();
+    assert_eq!(RoadGeometry::default().for_tile_map(&map).unwrap(), RoadGeometry::new(4));
+}
@@ -0,0 +1,194 @@
+// Human drivers mixed into autonomous traffic
+
+use rand::rngs::StdRng;
+use std::sync::Arc;
+use rand::{Rng, SeedableRng};
+
+use smart_road::config::{JunctionKind, SimConfig};
+use smart_road::driver::{Autonomy, HumanDriver};
+use smart_road::geometry::RoadGeometry;
+use smart_road::network::RoadNetwork;
+use smart_road::perception::Sensor;
+use smart_road::safety::{Invariant, SafetyMonitor};
+use smart_road::simulation::{Simulation, STEP};
+use smart_road::spatial::{Neighborhood, SpatialHash};
+use smart_road::stats::Stats;
+use smart_road::v2i::Endpoint;
+use smart_road::vehicle::{Direction, Route, Vehicle, VelocityLevel};
+
+#[test]
+fn humans_act_on_what_they_saw_a_reaction_time_ago() {
+    let mut driver = HumanDriver::new(0.5, 1.0);
+    let acted: Vec<f32> = (0..120)
+        .map(|step| {
+            let t = step as f32 * STEP;
+            driver.react(t, if t < 1.0 { 0.0 } else { 100.0 })
+        })
+        .collect();
+
+    let first_move = acted.iter().position(|&speed| speed > 0.0).unwrap() as f32 * STEP;
+    assert!((first_move - 1.5).abs() < 2.0 * STEP, "moved at {}", first_move);
+}
+
+#[test]
+fn copies_of_a_driver_leave_pending_decisions_behind() {
+    let mut driver = HumanDriver::new(0.5, 1.0);
+    driver.react(0.0, 0.0);
+    let mut copy = driver.clone();
+    assert_eq!(driver.react(0.1, 100.0), 0.0);
+    assert_eq!(copy.react(0.1, 100.0), 100.0);
+    assert_eq!((copy.reaction, copy.gap_acceptance), (0.5, 1.0));
+}
+
+#[test]
+fn penetration_sets_the_share_of_human_drivers() {
+    let mut rng = StdRng::seed_from_u64(4);
+    let humans = (0..4000).filter(|_| HumanDriver::at_penetration(&mut rng, 0.7).is_some()).count();
+    assert!((humans as f32 / 4000.0 - 0.3).abs() < 0.03, "{} humans", humans);
+
+    // A fully autonomous fleet leaves the random sequence alone
+    let mut rng = StdRng::seed_from_u64(4);
+    assert!(HumanDriver::at_penetration(&mut rng, 1.0).is_none());
+    assert_eq!(rng.random::<u64>(), StdRng::seed_from_u64(4).random::<u64>());
+}
+
+/// Seconds until a follower closing in on a stopped leader starts braking,
+/// seconds until it starts moving once the leader pulls away, and the
+/// tightest bumper gap on the way in
+fn start_up(driver: Option<HumanDriver>) -> (f32, f32, f32) {
+    let g = Arc::new(RoadGeometry::default());
+    let car = |lane| Vehicle::new(&g, Direction::Up, Route::Straight, 1, lane, JunctionKind::Cross);
+    let lane = g
+        .spawn_lanes(Direction::Up)
+        .into_iter()
+        .find(|&lane| car(lane).path.iter().all(|&(x, _)| x == g.lane_center(lane)))
+        .unwrap();
+    let y = g.pixel_size() as f32 - 100.0;
+    let mut cars = vec![car(lane), car(lane)];
+    // Slow enough to see the follower's lag before it has to brake regardless
+    for (v, offset) in cars.iter_mut().zip([0.0, 250.0]) {
+        v.x = g.lane_center(lane);
+        v.y = y - offset;
+        v.velocity_level = VelocityLevel::Slow;
+        v.speed = 60.0;
+        v.v2i.granted = true; // Following is under test here, not admission
+        v.v2i.grant_until = f32::MAX;
+    }
+    cars[0].driver = driver;
+    cars[1].speed = 0.0;
+
+    let mut grid = SpatialHash::new();
+    let mut closest = f32::MAX;
+    let (mut braked, mut started) = (None, None);
+    for step in 0..600 {
+        let pulls_away = step >= 300;
+        let front = cars.clone();
+        grid.rebuild(&front);
+        cars[0].update(STEP, Neighborhood::new(&front, &grid), &[], &Sensor::perfect());
+        if braked.is_none() && cars[0].speed < front[0].speed {
+            braked = Some(step as f32 * STEP);
+        }
+        if pulls_away {
+            cars[1].speed = 60.0;
+            cars[1].y -= 60.0 * STEP;
+            if started.is_none() && cars[0].speed > 0.0 {
+                started = Some((step - 300) as f32 * STEP);
+            }
+        }
+        closest = closest.min(cars[0].footprint_gap(&cars[1]));
+    }
+    (braked.unwrap(), started.unwrap(), closest)
+}
+
+#[test]
+fn humans_brake_and_start_late_but_stop_in_time() {
+    let (av_brakes, av_starts, gap) = start_up(None);
+    assert!(gap >= 0.0);
+
+    let (human_brakes, human_starts, gap) = start_up(Some(HumanDriver::new(0.8, 1.0)));
+    assert!(gap >= 0.0, "gap {}", gap);
+    assert!(human_brakes - av_brakes > 0.5, "braked: autonomous {} human {}", av_brakes, human_brakes);
+    assert!(human_starts - av_starts > 0.7, "started: autonomous {} human {}", av_starts, human_starts);
+}
+
+#[test]
+fn single_junction_stats_are_split_by_class() {
+    let config = SimConfig { seed: Some(3), av_penetration: 0.5, ..SimConfig::default() };
+    let mut sim = Simulation::new(config);
+    sim.auto_spawn = true;
+    for _ in 0..(120.0 / STEP) as u32 {
+        sim.step(STEP);
+    }
+
+    let [av, human] = sim.stats.by_class;
+    assert_eq!(av.spawned + human.spawned, sim.stats.total_vehicles);
+    assert_eq!(av.trips + human.trips, sim.stats.vehicles_exited);
+    assert!(av.trips > 0 && human.trips > 0, "{:?}", sim.stats.by_class);
+}
+
+/// Busy 2x2 grid with `av_penetration` of the cars autonomous, checking the
+/// reservations after every step
+fn run_mixed(av_penetration: f32, seconds: f32) -> (RoadNetwork, Stats, SafetyMonitor) {
+    let mut network = RoadNetwork::grid(2, 2);
+    network.av_penetration = av_penetration;
+    network.bus.logging = true;
+    let mut rng = StdRng::seed_from_u64(8);
+    let mut stats = Stats::new();
+    let mut safety = SafetyMonitor::new(false);
+
+    let mut since_spawn = 0.0;
+    for step in 1..=(seconds / STEP) as u64 {
+        since_spawn += STEP;
+        if since_spawn > 0.4 {
+            network.spawn_random(&mut rng, &mut stats);
+            since_spawn = 0.0;
+        }
+        network.update(STEP, &mut stats);
+        safety.check_network(step, &network, &mut stats);
+
+        for node in &network.nodes {
+            let held = &node.manager.reservations;
+            if held.iter().any(|r| r.exclusive) {
+                assert_eq!(held.len(), 1, "junction shared with a human driver: {:?}", held);
+            }
+        }
+    }
+    (network, stats, safety)
+}
+
+#[test]
+fn humans_stay_off_the_radio_and_get_the_junction_to_themselves() {
+    let (network, stats, safety) = run_mixed(0.6, 180.0);
+
+    let [av, human] = stats.by_class;
+    assert!(av.trips > 0 && human.trips > 0, "{:?}", stats.by_class);
+    assert_eq!(av.spawned + human.spawned, stats.network_spawned);
+    assert_eq!(av.trips + human.trips, stats.network_trips);
+
+    // Only the autonomous cars ever talk to a junction
+    let talked: Vec<usize> = network
+        .bus
+        .log
+        .iter()
+        .flat_map(|m| [m.from, m.to])
+        .filter_map(|end| match end {
+            Endpoint::Vehicle(id) => Some(id),
+            Endpoint::Junction(_) => None,
+        })
+        .collect();
+    assert!(network.vehicles.values().filter(|v| v.driver.is_some()).all(|v| !talked.contains(&v.id)));
+
+    // Any crossing without a reservation is a human misjudging the gap
+    for v in safety.violations.iter().filter(|v| v.invariant == Invariant::Unauthorized) {
+        assert_eq!(v.involved, vec![Autonomy::Human], "{:#?}", v);
+    }
+    assert_eq!(av.violations, 0, "{:#?}", safety.violations);
+}
+
+#[test]
+fn human_drivers_slow_the_network_down() {
+    let (_, all_av, _) = run_mixed(1.0, 180.0);
+    let (_, mixed, _) = run_mixed(0.5, 180.0);
+    let avg = |s: &Stats| s.network_travel_time / s.network_trips as f32;
+    assert!(avg(&mixed) > avg(&all_av), "{} vs {}", avg(&mixed), avg(&all_av));
+}
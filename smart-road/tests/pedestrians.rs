@@ -0,0 +1,46 @@
+// Pedestrians only step out when approaching cars can still stop
+
+use smart_road::config::JunctionKind;
+use std::sync::Arc;
+
+use smart_road::geometry::RoadGeometry;
+use smart_road::pedestrian::{build_crosswalks, update_pedestrians, Pedestrian, PedestrianState};
+use smart_road::simulation::STEP;
+use smart_road::stats::Stats;
+use smart_road::vehicle::{Direction, Route, Vehicle, VelocityLevel};
+
+/// Northbound car at full speed whose front bumper is `gap` px short of the
+/// south crosswalk, and whether a pedestrian waiting there starts crossing
+fn pedestrian_crosses_with_car_at(gap: f32) -> bool {
+    let g = Arc::new(RoadGeometry::default());
+    let mut crosswalks = build_crosswalks(&g);
+    let at = crosswalks.iter().position(|cw| cw.side == Direction::Down).unwrap();
+
+    let car = |lane| Vehicle::new(&g, Direction::Up, Route::Straight, 1, lane, JunctionKind::Cross);
+    let lane = g
+        .spawn_lanes(Direction::Up)
+        .into_iter()
+        .find(|&lane| car(lane).path.iter().all(|&(x, _)| x == g.lane_center(lane)))
+        .unwrap();
+    let mut v = car(lane);
+    v.set_velocity_level(VelocityLevel::Fast);
+    v.speed = v.target_speed;
+    v.x = g.lane_center(lane);
+    v.y = crosswalks[at].y1 + v.half_length() + gap;
+
+    let mut walker = Pedestrian::new(at, &crosswalks[at], false);
+    walker.state = PedestrianState::Waiting;
+    let mut pedestrians = vec![walker];
+    update_pedestrians(&mut pedestrians, &mut crosswalks, &[v], &mut Stats::new(), STEP);
+    pedestrians[0].state == PedestrianState::Crossing
+}
+
+#[test]
+fn pedestrians_wait_for_cars_too_close_to_stop() {
+    // Fast cars need 81 px to stop: just short of the margin they drive on
+    assert!(!pedestrian_crosses_with_car_at(50.0));
+    assert!(!pedestrian_crosses_with_car_at(5.0));
+
+    // Far enough back to stop, so the pedestrian may go
+    assert!(pedestrian_crosses_with_car_at(300.0));
+}
@@ -0,0 +1,119 @@
+// Sensor range, field of view, occlusion and noise as seen by car-following
+
+use smart_road::config::JunctionKind;
+use std::sync::Arc;
+
+use smart_road::geometry::RoadGeometry;
+use smart_road::perception::{Perception, Sensor};
+use smart_road::simulation::STEP;
+use smart_road::spatial::{Neighborhood, SpatialHash};
+use smart_road::vehicle::{Direction, Route, Vehicle};
+
+/// Northbound cars going straight on in a lane they never leave, `offsets`
+/// pixels ahead (negative: behind) of the first, which is the observer
+fn lane_of_cars(offsets: &[f32]) -> Vec<Vehicle> {
+    let g = Arc::new(RoadGeometry::default());
+    let car = |lane| Vehicle::new(&g, Direction::Up, Route::Straight, 1, lane, JunctionKind::Cross);
+    let lane = g
+        .spawn_lanes(Direction::Up)
+        .into_iter()
+        .find(|&lane| car(lane).path.iter().all(|&(x, _)| x == g.lane_center(lane)))
+        .unwrap();
+    let y = g.pixel_size() as f32 - 100.0;
+    [0.0].iter()
+        .chain(offsets)
+        .map(|offset| {
+            let mut v = car(lane);
+            v.x = g.lane_center(lane);
+            v.y = y - offset;
+            v.speed = 100.0;
+            v.v2i.granted = true; // Following is under test here, not admission
+            v.v2i.grant_until = f32::MAX;
+            v
+        })
+        .collect()
+}
+
+/// Ids of the cars the first one perceives within 200 px
+fn seen(cars: &[Vehicle], model: Perception) -> Vec<usize> {
+    let mut grid = SpatialHash::new();
+    grid.rebuild(cars);
+    let sensor = Sensor { model, seed: 1, step: 1 };
+    let mut ids: Vec<usize> =
+        sensor.perceive(&cars[0], Neighborhood::new(cars, &grid), 200.0).iter().map(|v| v.id).collect();
+    ids.sort();
+    ids
+}
+
+#[test]
+fn perfect_sensor_sees_the_true_state() {
+    let cars = lane_of_cars(&[60.0, 120.0, -60.0]);
+    assert_eq!(seen(&cars, Perception::default()), vec![cars[1].id, cars[2].id, cars[3].id]);
+}
+
+#[test]
+fn range_and_field_of_view_limit_what_is_seen() {
+    let cars = lane_of_cars(&[60.0, 120.0, -60.0]);
+
+    let short = Perception { range: 80.0, ..Perception::default() };
+    assert_eq!(seen(&cars, short), vec![cars[1].id, cars[3].id]);
+
+    let forward = Perception { fov: 120.0, ..Perception::default() };
+    assert_eq!(seen(&cars, forward), vec![cars[1].id, cars[2].id]);
+}
+
+#[test]
+fn occlusion_hides_cars_behind_the_leader() {
+    let cars = lane_of_cars(&[60.0, 120.0]);
+    let occluded = Perception { occlusion: true, ..Perception::default() };
+    assert_eq!(seen(&cars, occluded), vec![cars[1].id]);
+}
+
+#[test]
+fn noise_is_unbiased_and_repeatable() {
+    let cars = lane_of_cars(&[60.0]);
+    let mut grid = SpatialHash::new();
+    grid.rebuild(&cars);
+    let model = Perception { position_noise: 5.0, ..Perception::default() };
+    let error = |step| {
+        let sensor = Sensor { model, seed: 3, step };
+        sensor.perceive(&cars[0], Neighborhood::new(&cars, &grid), 200.0)[0].y - cars[1].y
+    };
+
+    let errors: Vec<f32> = (0..4000).map(error).collect();
+    let mean = errors.iter().sum::<f32>() / errors.len() as f32;
+    let sd = (errors.iter().map(|e| (e - mean).powi(2)).sum::<f32>() / errors.len() as f32).sqrt();
+    assert!(mean.abs() < 0.3, "mean error {}", mean);
+    assert!((sd - 5.0).abs() < 0.3, "error spread {}", sd);
+    assert_eq!(error(17), error(17));
+}
+
+/// Smallest bumper gap while a car at cruise speed closes in on a stopped
+/// one `distance` pixels ahead, seeing it through `model`
+fn gap_behind_stopped_car(distance: f32, model: Perception) -> f32 {
+    let mut cars = lane_of_cars(&[distance]);
+    cars[1].speed = 0.0;
+    let mut grid = SpatialHash::new();
+    let mut closest = f32::MAX;
+
+    for step in 0..300 {
+        let front = cars.clone();
+        grid.rebuild(&front);
+        let sensor = Sensor { model, seed: 2, step };
+        cars[0].update(STEP, Neighborhood::new(&front, &grid), &[], &sensor);
+        closest = closest.min(cars[0].footprint_gap(&cars[1]));
+    }
+    closest
+}
+
+#[test]
+fn followers_stop_behind_what_they_perceive() {
+    assert!(gap_behind_stopped_car(150.0, Perception::default()) >= 0.0);
+
+    let forward = Perception { range: 200.0, fov: 120.0, occlusion: true, ..Perception::default() };
+    assert!(gap_behind_stopped_car(150.0, forward) >= 0.0);
+
+    // Out of range until too late to stop
+    let short = Perception { range: 60.0, ..Perception::default() };
+    assert!(gap_behind_stopped_car(150.0, short) < 0.0);
+}
@@ -0,0 +1,137 @@
+// Headless scenario runs on the standard 3-lane layout
+
+use smart_road::config::JunctionKind;
+use smart_road::scenario::{Scenario, Spawn};
+use smart_road::simulation::{DIRECTIONS, ROUTES};
+use smart_road::vehicle::{exit_side, Direction, Route};
+
+fn lone_vehicle(junction: JunctionKind, dir: Direction, route: Route) -> Scenario {
+    let mut scenario = Scenario::new(1).with_spawn(0.0, Spawn::Vehicle(dir, route));
+    scenario.config.junction = junction;
+    scenario
+}
+
+fn every_movement_leaves_on_its_exit_side(junction: JunctionKind) {
+    for dir in DIRECTIONS {
+        for route in ROUTES.into_iter().chain([Route::UTurn]) {
+            let outcome = lone_vehicle(junction, dir, route).run();
+            outcome.assert_exits(0, exit_side(dir, route));
+            outcome.assert_planned_exit_lanes();
+            outcome.assert_no_violations();
+            assert_eq!(outcome.stats.wrong_exits, 0, "{:?} {:?}", dir, route);
+        }
+    }
+}
+
+#[test]
+fn cross_movements_leave_on_their_exit_side() {
+    every_movement_leaves_on_its_exit_side(JunctionKind::Cross);
+}
+
+#[test]
+fn roundabout_movements_leave_on_their_exit_side() {
+    every_movement_leaves_on_its_exit_side(JunctionKind::Roundabout);
+}
+
+#[test]
+fn vehicles_leave_heading_towards_their_exit_side() {
+    for dir in DIRECTIONS {
+        for route in ROUTES {
+            let outcome = lone_vehicle(JunctionKind::Cross, dir, route).run();
+            let exit = outcome.trip(0).exit.expect("vehicle left the map");
+            assert_eq!(exit.heading, exit_side(dir, route), "{:?} {:?}", dir, route);
+        }
+    }
+}
+
+#[test]
+fn lone_vehicle_is_not_delayed() {
+    let outcome = lone_vehicle(JunctionKind::Cross, Direction::Up, Route::Straight).run();
+    outcome.assert_all_exited();
+    outcome.assert_mean_delay_below(2.0);
+}
+
+#[test]
+fn staggered_cross_traffic_does_not_collide() {
+    let mut scenario = Scenario::new(3);
+    for (i, dir) in DIRECTIONS.into_iter().enumerate() {
+        scenario = scenario.with_spawn(i as f32 * 5.0, Spawn::Vehicle(dir, Route::Straight));
+    }
+    let outcome = scenario.run();
+
+    outcome.assert_all_exited();
+    outcome.assert_no_collisions();
+    for (i, dir) in DIRECTIONS.into_iter().enumerate() {
+        outcome.assert_exits(i, dir);
+    }
+}
+
+#[test]
+fn blocked_entry_spawns_once_clear() {
+    let outcome = Scenario::new(4)
+        .with_spawn(0.0, Spawn::Vehicle(Direction::Left, Route::Straight))
+        .with_spawn(0.0, Spawn::Vehicle(Direction::Left, Route::Straight))
+        .run();
+
+    outcome.assert_all_exited();
+    assert_eq!(outcome.trip(0).spawned_at, 0.0);
+    assert!(outcome.trip(1).spawned_at > 0.0, "second vehicle spawned on top of the first");
+}
+
+#[test]
+fn cyclist_rides_straight_through_on_the_bike_lane() {
+    let mut scenario = Scenario::new(5).with_spawn(0.0, Spawn::Bicycle(Direction::Down));
+    scenario.config.bike_lanes = true;
+    let outcome = scenario.run();
+
+    outcome.assert_exits(0, Direction::Down);
+    outcome.assert_no_violations();
+}
+
+#[test]
+fn passing_a_cyclist_on_the_bike_lane_is_no_close_call() {
+    // The car settles into the curb lane, passes alongside, then turns right
+    // behind the cyclist
+    let mut scenario = Scenario::new(5)
+        .with_spawn(0.0, Spawn::Bicycle(Direction::Up))
+        .with_spawn(3.0, Spawn::Vehicle(Direction::Up, Route::Right));
+    scenario.config.bike_lanes = true;
+    let outcome = scenario.run();
+
+    outcome.assert_all_exited();
+    assert_eq!(outcome.stats.bike_yields, 1);
+    assert_eq!(outcome.stats.bike_close_calls, 0);
+}
+
+#[test]
+fn same_seed_gives_the_same_run() {
+    let mut scenario = Scenario::new(42).with_spawn(1.0, Spawn::Vehicle(Direction::Right, Route::Left));
+    scenario.auto_spawn = true;
+    scenario.config.duration = 30.0;
+
+    let exits = |scenario: &Scenario| {
+        let outcome = scenario.run();
+        let trips: Vec<_> = outcome.trips.iter().map(|t| (t.direction, t.route, t.spawned_at, t.exit)).collect();
+        (trips, outcome.stats.vehicles_exited, outcome.violations.len())
+    };
+    assert_eq!(exits(&scenario), exits(&scenario));
+}
+
+#[test]
+fn exits_are_counted_by_origin_and_destination() {
+    let mut scenario = Scenario::new(6);
+    let movements = [(Direction::Up, Route::Left), (Direction::Left, Route::Straight), (Direction::Down, Route::Right)];
+    for (i, (dir, route)) in movements.into_iter().enumerate() {
+        scenario = scenario.with_spawn(i as f32 * 5.0, Spawn::Vehicle(dir, route));
+    }
+    let outcome = scenario.run();
+    outcome.assert_all_exited();
+
+    let index = |d: Direction| DIRECTIONS.iter().position(|&x| x == d).unwrap();
+    let mut expected = [[0; 4]; 4];
+    for (dir, route) in movements {
+        expected[index(dir)][index(exit_side(dir, route))] += 1;
+    }
+    assert_eq!(outcome.stats.od, expected);
+    assert_eq!(outcome.stats.vehicles_exited, 3);
+}
@@ -0,0 +1,208 @@
+// Junction controllers and vehicles talking over the V2I bus, in a 2x2
+// network and at the single cross junction
+
+use std::collections::BTreeSet;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use smart_road::config::SimConfig;
+use smart_road::manager::movements_conflict;
+use smart_road::network::{latency_sweep, RoadNetwork};
+use smart_road::safety::{Invariant, SafetyMonitor};
+use smart_road::simulation::{Command, Simulation, STEP};
+use smart_road::stats::Stats;
+use smart_road::v2i::{Channel, Endpoint, MessageBus, Payload};
+
+const SPAWN_INTERVAL: f32 = 0.4;
+
+/// Busy 2x2 grid for `seconds`, checking the reservations after every step
+fn run(seconds: f32) -> (RoadNetwork, Stats, SafetyMonitor) {
+    let mut network = RoadNetwork::grid(2, 2);
+    network.bus.logging = true;
+    let mut rng = StdRng::seed_from_u64(9);
+    let mut stats = Stats::new();
+    let mut safety = SafetyMonitor::new(false);
+
+    let mut since_spawn = 0.0;
+    for step in 1..=(seconds / STEP) as u64 {
+        since_spawn += STEP;
+        if since_spawn > SPAWN_INTERVAL {
+            network.spawn_random(&mut rng, &mut stats);
+            since_spawn = 0.0;
+        }
+        network.update(STEP, &mut stats);
+        safety.check_network(step, &network, &mut stats);
+
+        for node in &network.nodes {
+            let held = &node.manager.reservations;
+            for (i, a) in held.iter().enumerate() {
+                for b in &held[i + 1..] {
+                    assert!(!movements_conflict(a.movement, b.movement), "conflicting reservations {:?} {:?}", a, b);
+                }
+            }
+        }
+    }
+    (network, stats, safety)
+}
+
+#[test]
+fn crossings_are_authorized_and_trips_complete() {
+    let (_, stats, safety) = run(120.0);
+    assert!(safety.violations.is_empty(), "{:#?}", safety.violations);
+    assert!(stats.network_trips > 0);
+}
+
+#[test]
+fn grants_only_answer_requests() {
+    let (network, _, _) = run(60.0);
+    let log = &network.bus.log;
+
+    for (i, m) in log.iter().enumerate().filter(|(_, m)| matches!(m.payload, Payload::Grant { .. })) {
+        let asked = log[..i]
+            .iter()
+            .any(|r| matches!(r.payload, Payload::Request(_)) && r.from == m.to && r.to == m.from);
+        assert!(asked, "unrequested grant {:?}", m);
+        assert!(matches!(m.from, Endpoint::Junction(_)));
+    }
+}
+
+#[test]
+fn every_message_kind_is_used() {
+    let (network, _, _) = run(120.0);
+    for (kind, sent) in Payload::KINDS.iter().zip(network.bus.sent) {
+        assert!(sent > 0, "no {} messages", kind);
+    }
+    assert_eq!(network.bus.sent.iter().sum::<u32>() as usize, network.bus.log.len());
+}
+
+#[test]
+fn log_exports_one_row_per_message() {
+    let (network, _, _) = run(20.0);
+    let path = std::env::temp_dir().join(format!("smart-road-v2i-{}.csv", std::process::id()));
+    network.bus.export(&path).unwrap();
+
+    let csv = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).ok();
+    assert_eq!(csv.lines().next(), Some("sent,arrives,from,to,type,detail"));
+    assert_eq!(csv.lines().count(), network.bus.log.len() + 1);
+}
+
+
+/// Default 2x2 demand over a degraded radio link
+fn run_over(channel: Channel, seconds: f32) -> (RoadNetwork, Stats, SafetyMonitor) {
+    let mut network = RoadNetwork::grid(2, 2);
+    network.bus = MessageBus::new(5);
+    network.bus.channel = channel;
+    let mut rng = StdRng::seed_from_u64(5);
+    let mut stats = Stats::new();
+    let mut safety = SafetyMonitor::new(false);
+    network.run((seconds / STEP) as u64, &mut rng, &mut stats, &mut safety);
+    (network, stats, safety)
+}
+
+#[test]
+fn late_grants_hold_vehicles_at_the_stop_line() {
+    let (_, stats, safety) = run_over(Channel { latency: 0.8, jitter: 0.3, loss: 0.0 }, 120.0);
+    assert!(safety.violations.is_empty(), "{:#?}", safety.violations);
+    assert!(stats.network_trips > 0);
+}
+
+#[test]
+fn lost_messages_cost_time_not_safety() {
+    let (network, stats, safety) = run_over(Channel { latency: 0.1, jitter: 0.1, loss: 0.3 }, 120.0);
+    assert!(network.bus.total_lost() > 0);
+    assert!(safety.violations.is_empty(), "{:#?}", safety.violations);
+    assert!(stats.network_trips > 0);
+}
+
+#[test]
+fn throughput_falls_and_waits_grow_with_latency() {
+    let config = SimConfig { duration: 120.0, ..SimConfig::default() };
+    let points = latency_sweep(&config, 2, 2, &[0.0, 1.6], 9);
+    let (fast, slow) = (&points[0], &points[1]);
+    assert!(slow.throughput < fast.throughput, "{:?}", points);
+    assert!(slow.grant_wait > fast.grant_wait, "{:?}", points);
+    assert!(points.iter().all(|p| p.violations == 0), "{:?}", points);
+}
+
+#[test]
+fn the_sweep_keeps_the_configured_fleet_and_links() {
+    // Only junction (0, 0) loses messages, and only radios can lose any
+    let sweep = |penetration: &str| {
+        let args = ["--av-penetration", penetration, "--channel", "0,0=0/0/0.5", "--duration", "60"].map(String::from);
+        latency_sweep(&SimConfig::from_arg_list(&args), 2, 2, &[0.0, 0.4], 9)
+    };
+    assert!(sweep("1").iter().all(|p| p.lost > 0), "{:?}", sweep("1"));
+    assert!(sweep("0").iter().all(|p| p.lost == 0 && p.trips > 0), "{:?}", sweep("0"));
+}
+
+#[test]
+fn junctions_can_have_their_own_channel() {
+    let mut bus = MessageBus::new(0);
+    bus.channel = Channel::parse("100/20").unwrap();
+    bus.channels.insert(2, Channel::parse("400/0/0.5").unwrap());
+
+    let channel = |j| bus.channel_between(Endpoint::Vehicle(7), Endpoint::Junction(j));
+    assert_eq!(channel(1), Channel { latency: 0.1, jitter: 0.02, loss: 0.0 });
+    assert_eq!(channel(2), Channel { latency: 0.4, jitter: 0.0, loss: 0.5 });
+    assert!(Channel::parse("100/20/1").is_err());
+}
+
+/// Single cross junction busy with cars for `seconds` over `channel`,
+/// checking the manager's grants after every step; also the ids of the cars
+/// that crossed
+fn run_single(channel: Channel, seconds: f32) -> (Simulation, BTreeSet<usize>) {
+    let mut sim = Simulation::new(SimConfig { seed: Some(6), channel, ..SimConfig::default() });
+    sim.controller.bus.logging = true;
+
+    let mut crossed = BTreeSet::new();
+    let mut since_spawn = 0.0;
+    for _ in 0..(seconds / STEP) as u32 {
+        since_spawn += STEP;
+        if since_spawn > SPAWN_INTERVAL {
+            sim.apply(Command::SpawnRandom);
+            since_spawn = 0.0;
+        }
+        sim.step(STEP);
+        crossed.extend(sim.vehicles.iter().filter(|v| v.v2i.done).map(|v| v.id));
+        // Human drivers are registered by sight, whatever they cross; grants never conflict
+        let controller = &sim.controller;
+        let granted: Vec<_> = controller.manager.reservations.iter().filter(|r| controller.leases.contains_key(&r.vehicle)).collect();
+        for (i, a) in granted.iter().enumerate() {
+            for b in &granted[i + 1..] {
+                assert!(!movements_conflict(a.movement, b.movement), "conflicting grants {:?} {:?}", a, b);
+            }
+        }
+    }
+    (sim, crossed)
+}
+
+#[test]
+fn the_single_junction_admits_cars_over_the_bus() {
+    let (sim, crossed) = run_single(Channel::default(), 120.0);
+    let unauthorized = sim.safety.violations.iter().filter(|v| v.invariant == Invariant::Unauthorized).count();
+    assert_eq!(unauthorized, 0, "{:#?}", sim.safety.violations);
+    assert!(sim.stats.vehicles_exited > 50, "{} exited", sim.stats.vehicles_exited);
+    assert!(sim.controller.manager.rejected > 0, "nobody ever had to wait");
+
+    // Every car that crossed asked, was granted and let go of the junction
+    let log = &sim.controller.bus.log;
+    let from = |id: usize, kind: &str| log.iter().any(|m| m.from == Endpoint::Vehicle(id) && m.payload.name() == kind);
+    let to = |id: usize, kind: &str| log.iter().any(|m| m.to == Endpoint::Vehicle(id) && m.payload.name() == kind);
+    assert!(!crossed.is_empty());
+    for id in crossed {
+        assert!(from(id, "request") && to(id, "grant") && from(id, "cancel"), "vehicle {}", id);
+    }
+}
+
+#[test]
+fn the_single_junction_waits_out_a_poor_link() {
+    let (ideal, _) = run_single(Channel::default(), 90.0);
+    let (poor, _) = run_single(Channel { latency: 0.4, jitter: 0.1, loss: 0.2 }, 90.0);
+
+    let unauthorized = poor.safety.violations.iter().filter(|v| v.invariant == Invariant::Unauthorized).count();
+    assert_eq!(unauthorized, 0, "{:#?}", poor.safety.violations);
+    assert!(poor.controller.bus.total_lost() > 0);
+    assert!(poor.stats.vehicles_exited < ideal.stats.vehicles_exited, "{} vs {}", poor.stats.vehicles_exited, ideal.stats.vehicles_exited);
+}